@@ -1,13 +1,19 @@
 //! Wrapping seaplane SDK calls with things like CLI specific contexts, errors, etc.
 
 mod formations;
+#[cfg(feature = "compute_api_v2")]
+mod formations_v2;
 mod locks;
 mod metadata;
+mod queue;
 mod restrict;
 
 pub use formations::FormationsReq;
+#[cfg(feature = "compute_api_v2")]
+pub use formations_v2::FormationsV2Req;
 pub use locks::LocksReq;
 pub use metadata::MetadataReq;
+pub use queue::build_queue;
 use reqwest::Url;
 pub use restrict::RestrictReq;
 use seaplane::api::identity::v0::{AccessToken, TokenRequest};
@@ -1,10 +1,13 @@
+use std::{fs::File, path::Path};
+
 use reqwest::Url;
 use seaplane::{
     api::{
         identity::v0::AccessToken,
         metadata::v1::{
-            Key, KeyValue as KeyValueModel, KeyValueRange as KeyValueRangeModel, MetadataRequest,
-            Value as ValueModel,
+            ChunkManifest, Key, KeyValue as KeyValueModel, KeyValueRange as KeyValueRangeModel,
+            MetadataRequest, MetadataRequestBuilder, Value as ValueModel, WatchEvent, Watcher,
+            DEFAULT_CHUNK_SIZE,
         },
         shared::v1::RangeQueryContext,
         ApiErrorKind,
@@ -77,10 +80,10 @@ impl MetadataReq {
         Ok(())
     }
 
-    /// Re-build the inner `MetadataRequest`. This is mostly useful when one wants to point at
-    /// different Metadata than the original request was pointed at. This method will also refresh
-    /// the access token, only if required.
-    fn refresh_inner(&mut self) -> Result<()> {
+    /// A `MetadataRequestBuilder` with the access token and URLs of this request, but which isn't
+    /// pointed at any key or range yet. This method will also refresh the access token, only if
+    /// required.
+    fn builder(&mut self) -> Result<MetadataRequestBuilder> {
         let mut builder = MetadataRequest::builder().token(self.token_or_refresh()?);
 
         #[cfg(feature = "allow_insecure_urls")]
@@ -94,6 +97,14 @@ impl MetadataReq {
         if let Some(url) = &self.metadata_url {
             builder = builder.base_url(url);
         }
+        Ok(builder)
+    }
+
+    /// Re-build the inner `MetadataRequest`. This is mostly useful when one wants to point at
+    /// different Metadata than the original request was pointed at. This method will also refresh
+    /// the access token, only if required.
+    fn refresh_inner(&mut self) -> Result<()> {
+        let mut builder = self.builder()?;
 
         if let Some(key) = &self.key {
             builder = builder.encoded_key(key);
@@ -107,6 +118,18 @@ impl MetadataReq {
         Ok(())
     }
 
+    /// Takes the next snapshot of the target of `watcher` and returns what changed since the last
+    /// one, retrying once with a new access token if the current one has expired.
+    pub fn poll_watcher(&mut self, watcher: &mut Watcher) -> Result<Vec<WatchEvent>> {
+        match watcher.poll(self.builder()?) {
+            Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::Unauthorized => {
+                self.refresh_token()?;
+                Ok(watcher.poll(self.builder()?)?)
+            }
+            res => Ok(res?),
+        }
+    }
+
     /// Retrieves the JWT access token, requesting a new one if required.
     pub fn token_or_refresh(&mut self) -> Result<&str> {
         if self.token.is_none() {
@@ -126,6 +149,24 @@ impl MetadataReq {
         maybe_retry_cloned!(self.put_value(value))
     }
     pub fn delete_value(&mut self) -> Result<()> { maybe_retry!(self.delete_value()) }
+    /// Streams the file at `path` into the store in chunks (the file is re-opened on retry)
+    pub fn put_value_chunked<P: AsRef<Path>>(&mut self, path: P) -> Result<ChunkManifest> {
+        maybe_retry!(self.put_value_chunked(File::open(path.as_ref())?, DEFAULT_CHUNK_SIZE))
+    }
+    /// Reassembles a chunked value into the file at `path` (the file is truncated on retry)
+    pub fn get_value_chunked<P: AsRef<Path>>(&mut self, path: P) -> Result<ChunkManifest> {
+        maybe_retry!(self.get_value_chunked(File::create(path.as_ref())?))
+    }
+    pub fn get_chunk_manifest(&mut self) -> Result<Option<ChunkManifest>> {
+        maybe_retry!(self.get_chunk_manifest())
+    }
+    pub fn delete_value_chunked(&mut self) -> Result<()> {
+        maybe_retry!(self.delete_value_chunked())
+    }
+    pub fn delete_chunks(&mut self, manifest: &ChunkManifest) -> Result<()> {
+        maybe_retry!(self.delete_chunks(manifest))
+    }
+    pub fn cleanup_chunks(&mut self) -> Result<usize> { maybe_retry!(self.cleanup_chunks()) }
     pub fn get_page(&mut self) -> Result<KeyValueRangeModel> { maybe_retry!(self.get_page()) }
     pub fn get_all_pages(&mut self) -> Result<Vec<KeyValueModel>> {
         maybe_retry_cloned!(self.get_all_pages())
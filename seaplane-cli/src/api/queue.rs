@@ -0,0 +1,75 @@
+use seaplane::{
+    api::{
+        locks::v1::LocksRequestBuilder, metadata::v1::MetadataRequestBuilder, shared::v1::Directory,
+    },
+    queue::Queue,
+};
+
+use crate::{api::request_token_jwt, context::Ctx, error::Result};
+
+/// Builds an SDK `Queue` for the tasks under `directory`.
+///
+/// A single access token is requested up front and re-used for every request the queue makes,
+/// which is fine for the handful of requests a single CLI invocation makes.
+pub fn build_queue(ctx: &Ctx, directory: Directory) -> Result<Queue> {
+    #[cfg(feature = "allow_insecure_urls")]
+    let insecure_urls = ctx.insecure_urls;
+    #[cfg(not(feature = "allow_insecure_urls"))]
+    let insecure_urls = false;
+    #[cfg(feature = "allow_invalid_certs")]
+    let invalid_certs = ctx.invalid_certs;
+    #[cfg(not(feature = "allow_invalid_certs"))]
+    let invalid_certs = false;
+
+    let token = request_token_jwt(
+        ctx.args.api_key()?,
+        ctx.identity_url.as_ref(),
+        insecure_urls,
+        invalid_certs,
+    )?;
+
+    let metadata_token = token.clone();
+    let metadata_url = ctx.metadata_url.clone();
+    let metadata = move || {
+        #[cfg_attr(
+            not(any(feature = "allow_insecure_urls", feature = "allow_invalid_certs")),
+            allow(unused_mut)
+        )]
+        let mut builder = MetadataRequestBuilder::new().token(&metadata_token);
+        #[cfg(feature = "allow_insecure_urls")]
+        {
+            builder = builder.allow_http(insecure_urls);
+        }
+        #[cfg(feature = "allow_invalid_certs")]
+        {
+            builder = builder.allow_invalid_certs(invalid_certs);
+        }
+        Ok(match &metadata_url {
+            Some(url) => builder.base_url(url),
+            None => builder,
+        })
+    };
+
+    let locks_url = ctx.locks_url.clone();
+    let locks = move || {
+        #[cfg_attr(
+            not(any(feature = "allow_insecure_urls", feature = "allow_invalid_certs")),
+            allow(unused_mut)
+        )]
+        let mut builder = LocksRequestBuilder::new().token(&token);
+        #[cfg(feature = "allow_insecure_urls")]
+        {
+            builder = builder.allow_http(insecure_urls);
+        }
+        #[cfg(feature = "allow_invalid_certs")]
+        {
+            builder = builder.allow_invalid_certs(invalid_certs);
+        }
+        Ok(match &locks_url {
+            Some(url) => builder.base_url(url),
+            None => builder,
+        })
+    };
+
+    Ok(Queue::new(directory, metadata, locks))
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use reqwest::Url;
 use seaplane::{
     api::{
@@ -151,6 +153,15 @@ impl RestrictReq {
     }
     pub fn delete_restriction(&mut self) -> Result<()> { maybe_retry!(self.delete_restriction()) }
 
+    pub fn wait_until_enforced_with<F: FnMut(&Restriction)>(
+        &mut self,
+        timeout: Duration,
+        poll_interval: Duration,
+        mut on_poll: F,
+    ) -> Result<Restriction> {
+        maybe_retry!(self.wait_until_enforced_with(timeout, poll_interval, &mut on_poll))
+    }
+
     pub fn get_page(&mut self) -> Result<RestrictionRange> { maybe_retry!(self.get_page()) }
     pub fn get_all_pages(&mut self) -> Result<Vec<Restriction>> {
         maybe_retry!(self.get_all_pages())
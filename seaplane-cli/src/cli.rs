@@ -118,12 +118,14 @@ impl Seaplane {
             .arg(arg!(--("stateless") -('S') global)
                 .help("Ignore local state files, do not read from or write to them"))
             .subcommand(SeaplaneAccount::command())
+            .subcommand(SeaplaneFlags::command())
             .subcommand(SeaplaneFlight::command())
             .subcommand(SeaplaneFormation::command())
             .subcommand(SeaplaneInit::command())
             .subcommand(SeaplaneLicense::command())
             .subcommand(SeaplaneMetadata::command())
             .subcommand(SeaplaneLocks::command())
+            .subcommand(SeaplaneQueue::command())
             .subcommand(SeaplaneRestrict::command())
             .subcommand(SeaplaneShellCompletion::command());
 
@@ -216,11 +218,13 @@ impl CliCommand for Seaplane {
     ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
         match matches.subcommand() {
             Some(("account", m)) => Some((Box::new(SeaplaneAccount), m)),
+            Some(("flags", m)) => Some((Box::new(SeaplaneFlags), m)),
             Some(("flight", m)) => Some((Box::new(SeaplaneFlight), m)),
             Some(("formation", m)) => Some((Box::new(SeaplaneFormation), m)),
             Some(("init", m)) => Some((Box::new(SeaplaneInit), m)),
             Some(("metadata", m)) => Some((Box::new(SeaplaneMetadata), m)),
             Some(("locks", m)) => Some((Box::new(SeaplaneLocks), m)),
+            Some(("queue", m)) => Some((Box::new(SeaplaneQueue), m)),
             Some(("restrict", m)) => Some((Box::new(SeaplaneRestrict), m)),
             Some(("shell-completion", m)) => Some((Box::new(SeaplaneShellCompletion), m)),
             Some(("license", m)) => Some((Box::new(SeaplaneLicense), m)),
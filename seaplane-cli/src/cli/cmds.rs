@@ -2,6 +2,7 @@ mod account;
 mod completion;
 #[cfg(feature = "unstable")]
 mod config;
+pub mod flags;
 pub mod flight;
 pub mod formation;
 #[cfg(feature = "unstable")]
@@ -10,17 +11,20 @@ mod init;
 mod license;
 pub mod locks;
 pub mod metadata;
+pub mod queue;
 pub mod restrict;
 
 pub use self::{
     account::SeaplaneAccount,
     completion::SeaplaneShellCompletion,
+    flags::SeaplaneFlags,
     flight::SeaplaneFlight,
     formation::{Provider, Region, SeaplaneFormation},
     init::SeaplaneInit,
     license::SeaplaneLicense,
     locks::SeaplaneLocks,
     metadata::SeaplaneMetadata,
+    queue::SeaplaneQueue,
     restrict::SeaplaneRestrict,
 };
 #[cfg(feature = "unstable")]
@@ -0,0 +1,58 @@
+mod common;
+mod create;
+mod list;
+mod toggle;
+
+use clap::{value_parser, ArgMatches, Command};
+
+pub use self::{
+    common::SeaplaneFlagsCommonArgMatches, create::SeaplaneFlagsCreate, list::SeaplaneFlagsList,
+    toggle::SeaplaneFlagsToggle,
+};
+use crate::{cli::CliCommand, printer::OutputFormat};
+
+static LONG_ABOUT: &str = "Manage feature flags stored in a metadata directory
+
+Each flag is a metadata key under the flags directory (--dir) whose value describes the flag as
+JSON. Flags can be on for everyone, for a percentage of users, or only for an allow-list of
+users, and can be limited to certain regions. Applications evaluate them locally using the
+flags module of the Seaplane SDK.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFlags;
+
+impl SeaplaneFlags {
+    pub fn command() -> Command {
+        Command::new("flags")
+            .about("Manage feature flags stored in a metadata directory")
+            .long_about(LONG_ABOUT)
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .arg(
+                arg!(--format =["FORMAT"=>"table"] global)
+                    .help("Change the output format")
+                    .value_parser(value_parser!(OutputFormat)),
+            )
+            .arg(
+                arg!(--dir =["DIR"=>"flags"] global)
+                    .help("The metadata directory the flags are stored under"),
+            )
+            .subcommand(SeaplaneFlagsList::command())
+            .subcommand(SeaplaneFlagsCreate::command())
+            .subcommand(SeaplaneFlagsToggle::command())
+    }
+}
+
+impl CliCommand for SeaplaneFlags {
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match &matches.subcommand() {
+            Some(("list", m)) => Some((Box::new(SeaplaneFlagsList), m)),
+            Some(("create", m)) => Some((Box::new(SeaplaneFlagsCreate), m)),
+            Some(("toggle", m)) => Some((Box::new(SeaplaneFlagsToggle), m)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use clap::{Arg, ArgMatches};
+
+use crate::cli::validator::validate_flag_name;
+
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
+/// values of arguments were used or not. i.e. `seaplane flags create` may not have the same
+/// arguments as `seaplane account token` even though both produce an `ArgMatches`.
+#[allow(missing_debug_implementations)]
+pub struct SeaplaneFlagsCommonArgMatches<'a>(pub &'a ArgMatches);
+
+pub fn name() -> Arg {
+    arg!(name =["NAME"] required)
+        .help("The name of the flag")
+        .value_parser(validate_flag_name)
+}
@@ -0,0 +1,124 @@
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::{
+    api::ApiErrorKind,
+    error::SeaplaneError,
+    flags::{flag_key, Flag, FlagRule},
+};
+use serde_json::json;
+
+use crate::{
+    api::MetadataReq,
+    cli::{
+        cmds::flags::{common, SeaplaneFlagsCommonArgMatches},
+        specs::region_spec,
+        CliCommand, Region,
+    },
+    context::{Ctx, FlagsCtx},
+    error::{CliErrorKind, Context, Result},
+    printer::{Color, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Create a feature flag
+
+By default the flag is on for everyone. Use --percentage to turn it on for a stable percentage of
+user IDs instead, or --allow to only turn it on for specific user IDs. Either kind of flag can be
+limited to some regions with --region.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFlagsCreate;
+
+impl SeaplaneFlagsCreate {
+    pub fn command() -> Command {
+        Command::new("create")
+            .about("Create a feature flag")
+            .long_about(LONG_ABOUT)
+            .arg(common::name())
+            .arg(
+                arg!(--percentage - ('p') =["PCT"])
+                    .value_parser(value_parser!(u8).range(0..=100))
+                    .help("Turn the flag on for PCT percent of user IDs"),
+            )
+            .arg(
+                arg!(--allow =["USER"]...)
+                    .conflicts_with("percentage")
+                    .help("Only turn the flag on for these user IDs (supports comma separated list, or multiple uses)"),
+            )
+            .arg(
+                arg!(--region|regions =["REGION"]... ignore_case)
+                    .value_parser(value_parser!(Region))
+                    .help("Only turn the flag on in these regions (supports comma separated list, or multiple uses) (See REGION SPEC below)"),
+            )
+            .arg(arg!(--description =["TEXT"]).help("A description of the flag"))
+            .arg(arg!(--disabled).help("Create the flag turned off"))
+            .arg(arg!(--force).help("Overwrite the flag if it already exists"))
+            .after_help(region_spec(false))
+            .after_long_help(region_spec(true))
+    }
+}
+
+impl CliCommand for SeaplaneFlagsCreate {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = MetadataReq::new(ctx)?;
+        let flags_ctx = ctx.flags_ctx.get_or_init();
+        let name = flags_ctx.name.as_deref().unwrap();
+        let flag = flags_ctx.flag.as_ref().unwrap();
+        req.set_key(flag_key(flags_ctx.directory.as_ref().unwrap(), name).encoded())?;
+
+        if !ctx.args.force {
+            match req.get_value() {
+                Ok(_) => {
+                    return Err(CliErrorKind::DuplicateName(name.to_owned())
+                        .into_err()
+                        .context("(hint: you can overwrite the existing flag with '")
+                        .color_context(Color::Green, "--force")
+                        .context("')\n"))
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
+                        if ae.kind == ApiErrorKind::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        req.put_value(flag.to_value()?)?;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", json!({ name: flag })),
+            OutputFormat::Table => cli_println!("Created flag '{name}'"),
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.flags_ctx
+            .init(FlagsCtx::from_flags_common(&SeaplaneFlagsCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.args.force = matches.get_flag("force");
+
+        let rule = if let Some(percentage) = matches.get_one::<u8>("percentage") {
+            FlagRule::Percentage { percentage: *percentage }
+        } else if let Some(users) = matches.get_many::<String>("allow") {
+            FlagRule::AllowList { users: users.cloned().collect() }
+        } else {
+            FlagRule::Boolean
+        };
+        let mut flag = Flag::new(rule);
+        flag.enabled = !matches.get_flag("disabled");
+        let mut regions: Vec<_> = matches
+            .get_many::<Region>("region")
+            .unwrap_or_default()
+            .filter_map(Region::into_model)
+            .collect();
+        regions.sort();
+        regions.dedup();
+        flag.regions = regions;
+        flag.description = matches.get_one::<String>("description").cloned();
+
+        let flags_ctx = ctx.flags_ctx.get_mut().unwrap();
+        flags_ctx.name = matches.get_one::<String>("name").cloned();
+        flags_ctx.flag = Some(flag);
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use clap::{ArgMatches, Command};
+use seaplane::{api::shared::v1::RangeQueryContext, flags::FlagSet};
+
+use crate::{
+    api::MetadataReq,
+    cli::{cmds::flags::SeaplaneFlagsCommonArgMatches, CliCommand},
+    context::{Ctx, FlagsCtx},
+    error::Result,
+    ops::flags::Flags,
+    printer::{Output, OutputFormat},
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFlagsList;
+
+impl SeaplaneFlagsList {
+    pub fn command() -> Command {
+        Command::new("list")
+            .visible_alias("ls")
+            .about("List all feature flags")
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the heading when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFlagsList {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let flags = {
+            let dir = ctx.flags_ctx.get_or_init().directory.clone().unwrap();
+            let mut range = RangeQueryContext::new();
+            range.set_directory(dir.clone());
+            let mut req = MetadataReq::new(ctx)?;
+            req.set_dir(range)?;
+            Flags::new(FlagSet::from_key_values(&dir, req.get_all_pages()?)?)
+        };
+
+        flags.warn_invalid();
+        match ctx.args.out_format {
+            OutputFormat::Json => flags.print_json(ctx)?,
+            OutputFormat::Table => flags.print_table(ctx)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.flags_ctx
+            .init(FlagsCtx::from_flags_common(&SeaplaneFlagsCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let flags_ctx = ctx.flags_ctx.get_mut().unwrap();
+        flags_ctx.no_header = matches.get_flag("no-header");
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use clap::{ArgMatches, Command};
+use seaplane::flags::{flag_key, Flag};
+use serde_json::json;
+
+use crate::{
+    api::MetadataReq,
+    cli::{
+        cmds::flags::{common, SeaplaneFlagsCommonArgMatches},
+        CliCommand,
+    },
+    context::{Ctx, FlagsCtx},
+    error::Result,
+    printer::OutputFormat,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFlagsToggle;
+
+impl SeaplaneFlagsToggle {
+    pub fn command() -> Command {
+        Command::new("toggle")
+            .about("Turn a feature flag on or off")
+            .long_about(
+                "Turn a feature flag on or off
+
+Without --on or --off the flag is turned off if it is currently on, and on if it is currently off.",
+            )
+            .arg(common::name())
+            .arg(arg!(--on).help("Turn the flag on").conflicts_with("off"))
+            .arg(arg!(--off).help("Turn the flag off"))
+    }
+}
+
+impl CliCommand for SeaplaneFlagsToggle {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = MetadataReq::new(ctx)?;
+        let flags_ctx = ctx.flags_ctx.get_or_init();
+        let name = flags_ctx.name.as_deref().unwrap();
+        req.set_key(flag_key(flags_ctx.directory.as_ref().unwrap(), name).encoded())?;
+
+        let mut flag = Flag::from_value(&req.get_value()?)?;
+        flag.enabled = flags_ctx.enable.unwrap_or(!flag.enabled);
+        req.put_value(flag.to_value()?)?;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", json!({ name: flag })),
+            OutputFormat::Table => {
+                cli_println!("Flag '{name}' is now {}", if flag.enabled { "on" } else { "off" })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.flags_ctx
+            .init(FlagsCtx::from_flags_common(&SeaplaneFlagsCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let flags_ctx = ctx.flags_ctx.get_mut().unwrap();
+        flags_ctx.name = matches.get_one::<String>("name").cloned();
+        flags_ctx.enable = match (matches.get_flag("on"), matches.get_flag("off")) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        Ok(())
+    }
+}
@@ -5,12 +5,20 @@ mod configuration;
 mod container_stats;
 mod delete;
 mod fetch;
+mod history;
 mod land;
 mod launch;
 mod list;
 #[cfg(feature = "unstable")]
 mod load_balance;
+#[cfg(feature = "compute_api_v2")]
+mod migrate;
 mod plan;
+mod rollback;
+#[cfg(feature = "unstable")]
+mod rollout;
+#[cfg(feature = "unstable")]
+mod scale;
 mod status;
 #[cfg(feature = "unstable")]
 mod template;
@@ -19,16 +27,20 @@ use clap::{ArgMatches, Command};
 pub use common::{Provider, Region};
 pub use plan::SeaplaneFormationPlanArgMatches;
 
+#[cfg(feature = "compute_api_v2")]
+use self::migrate::SeaplaneFormationMigrate;
 #[cfg(feature = "unstable")]
 use self::{
     configuration::SeaplaneFormationConfiguration,
     container_stats::SeaplaneFormationContainerStatistics,
-    load_balance::SeaplaneFormationLoadBalance, template::SeaplaneFormationTemplate,
+    load_balance::SeaplaneFormationLoadBalance, rollout::SeaplaneFormationRollout,
+    scale::SeaplaneFormationScale, template::SeaplaneFormationTemplate,
 };
 pub use self::{
-    delete::SeaplaneFormationDelete, fetch::SeaplaneFormationFetch, land::SeaplaneFormationLand,
+    delete::SeaplaneFormationDelete, fetch::SeaplaneFormationFetch,
+    history::SeaplaneFormationHistory, land::SeaplaneFormationLand,
     launch::SeaplaneFormationLaunch, list::SeaplaneFormationList, plan::SeaplaneFormationPlan,
-    status::SeaplaneFormationStatus,
+    rollback::SeaplaneFormationRollback, status::SeaplaneFormationStatus,
 };
 use crate::{cli::CliCommand, error::Result, Ctx};
 
@@ -47,9 +59,11 @@ impl SeaplaneFormation {
             .subcommand(SeaplaneFormationPlan::command())
             .subcommand(SeaplaneFormationDelete::command())
             .subcommand(SeaplaneFormationFetch::command())
+            .subcommand(SeaplaneFormationHistory::command())
             .subcommand(SeaplaneFormationLand::command())
             .subcommand(SeaplaneFormationLaunch::command())
             .subcommand(SeaplaneFormationList::command())
+            .subcommand(SeaplaneFormationRollback::command())
             .subcommand(SeaplaneFormationStatus::command());
 
         #[cfg(feature = "unstable")]
@@ -58,9 +72,16 @@ impl SeaplaneFormation {
                 .subcommand(SeaplaneFormationConfiguration::command())
                 .subcommand(SeaplaneFormationContainerStatistics::command())
                 .subcommand(SeaplaneFormationLoadBalance::command())
+                .subcommand(SeaplaneFormationRollout::command())
+                .subcommand(SeaplaneFormationScale::command())
                 .subcommand(SeaplaneFormationTemplate::command())
         }
 
+        #[cfg(feature = "compute_api_v2")]
+        {
+            app = app.subcommand(SeaplaneFormationMigrate::command())
+        }
+
         app
     }
 }
@@ -74,9 +95,11 @@ impl CliCommand for SeaplaneFormation {
             Some(("plan", m)) => Some((Box::new(SeaplaneFormationPlan), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneFormationDelete), m)),
             Some(("fetch-remote", m)) => Some((Box::new(SeaplaneFormationFetch), m)),
+            Some(("history", m)) => Some((Box::new(SeaplaneFormationHistory), m)),
             Some(("land", m)) => Some((Box::new(SeaplaneFormationLand), m)),
             Some(("launch", m)) => Some((Box::new(SeaplaneFormationLaunch), m)),
             Some(("list", m)) => Some((Box::new(SeaplaneFormationList), m)),
+            Some(("rollback", m)) => Some((Box::new(SeaplaneFormationRollback), m)),
             Some(("status", m)) => Some((Box::new(SeaplaneFormationStatus), m)),
             #[cfg(feature = "unstable")]
             Some(("configuration", m)) => Some((Box::new(SeaplaneFormationConfiguration), m)),
@@ -87,7 +110,13 @@ impl CliCommand for SeaplaneFormation {
             #[cfg(feature = "unstable")]
             Some(("load-balance", m)) => Some((Box::new(SeaplaneFormationLoadBalance), m)),
             #[cfg(feature = "unstable")]
+            Some(("rollout", m)) => Some((Box::new(SeaplaneFormationRollout), m)),
+            #[cfg(feature = "unstable")]
+            Some(("scale", m)) => Some((Box::new(SeaplaneFormationScale), m)),
+            #[cfg(feature = "unstable")]
             Some(("template", m)) => Some((Box::new(SeaplaneFormationTemplate), m)),
+            #[cfg(feature = "compute_api_v2")]
+            Some(("migrate", m)) => Some((Box::new(SeaplaneFormationMigrate), m)),
             _ => None,
         }
     }
@@ -4,12 +4,17 @@
 //! The only additional information is the formation name, which is not part of the configuration,
 //! but many commands need as well.
 
+use std::str::FromStr;
+
 use clap::{builder::PossibleValue, value_parser, Arg};
 use seaplane::api::shared::v1::{Provider as ProviderModel, Region as RegionModel};
 
-use crate::cli::validator::{
-    validate_endpoint, validate_formation_name, validate_name_id, validate_name_id_path_inline,
-    validate_public_endpoint,
+use crate::{
+    cli::validator::{
+        validate_endpoint, validate_formation_name, validate_name_id, validate_name_id_path_inline,
+        validate_public_endpoint,
+    },
+    ops::preset::LONG_PRESET,
 };
 
 static LONG_NAME: &str =
@@ -229,147 +234,27 @@ impl<'a> Into<ProviderModel> for &'a Provider {
     }
 }
 
-// TODO: @needs-decision @pre-1.0 we need to come back and address how many aliases there are and
-// their sort order for the CLI's "possible values" message. They're currently "grouped" in region
-// aliases, but for one it's too many values, also it makes the sort look wild in the help message.
-// The Compute API only uses the XA, XC, XE values, but those are the least user friendly.
-/// We provide a shim between the Seaplane Region so we can do some additional UX work
-#[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumString)]
-#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
-pub enum Region {
-    XA,
-    Asia,
-    XC,
-    PRC,
-    PeoplesRepublicOfChina,
-    XE,
-    Europe,
-    EU,
-    XF,
-    Africa,
-    XN,
-    NorthAmerica,
-    NAmerica,
-    XO,
-    Oceania,
-    XQ,
-    Antarctica,
-    XS,
-    SAmerica,
-    SouthAmerica,
-    XU,
-    UK,
-    UnitedKingdom,
-    All,
-}
+/// A region given on the command line, which is either `all` or a region the SDK can parse from its
+/// code, name or one of its aliases (see `RegionModel::from_alias`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region(Option<RegionModel>);
 
-impl clap::ValueEnum for Region {
-    fn value_variants<'a>() -> &'a [Self] {
-        use Region::*;
-        &[
-            XA, // Asia,
-            XC, // PRC, PeoplesRepublicOfChina,
-            XE, // Europe, EU,
-            XF, // Africa,
-            XN, // NorthAmerica, NAmerica,
-            XO, // Oceania,
-            XQ, // Antarctica,
-            XS, // SAmerica, SouthAmerica,
-            XU, // UK, UnitedKingdom,
-            All,
-        ]
-    }
+impl FromStr for Region {
+    type Err = String;
 
-    fn to_possible_value<'a>(&self) -> Option<PossibleValue> {
-        use Region::*;
-        match self {
-            XA => Some(PossibleValue::new("xa").alias("asia")),
-            Asia => None,
-            XC => Some(PossibleValue::new("xc").aliases([
-                "prc",
-                "china",
-                "peoples-republic-of-china",
-                "peoples_republic_of_china",
-                "peoplesrepublicofchina",
-            ])),
-            PRC => None,
-            PeoplesRepublicOfChina => None,
-            XE => Some(PossibleValue::new("xe").aliases(["europe", "eu"])),
-            Europe => None,
-            EU => None,
-            XF => Some(PossibleValue::new("xf").alias("africa")),
-            Africa => None,
-            XN => Some(PossibleValue::new("xn").aliases([
-                "namerica",
-                "northamerica",
-                "n-america",
-                "north-america",
-                "n_america",
-                "north_america",
-            ])),
-            NorthAmerica => None,
-            NAmerica => None,
-            XO => Some(PossibleValue::new("xo").alias("oceania")),
-            Oceania => None,
-            XQ => Some(PossibleValue::new("xq").alias("antarctica")),
-            Antarctica => None,
-            XS => Some(PossibleValue::new("xs").aliases([
-                "samerica",
-                "southamerica",
-                "s-america",
-                "south-america",
-                "s_america",
-                "south_america",
-            ])),
-            SAmerica => None,
-            SouthAmerica => None,
-            XU => Some(PossibleValue::new("xu").aliases([
-                "uk",
-                "unitedkingdom",
-                "united-kingdom",
-                "united_kingdom",
-            ])),
-            UK => None,
-            UnitedKingdom => None,
-            All => Some(PossibleValue::new("all")),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self(None));
         }
-    }
-
-    fn from_str(input: &str, _ignore_case: bool) -> Result<Self, String> {
-        // Because we use strum(ascii_ignore_case) for our FromStr impl we unconditionally ignore
-        // case and can ignore clap's hint
-        input.parse().map_err(|e| format!("{e}"))
+        RegionModel::from_alias(s)
+            .map(|r| Self(Some(r)))
+            .ok_or_else(|| format!("unknown region '{s}' (see REGION SPEC in --help)"))
     }
 }
 
 impl Region {
-    pub fn into_model(&self) -> Option<RegionModel> {
-        if self == &Region::All {
-            None
-        } else {
-            Some(self.into())
-        }
-    }
-}
-
-#[allow(clippy::from_over_into)]
-impl<'a> Into<RegionModel> for &'a Region {
-    fn into(self) -> RegionModel {
-        use Region::*;
-        match self {
-            XA | Asia => RegionModel::XA,
-            XC | PRC | PeoplesRepublicOfChina => RegionModel::XC,
-            XE | Europe | EU => RegionModel::XE,
-            XF | Africa => RegionModel::XF,
-            XN | NorthAmerica | NAmerica => RegionModel::XN,
-            XO | Oceania => RegionModel::XO,
-            XQ | Antarctica => RegionModel::XQ,
-            XS | SAmerica | SouthAmerica => RegionModel::XS,
-            XU | UK | UnitedKingdom => RegionModel::XU,
-            All => panic!("Region::All cannot be converted into seaplane::api::shared::v1::Region"),
-        }
-    }
+    /// The region, or `None` for `all`
+    pub fn into_model(&self) -> Option<RegionModel> { self.0.clone() }
 }
 
 pub fn args() -> Vec<Arg> {
@@ -399,6 +284,9 @@ pub fn args() -> Vec<Arg> {
             .required(true)
             .long_help(LONG_FLIGHT)
             .value_parser(validate_name_id_path_inline),
+        arg!(--preset =["NAME"])
+            .help("A named placement preset to expand into provider and region values")
+            .long_help(LONG_PRESET),
         arg!(--provider|providers =["PROVIDER"=>"all"]... ignore_case)
             .help("A provider that this Formation's Flights are permitted to run on (supports comma separated list, or multiple uses)")
             .long_help(LONG_PROVIDER)
@@ -1,6 +1,19 @@
-use clap::Command;
+mod activate;
+mod add;
+mod common;
+mod deactivate;
+mod get;
+mod list;
+mod remove;
 
-use crate::{cli::CliCommand, error::Result, Ctx};
+use clap::{ArgMatches, Command};
+
+pub use self::{
+    activate::SeaplaneFormationConfigurationActivate, add::SeaplaneFormationConfigurationAdd,
+    deactivate::SeaplaneFormationConfigurationDeactivate, get::SeaplaneFormationConfigurationGet,
+    list::SeaplaneFormationConfigurationList, remove::SeaplaneFormationConfigurationRemove,
+};
+use crate::cli::CliCommand;
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationConfiguration;
@@ -10,11 +23,32 @@ impl SeaplaneFormationConfiguration {
         Command::new("configuration")
             .visible_alias("cfg")
             .about("Operate on Seaplane Formation Configurations")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(SeaplaneFormationConfigurationList::command())
+            .subcommand(SeaplaneFormationConfigurationGet::command())
+            .subcommand(SeaplaneFormationConfigurationAdd::command())
+            .subcommand(SeaplaneFormationConfigurationRemove::command())
+            .subcommand(SeaplaneFormationConfigurationActivate::command())
+            .subcommand(SeaplaneFormationConfigurationDeactivate::command())
     }
 }
 
 impl CliCommand for SeaplaneFormationConfiguration {
-    fn run(&self, _ctx: &mut Ctx) -> Result<()> {
-        todo!("impl SeaplaneFormationConfiguration::run")
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match &matches.subcommand() {
+            Some(("list", m)) => Some((Box::new(SeaplaneFormationConfigurationList), m)),
+            Some(("get", m)) => Some((Box::new(SeaplaneFormationConfigurationGet), m)),
+            Some(("add", m)) => Some((Box::new(SeaplaneFormationConfigurationAdd), m)),
+            Some(("remove", m)) => Some((Box::new(SeaplaneFormationConfigurationRemove), m)),
+            Some(("activate", m)) => Some((Box::new(SeaplaneFormationConfigurationActivate), m)),
+            Some(("deactivate", m)) => {
+                Some((Box::new(SeaplaneFormationConfigurationDeactivate), m))
+            }
+            _ => None,
+        }
     }
 }
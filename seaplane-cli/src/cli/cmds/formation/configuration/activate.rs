@@ -0,0 +1,79 @@
+use clap::{ArgMatches, Command};
+
+use super::common;
+use crate::{
+    api::FormationsReq, cli::CliCommand, error::Result, ops::formation::TrafficWeights, Ctx,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationActivate;
+
+impl SeaplaneFormationConfigurationActivate {
+    pub fn command() -> Command {
+        Command::new("activate")
+            .about("Make a configuration of a remote Formation Instance active, so it receives traffic")
+            .long_about("Make a configuration of a remote Formation Instance active, so it receives traffic
+
+The configuration gets the average traffic weight of the already active configurations, which can be
+changed afterwards with 'seaplane formation load-balance'.")
+            .arg(common::formation())
+            .arg(common::configuration())
+            .arg(common::force())
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationActivate {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let cfg = ctx
+            .formation_ctx
+            .get_or_init()
+            .configuration
+            .clone()
+            .unwrap();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let uuid = common::resolve_uuid(ctx, &req.list_configuration_ids()?, &cfg)?;
+        let mut weights = TrafficWeights::from(&req.get_active_configurations()?);
+        if weights.contains(uuid) {
+            cli_print!("Configuration ");
+            cli_print!(@Green, "{uuid}");
+            cli_println!(" is already active");
+            return Ok(());
+        }
+        weights.activate(uuid);
+        req.set_active_configurations(&weights.to_model()?, ctx.args.force)?;
+
+        if let Some(id) = ctx
+            .db
+            .formations
+            .get_configuration_by_uuid(uuid)
+            .map(|c| c.id)
+        {
+            if let Some(f) = ctx
+                .db
+                .formations
+                .formation_index_of_name(&name)
+                .and_then(|idx| ctx.db.formations.get_formation_mut(idx))
+            {
+                f.grounded.remove(&id);
+                f.in_air.insert(id);
+            }
+            ctx.persist_formations()?;
+        }
+
+        cli_print!("Successfully activated configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_print!(" of remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("'");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        common::update_ctx(matches, ctx);
+        ctx.args.force = matches.get_flag("force");
+        Ok(())
+    }
+}
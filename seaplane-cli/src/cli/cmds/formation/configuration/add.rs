@@ -0,0 +1,96 @@
+use clap::{ArgMatches, Command};
+
+use super::common;
+use crate::{
+    api::FormationsReq,
+    cli::{errors, CliCommand},
+    error::{Context, Result},
+    printer::Color,
+    Ctx,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationAdd;
+
+impl SeaplaneFormationConfigurationAdd {
+    pub fn command() -> Command {
+        Command::new("add")
+            .about("Upload a local configuration of a Formation Plan to its remote Formation Instance")
+            .arg(common::formation())
+            .arg(
+                arg!(configuration =["ID"] required)
+                    .help("The local ID (or a unique prefix of it) of a configuration which hasn't been uploaded yet"),
+            )
+            .arg(
+                arg!(--active)
+                    .help("Also make the configuration active, so it starts receiving traffic"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationAdd {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let formation_ctx = ctx.formation_ctx.get_or_init();
+        let cfg = formation_ctx.configuration.clone().unwrap();
+        let active = formation_ctx.launch;
+
+        let ids: Vec<_> = ctx
+            .db
+            .formations
+            .formation_index_of_name(&name)
+            .and_then(|idx| ctx.db.formations.get_formation(idx))
+            .map(|f| f.local_only_configs())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|id| id.to_string().starts_with(&cfg))
+            .collect();
+        let id = match ids.as_slice() {
+            [id] => *id,
+            [] => {
+                return errors::no_matching_item(cfg, false, false)
+                    .context("(hint: list the configurations which haven't been uploaded with '")
+                    .with_color_context(|| {
+                        (Color::Green, format!("seaplane formation configuration list {name}"))
+                    })
+                    .context("')\n");
+            }
+            _ => return errors::ambiguous_item(cfg, false),
+        };
+        // re unwrap: the ID came from the Formation so the configuration has to exist
+        let model = ctx
+            .db
+            .formations
+            .get_configuration(&id)
+            .unwrap()
+            .model
+            .clone();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let uuid = req.add_configuration(&model, active)?;
+
+        ctx.db.formations.add_uuid(&id, uuid);
+        if active {
+            ctx.db.formations.add_in_air_by_name(&name, id);
+        } else {
+            ctx.db.formations.add_grounded_by_name(&name, id);
+        }
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully added configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_print!(" (local ID ");
+        cli_print!(@Green, "{}", &id.to_string()[..8]);
+        cli_print!(") to remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("'{}", if active { " as active" } else { "" });
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        common::update_ctx(matches, ctx);
+        ctx.formation_ctx.get_mut_or_init().launch = matches.get_flag("active");
+        Ok(())
+    }
+}
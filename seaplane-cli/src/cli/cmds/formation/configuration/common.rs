@@ -0,0 +1,60 @@
+use clap::{Arg, ArgMatches};
+use uuid::Uuid;
+
+use crate::{
+    cli::validator::validate_formation_name,
+    context::Ctx,
+    error::{CliErrorKind, Result},
+    ops::formation::uuid_of_prefix,
+};
+
+pub fn formation() -> Arg {
+    arg!(formation =["NAME"] required)
+        .value_parser(validate_formation_name)
+        .help("The name of the remote Formation Instance")
+}
+
+pub fn configuration() -> Arg {
+    arg!(configuration =["UUID|ID"] required)
+        .help("The UUID of the configuration, or the local ID it was uploaded from (a unique prefix of either is enough)")
+}
+
+pub fn force() -> Arg { arg!(--force - ('f')).help("Skip the safety checks of the Compute API") }
+
+/// Sets the Formation and configuration from the common arguments
+pub fn update_ctx(matches: &ArgMatches, ctx: &mut Ctx) {
+    ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+    ctx.formation_ctx.get_mut_or_init().configuration =
+        matches.get_one::<String>("configuration").cloned();
+}
+
+/// Returns the single UUID out of `remote` which either starts with `cfg`, or belongs to the only
+/// local configuration whose ID starts with `cfg`
+pub fn resolve_uuid(ctx: &Ctx, remote: &[Uuid], cfg: &str) -> Result<Uuid> {
+    match uuid_of_prefix(remote, cfg) {
+        Err(e) if matches!(e.kind(), CliErrorKind::NoMatchingItem(_)) => {
+            let local: Vec<Uuid> = ctx
+                .db
+                .formations
+                .configurations()
+                .filter(|c| c.id.to_string().starts_with(cfg))
+                .filter_map(|c| c.remote_id)
+                .filter(|uuid| remote.contains(uuid))
+                .collect();
+            match local.as_slice() {
+                [uuid] => Ok(*uuid),
+                [] => Err(e),
+                _ => Err(CliErrorKind::AmbiguousItem(cfg.to_owned()).into_err()),
+            }
+        }
+        res => res,
+    }
+}
+
+/// Returns the first 8 characters of the local ID of the configuration uploaded as `uuid`, if any
+pub fn local_id_of(ctx: &Ctx, uuid: Uuid) -> Option<String> {
+    ctx.db
+        .formations
+        .get_configuration_by_uuid(uuid)
+        .map(|c| c.id.to_string()[..8].to_owned())
+}
@@ -0,0 +1,81 @@
+use clap::{ArgMatches, Command};
+
+use super::common;
+use crate::{
+    api::FormationsReq,
+    cli::CliCommand,
+    error::{CliErrorKind, Context, Result},
+    ops::formation::TrafficWeights,
+    printer::Color,
+    Ctx,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationDeactivate;
+
+impl SeaplaneFormationConfigurationDeactivate {
+    pub fn command() -> Command {
+        Command::new("deactivate")
+            .about("Stop sending traffic to a configuration of a remote Formation Instance, keeping it uploaded")
+            .arg(common::formation())
+            .arg(common::configuration())
+            .arg(common::force())
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationDeactivate {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let cfg = ctx
+            .formation_ctx
+            .get_or_init()
+            .configuration
+            .clone()
+            .unwrap();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let uuid = common::resolve_uuid(ctx, &req.list_configuration_ids()?, &cfg)?;
+        let mut weights = TrafficWeights::from(&req.get_active_configurations()?);
+        if !weights.contains(uuid) {
+            cli_print!("Configuration ");
+            cli_print!(@Green, "{uuid}");
+            cli_println!(" is already inactive");
+            return Ok(());
+        }
+        weights.deactivate(uuid);
+        if weights.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "configuration {uuid} is the only active configuration of remote Formation Instance '{name}'"
+            ))
+            .into_err()
+            .context("(hint: land the whole Formation Instance with '")
+            .with_color_context(|| (Color::Green, format!("seaplane formation land {name}")))
+            .context("')\n"));
+        }
+        req.set_active_configurations(&weights.to_model()?, ctx.args.force)?;
+
+        if let Some(id) = ctx
+            .db
+            .formations
+            .get_configuration_by_uuid(uuid)
+            .map(|c| c.id)
+        {
+            ctx.db.formations.add_grounded_by_name(&name, id);
+            ctx.persist_formations()?;
+        }
+
+        cli_print!("Successfully deactivated configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_print!(" of remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("'");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        common::update_ctx(matches, ctx);
+        ctx.args.force = matches.get_flag("force");
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::Write;
+
+use clap::{value_parser, ArgMatches, Command};
+use tabwriter::TabWriter;
+
+use super::common;
+use crate::{
+    api::FormationsReq,
+    cli::CliCommand,
+    error::{CliError, Result},
+    printer::printer,
+    Ctx, OutputFormat,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationGet;
+
+impl SeaplaneFormationConfigurationGet {
+    pub fn command() -> Command {
+        Command::new("get")
+            .visible_alias("show")
+            .about("Show a single configuration of a remote Formation Instance")
+            .arg(common::formation())
+            .arg(common::configuration())
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationGet {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let cfg = ctx
+            .formation_ctx
+            .get_or_init()
+            .configuration
+            .clone()
+            .unwrap();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let uuid = common::resolve_uuid(ctx, &req.list_configuration_ids()?, &cfg)?;
+        let model = req.get_configuration(uuid)?;
+
+        if ctx.args.out_format == OutputFormat::Json {
+            cli_println!("{}", serde_json::to_string(&model)?);
+            return Ok(());
+        }
+
+        cli_print!("Configuration ");
+        cli_print!(@Green, "{uuid}");
+        if let Some(id) = common::local_id_of(ctx, uuid) {
+            cli_print!(" (local ID ");
+            cli_print!(@Green, "{id}");
+            cli_print!(")");
+        }
+        cli_println!(" of Formation Instance '{name}':");
+
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(tw, "FLIGHT\tIMAGE\tMIN\tMAX")?;
+        for flight in model.flights() {
+            writeln!(
+                tw,
+                "{}\t{}\t{}\t{}",
+                flight.name(),
+                flight.image_str(),
+                flight.minimum(),
+                flight
+                    .maximum()
+                    .map(|n| format!("{n}"))
+                    .unwrap_or_else(|| "INF".into()),
+            )?;
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing configuration"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+        drop(ptr);
+
+        for (key, value) in model.public_endpoints() {
+            cli_println!("Public endpoint {key} => {value}");
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        common::update_ctx(matches, ctx);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use clap::{value_parser, ArgMatches, Command};
+use serde::Serialize;
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use super::common;
+use crate::{
+    api::FormationsReq,
+    cli::CliCommand,
+    error::{CliError, Result},
+    ops::formation::TrafficWeights,
+    printer::printer,
+    Ctx, OutputFormat,
+};
+
+#[derive(Debug, Serialize)]
+struct ConfigurationRow {
+    local_id: Option<String>,
+    uuid: Option<Uuid>,
+    status: &'static str,
+    traffic_weight: Option<f32>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationList;
+
+impl SeaplaneFormationConfigurationList {
+    pub fn command() -> Command {
+        Command::new("list")
+            .visible_alias("ls")
+            .about("List the configurations of a remote Formation Instance, and any local configurations of its Plan which haven't been uploaded")
+            .arg(common::formation())
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationList {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let active = TrafficWeights::from(&req.get_active_configurations()?);
+
+        let mut rows: Vec<_> = req
+            .list_configuration_ids()?
+            .into_iter()
+            .map(|uuid| {
+                let weight = active
+                    .iter()
+                    .find(|tw| tw.configuration_id == uuid)
+                    .map(|tw| tw.traffic_weight);
+                ConfigurationRow {
+                    local_id: common::local_id_of(ctx, uuid),
+                    uuid: Some(uuid),
+                    status: if weight.is_some() { "active" } else { "inactive" },
+                    traffic_weight: weight,
+                }
+            })
+            .collect();
+        if let Some(formation) = ctx
+            .db
+            .formations
+            .formation_index_of_name(&name)
+            .and_then(|idx| ctx.db.formations.get_formation(idx))
+        {
+            rows.extend(
+                formation
+                    .local_only_configs()
+                    .into_iter()
+                    .map(|id| ConfigurationRow {
+                        local_id: Some(id.to_string()[..8].to_owned()),
+                        uuid: None,
+                        status: "local",
+                        traffic_weight: None,
+                    }),
+            );
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", serde_json::to_string(&rows)?),
+            OutputFormat::Table => print_table(&rows)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
+
+fn print_table(rows: &[ConfigurationRow]) -> Result<()> {
+    let mut tw = TabWriter::new(Vec::new());
+    writeln!(tw, "LOCAL ID\tUUID\tSTATUS\tTRAFFIC WEIGHT")?;
+    for row in rows {
+        writeln!(
+            tw,
+            "{}\t{}\t{}\t{}",
+            row.local_id.as_deref().unwrap_or("-"),
+            row.uuid.map(|u| u.to_string()).as_deref().unwrap_or("-"),
+            row.status,
+            row.traffic_weight
+                .map(|w| w.to_string())
+                .as_deref()
+                .unwrap_or("-"),
+        )?;
+    }
+    tw.flush()?;
+
+    let mut ptr = printer();
+    let page = tw
+        .into_inner()
+        .map_err(|_| CliError::bail("IO flush error writing configurations"))?;
+    ptr.write_all(&page)?;
+    ptr.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use clap::{ArgMatches, Command};
+
+use super::common;
+use crate::{api::FormationsReq, cli::CliCommand, error::Result, Ctx};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationConfigurationRemove;
+
+impl SeaplaneFormationConfigurationRemove {
+    pub fn command() -> Command {
+        Command::new("remove")
+            .visible_aliases(["rm", "delete"])
+            .about("Remove a configuration from a remote Formation Instance, keeping it in the local Formation Plan")
+            .arg(common::formation())
+            .arg(common::configuration())
+            .arg(common::force())
+    }
+}
+
+impl CliCommand for SeaplaneFormationConfigurationRemove {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let cfg = ctx
+            .formation_ctx
+            .get_or_init()
+            .configuration
+            .clone()
+            .unwrap();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let uuid = common::resolve_uuid(ctx, &req.list_configuration_ids()?, &cfg)?;
+        req.remove_configuration(uuid, ctx.args.force)?;
+
+        ctx.db.formations.remove_uuid_by_name(&name, uuid);
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully removed configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_print!(" from remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("'");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        common::update_ctx(matches, ctx);
+        ctx.args.force = matches.get_flag("force");
+        Ok(())
+    }
+}
@@ -1,6 +1,23 @@
-use clap::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{cli::CliCommand, error::Result, Ctx};
+use clap::{value_parser, ArgMatches, Command};
+
+use crate::{
+    api::FormationsReq,
+    cli::{validator::validate_formation_name, CliCommand},
+    error::Result,
+    ops::formation::{ContainerGroupBy, ContainerStats},
+    printer::Output,
+    Ctx, OutputFormat,
+};
+
+static LONG_ABOUT: &str = "Display statistics about the underlying physical container instances
+
+Without --group-by one row is shown per container instance of the remote Formation Instance.
+
+With --group-by the container instances are aggregated by their Flight, configuration, region,
+provider or country. For each usage field, plus the uptime of the containers, this shows how many
+containers reported it along with the sum and the 50th, 90th and 99th percentiles.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationContainerStatistics;
@@ -10,11 +27,49 @@ impl SeaplaneFormationContainerStatistics {
         Command::new("container-statistics")
             .visible_aliases(["container-stats", "statistics", "stats"])
             .about("Display statistics about the underlying physical container instances")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(
+                arg!(--("group-by") = ["KEY"])
+                    .value_parser(value_parser!(ContainerGroupBy))
+                    .help("Aggregate the container instances by this key"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
     }
 }
 
 impl CliCommand for SeaplaneFormationContainerStatistics {
-    fn run(&self, _ctx: &mut Ctx) -> Result<()> {
-        todo!("impl SeaplaneFormationContainerStatistics::run")
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        let stats = ContainerStats::new(req.get_containers()?, now);
+
+        let out: Box<dyn Output> = match ctx.formation_ctx.get_or_init().group_by {
+            Some(by) => Box::new(stats.group_by(by)),
+            None => Box::new(stats),
+        };
+        match ctx.args.out_format {
+            OutputFormat::Json => out.print_json(ctx),
+            OutputFormat::Table => out.print_table(ctx),
+        }
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.formation_ctx.get_mut_or_init().group_by = matches.get_one("group-by").copied();
+        Ok(())
     }
 }
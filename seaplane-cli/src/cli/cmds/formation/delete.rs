@@ -1,6 +1,8 @@
 use clap::{ArgMatches, Command};
 use seaplane::{api::ApiErrorKind, error::SeaplaneError};
 
+#[cfg(feature = "compute_api_v2")]
+use crate::api::FormationsV2Req;
 use crate::{
     api::FormationsReq,
     cli::{
@@ -11,6 +13,7 @@ use crate::{
     },
     context::Ctx,
     error::{CliErrorKind, Context, Result},
+    ops::formation::ComputeApiVersion,
     printer::Color,
 };
 
@@ -106,9 +109,33 @@ impl CliCommand for SeaplaneFormationDelete {
         // the local one too if this fails
         if formation_ctx.remote {
             let mut req = FormationsReq::new_delay_token(ctx)?;
+            #[cfg(feature = "compute_api_v2")]
+            let mut req_v2 = FormationsV2Req::new_delay_token(ctx)?;
             for idx in &indices {
                 let formation = ctx.db.formations.get_formation(*idx).unwrap();
                 if let Some(name) = &formation.name {
+                    if formation.api_version == ComputeApiVersion::V2 {
+                        formation.api_version.ensure_supported(name)?;
+                        #[cfg(feature = "compute_api_v2")]
+                        {
+                            req_v2.set_name(name)?;
+                            if let Err(e) = req_v2.delete() {
+                                if matches!(
+                                    e.kind(),
+                                    CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
+                                    if ae.kind == ApiErrorKind::NotFound)
+                                {
+                                    continue;
+                                }
+                                return Err(e);
+                            }
+                            cli_print!("Deleted remote Formation Instance '");
+                            cli_print!(@Green, "{}", name);
+                            cli_println!("'");
+                            continue;
+                        }
+                    }
+
                     req.set_name(name)?;
                     let cfg_uuids = match req.delete(ctx.args.force) {
                         Err(e) => {
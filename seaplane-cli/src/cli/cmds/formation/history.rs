@@ -0,0 +1,90 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::v1::ActiveConfigurations;
+
+use crate::{
+    cli::{validator::validate_formation_name, CliCommand},
+    error::Result,
+    ops::formation::DeploymentHistory,
+    printer::Output,
+    Ctx, OutputFormat,
+};
+
+static LONG_ABOUT: &str = "Show the local deployment log of a remote Formation Instance
+
+Each time launch, land, load-balance, rollout, scale or rollback changes the active configurations
+of a Formation Instance the CLI records the time, the command, and the configurations which were
+left active along with their traffic weights and the images of their Flights. Deployments are
+numbered from 1 for the oldest, and the number can be given to 'seaplane formation rollback --to'.
+
+The log is kept locally, so changes made by other machines or through the API directly are not
+included.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationHistory;
+
+impl SeaplaneFormationHistory {
+    pub fn command() -> Command {
+        Command::new("history")
+            .about("Show the local deployment log of a remote Formation Instance")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationHistory {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.as_deref().unwrap();
+        let history =
+            DeploymentHistory { deployments: ctx.db.formations.deployments(name), now: now() };
+        if history.deployments.is_empty() && ctx.args.out_format == OutputFormat::Table {
+            cli_print!("No deployments of remote Formation Instance '");
+            cli_print!(@Green, "{name}");
+            cli_println!("' were recorded");
+            return Ok(());
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => history.print_json(ctx),
+            OutputFormat::Table => history.print_table(ctx),
+        }
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
+
+/// The current time in seconds since the UNIX epoch
+pub(super) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Adds the `active` configurations set by `command` to the deployment log of the remote Formation
+/// Instance `name`
+pub(super) fn record(
+    ctx: &mut Ctx,
+    name: &str,
+    command: impl Into<String>,
+    active: &ActiveConfigurations,
+) -> Result<()> {
+    ctx.db
+        .formations
+        .record_deployment(name, command.into(), active, now());
+    ctx.persist_formations()
+}
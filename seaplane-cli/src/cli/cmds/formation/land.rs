@@ -1,5 +1,12 @@
+#[cfg(feature = "unstable")]
+use std::{thread, time::Duration};
+
 use clap::{ArgMatches, Command};
+use seaplane::api::compute::v1::ActiveConfigurations;
+#[cfg(feature = "unstable")]
+use uuid::Uuid;
 
+use super::history::record;
 use crate::{
     api::FormationsReq,
     cli::{
@@ -11,6 +18,23 @@ use crate::{
     error::Result,
     Ctx,
 };
+#[cfg(feature = "unstable")]
+use crate::{
+    cli::validator::validate_duration,
+    error::{CliErrorKind, Context},
+    ops::formation::{uuid_of_prefix, TrafficWeights},
+    printer::{Color, Pb},
+};
+
+#[cfg(feature = "unstable")]
+static LONG_DRAIN: &str =
+    "Move traffic away from the configurations being landed and wait before deactivating them, instead of stopping right away
+
+The configurations to land are given with --configuration. Without it, every active configuration
+except the one receiving the most traffic is landed, so all traffic ends up on that one.
+
+The other active configurations take over the traffic in proportion to their current weight. If no
+other active configuration is left to take over, nothing is landed and the command fails.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationLand;
@@ -18,7 +42,8 @@ pub struct SeaplaneFormationLand;
 impl SeaplaneFormationLand {
     pub fn command() -> Command {
         let validator = |s: &str| validate_name_id(validate_formation_name, s);
-        Command::new("land")
+        #[cfg_attr(not(feature = "unstable"), allow(unused_mut))]
+        let mut app = Command::new("land")
             .visible_alias("stop")
             .about("Land (Stop) all configurations of a remote Formation Instance")
             .arg(
@@ -32,7 +57,31 @@ impl SeaplaneFormationLand {
             )
             .arg(arg!(--fetch|sync|synchronize - ('F')).help(
                 "Fetch remote Formation Instances and synchronize local Plan definitions prior to attempting to land",
-            ))
+            ));
+
+        #[cfg(feature = "unstable")]
+        {
+            app = app
+                .arg(
+                    arg!(--drain)
+                        .help("Move traffic away from the configurations being landed and wait before deactivating them, instead of stopping right away")
+                        .long_help(LONG_DRAIN),
+                )
+                .arg(
+                    arg!(--over =["DURATION"=>"2m"])
+                        .requires("drain")
+                        .value_parser(validate_duration)
+                        .help("How long to let traffic drain with --drain, such as '30s' or '2m'"),
+                )
+                .arg(
+                    arg!(--configuration =["UUID"]...)
+                        .requires("drain")
+                        .conflicts_with("all")
+                        .help("The UUID (or an unambiguous prefix of it) of an active configuration to land with --drain (supports COMMA separated list, or multiple uses)"),
+                );
+        }
+
+        app
     }
 }
 
@@ -45,12 +94,12 @@ impl CliCommand for SeaplaneFormationLand {
             ctx.internal_run = false;
             ctx.args.name_id = old_name;
         }
-        let name = ctx.args.name_id.as_ref().unwrap();
+        let name = ctx.args.name_id.clone().unwrap();
         // Get the indices of any formations that match the given name/ID
         let indices = if ctx.args.all {
-            ctx.db.formations.formation_indices_of_left_matches(name)
+            ctx.db.formations.formation_indices_of_left_matches(&name)
         } else {
-            ctx.db.formations.formation_indices_of_matches(name)
+            ctx.db.formations.formation_indices_of_matches(&name)
         };
 
         match indices.len() {
@@ -65,11 +114,49 @@ impl CliCommand for SeaplaneFormationLand {
 
         let mut req = FormationsReq::new_delay_token(ctx)?;
         for idx in indices {
+            #[cfg(feature = "unstable")]
+            {
+                if let Some(over) = ctx.formation_ctx.get_or_init().drain {
+                    // re unwrap: the indices returned came from Formations so they have to be valid
+                    let formation = ctx.db.formations.get_formation(idx).unwrap();
+                    let formation_name = formation.name.clone().unwrap();
+                    req.set_name(&formation_name)?;
+                    let landed = drain(ctx, &mut req, &formation_name, over)?;
+                    if landed.is_empty() {
+                        continue;
+                    }
+
+                    // Only the landed configurations move from in air to grounded
+                    let ids: Vec<_> = landed
+                        .iter()
+                        .filter_map(|uuid| ctx.db.formations.get_configuration_by_uuid(*uuid))
+                        .map(|cfg| cfg.id)
+                        .collect();
+                    let formation = ctx.db.formations.get_formation_mut(idx).unwrap();
+                    for id in ids {
+                        if formation.in_air.remove(&id) {
+                            formation.grounded.insert(id);
+                        }
+                    }
+                    ctx.persist_formations()?;
+
+                    for uuid in landed {
+                        cli_print!("Successfully Landed configuration ");
+                        cli_print!(@Green, "{uuid}");
+                        cli_print!(" of remote Formation Instance '");
+                        cli_print!(@Green, "{formation_name}");
+                        cli_println!("'");
+                    }
+                    continue;
+                }
+            }
+
             // re unwrap: the indices returned came from Formations so they have to be valid
             let formation = ctx.db.formations.get_formation_mut(idx).unwrap();
-            req.set_name(formation.name.as_ref().unwrap())?;
-
             // re unwrap: We got the formation from the local DB so it has to have a name
+            let formation_name = formation.name.clone().unwrap();
+            req.set_name(&formation_name)?;
+
             req.stop()?;
 
             // Move all configurations from in air to grounded
@@ -78,7 +165,7 @@ impl CliCommand for SeaplaneFormationLand {
                 formation.grounded.insert(id);
             }
 
-            ctx.persist_formations()?;
+            record(ctx, &formation_name, "land", &ActiveConfigurations::new())?;
 
             cli_print!("Successfully Landed remote Formation Instance '");
             cli_print!(@Green, "{}", &name);
@@ -92,6 +179,82 @@ impl CliCommand for SeaplaneFormationLand {
         ctx.args.all = matches.get_flag("all");
         ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
         ctx.args.fetch = matches.get_flag("fetch");
+        #[cfg(feature = "unstable")]
+        {
+            let fctx = ctx.formation_ctx.get_mut_or_init();
+            fctx.drain = if matches.get_flag("drain") {
+                matches.get_one::<Duration>("over").copied()
+            } else {
+                None
+            };
+            fctx.drain_configurations = matches
+                .get_many::<String>("configuration")
+                .unwrap_or_default()
+                .cloned()
+                .collect();
+        }
         Ok(())
     }
 }
+
+/// Moves all traffic of the remote Formation Instance `name` away from the configurations being
+/// landed, waits for `over`, and only then deactivates them. Without any `--configuration` all but
+/// the active configuration receiving the most traffic are landed. Nothing is changed (and an error
+/// returned) if no other active configuration could take over the traffic.
+///
+/// Returns the UUIDs of the deactivated configurations.
+#[cfg(feature = "unstable")]
+fn drain(ctx: &mut Ctx, req: &mut FormationsReq, name: &str, over: Duration) -> Result<Vec<Uuid>> {
+    let before = TrafficWeights::from(&req.get_active_configurations()?);
+    let cfgs = &ctx.formation_ctx.get_or_init().drain_configurations;
+    let landed: Vec<Uuid> = if cfgs.is_empty() {
+        // Keep only the configuration receiving the most traffic
+        let keep = before
+            .iter()
+            .max_by(|a, b| a.traffic_weight.total_cmp(&b.traffic_weight))
+            .map(|tw| tw.configuration_id);
+        before
+            .iter()
+            .map(|tw| tw.configuration_id)
+            .filter(|uuid| Some(*uuid) != keep)
+            .collect()
+    } else {
+        cfgs.iter()
+            .map(|cfg| uuid_of_prefix(before.iter().map(|tw| &tw.configuration_id), cfg))
+            .collect::<Result<_>>()?
+    };
+
+    if before
+        .iter()
+        .all(|tw| landed.contains(&tw.configuration_id))
+    {
+        return Err(CliErrorKind::OneOff(format!(
+            "no other active configuration of remote Formation Instance '{name}' can take over its traffic, so nothing was landed"
+        ))
+        .into_err()
+        .context("(hint: land it without '")
+        .color_context(Color::Yellow, "--drain")
+        .context("' to stop it right away)\n"));
+    }
+
+    let mut after = before.drain(&landed)?;
+    req.set_active_configurations(&after.to_model()?, false)?;
+
+    let pb = Pb::new(ctx);
+    pb.set_message(format!(
+        "Waiting {}s for traffic to drain from {} configuration(s)...",
+        over.as_secs(),
+        landed.len()
+    ));
+    thread::sleep(over);
+    pb.finish_and_clear();
+
+    for uuid in &landed {
+        after.deactivate(*uuid);
+    }
+    let model = after.to_model()?;
+    req.set_active_configurations(&model, false)?;
+    record(ctx, name, "land --drain", &model)?;
+
+    Ok(landed)
+}
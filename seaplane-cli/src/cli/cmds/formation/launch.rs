@@ -1,4 +1,11 @@
-use clap::{ArgMatches, Command};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::{value_parser, ArgMatches, Command};
+#[cfg(feature = "compute_api_v2")]
+use seaplane::api::compute::v2::FlightHealthStatus;
 use seaplane::{
     api::{
         compute::v1::{ActiveConfiguration, ActiveConfigurations},
@@ -6,19 +13,24 @@ use seaplane::{
     },
     error::SeaplaneError,
 };
+use uuid::Uuid;
 
+use super::history::record;
 use crate::{
     api::FormationsReq,
     cli::{
         cmds::formation::SeaplaneFormationFetch,
         errors,
-        validator::{validate_formation_name, validate_name_id},
+        validator::{validate_duration, validate_formation_name, validate_name_id},
         CliCommand,
     },
     context::Ctx,
     error::{CliErrorKind, Context, Result},
-    printer::{Color, Pb},
+    ops::formation::{ComputeApiVersion, LaunchProgress, LaunchState},
+    printer::{Color, Output, OutputFormat, Pb},
 };
+#[cfg(feature = "compute_api_v2")]
+use crate::{api::FormationsV2Req, ops::formation::FormationStatus};
 
 static LONG_ABOUT: &str = "Start a local Formation Plan creating a remote Formation Instance
 
@@ -54,7 +66,18 @@ sending traffic to.
 
 These configurations will be made active. If the Seaplane Cloud already has active
 configurations for the given Formation, these newly activated configurations will be appended,
-and traffic will be balanced between any *all* configurations. ";
+and traffic will be balanced between any *all* configurations.
+
+Waiting:
+
+By default this command returns as soon as the configurations are sent to the Seaplane Cloud. With
+--wait it instead keeps checking on the remote Formation Instance until every Flight has at least
+its minimum number of containers running, showing the progress of each Flight. It fails if any
+container stops with a non-zero exit status (or with the v2 Compute API, if any Flight is
+unhealthy), or if --timeout passes first.";
+
+/// How often `--wait` checks on a launching Formation Instance
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationLaunch;
@@ -85,6 +108,23 @@ impl SeaplaneFormationLaunch {
                     "Upload the configuration(s) defined in this local Formation Plan to Seaplane but *DO NOT* set them to active",
                 ),
             )
+            .arg(
+                arg!(--wait)
+                    .conflicts_with("grounded")
+                    .help("Wait until every Flight has its minimum number of containers running"),
+            )
+            .arg(
+                arg!(--timeout =["DURATION"=>"10m"])
+                    .requires("wait")
+                    .value_parser(validate_duration)
+                    .help("How long to wait with --wait before failing, such as '30s', '5m' or '1h'"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .requires("wait")
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format of the summary printed when --wait fails"),
+            )
     }
 }
 
@@ -119,11 +159,24 @@ impl CliCommand for SeaplaneFormationLaunch {
 
         let pb = Pb::new(ctx);
         let grounded = ctx.formation_ctx.get_or_init().grounded;
+        let wait = ctx.formation_ctx.get_or_init().wait;
         let mut req = FormationsReq::new_delay_token(ctx)?;
+        #[cfg(feature = "compute_api_v2")]
+        let mut req_v2 = FormationsV2Req::new_delay_token(ctx)?;
         for idx in indices {
             // re unwrap: the indices returned came from Formations so they have to be valid
             let formation = ctx.db.formations.get_formation(idx).unwrap();
             let formation_name = formation.name.as_ref().unwrap().clone();
+
+            if formation.api_version == ComputeApiVersion::V2 {
+                formation.api_version.ensure_supported(&formation_name)?;
+                #[cfg(feature = "compute_api_v2")]
+                {
+                    launch_v2(ctx, &mut req_v2, idx, grounded, wait, &pb)?;
+                    continue;
+                }
+            }
+
             req.set_name(&formation_name)?;
 
             // Get the local configs that don't exist remote yet
@@ -152,6 +205,8 @@ impl CliCommand for SeaplaneFormationLaunch {
             let mut created_new = false;
             let mut has_public_endpoints = false;
             let mut cfg_uuids = Vec::new();
+            // The Flights of the configurations sent by this launch, for --wait
+            let mut progress = LaunchProgress::default();
 
             // Add those configurations to this formation
             'inner: for id in &cfgs_ids {
@@ -177,24 +232,29 @@ impl CliCommand for SeaplaneFormationLaunch {
                     // We don't set the configuration to active because we'll be doing that to
                     // *all* formation configs in a minute
                     pb.set_message("Searching for existing Formations...");
-                    if let Err(e) = req.add_configuration(&cfg.model, false) {
-                        match e.kind() {
+                    match req.add_configuration(&cfg.model, false) {
+                        Err(e) => match e.kind() {
                             CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
                                 if ae.kind == ApiErrorKind::NotFound =>
                             {
                                 // If the formation didn't exist, create it
                                 pb.set_message("Creating new Formation Instance...");
                                 let cfg_uuid = req.create(&cfg.model, !grounded)?;
+                                if let Some(uuid) = cfg_uuid.first() {
+                                    progress.add_configuration(*uuid, &cfg.model);
+                                }
                                 cfg_uuids.extend(cfg_uuid);
                                 ctx.db.formations.add_in_air_by_name(&formation_name, *id);
                                 created_new = true;
                                 break 'inner;
                             }
                             _ => return Err(e),
+                        },
+                        Ok(uuid) => {
+                            pb.set_message("Found existing Formation Instance...");
+                            progress.add_configuration(uuid, &cfg.model);
+                            ctx.db.formations.add_grounded_by_name(&formation_name, *id);
                         }
-                    } else {
-                        pb.set_message("Found existing Formation Instance...");
-                        ctx.db.formations.add_grounded_by_name(&formation_name, *id);
                     }
                 } else {
                     // TODO: Inform the user of possible error? Somehow there is no config by the
@@ -213,23 +273,16 @@ impl CliCommand for SeaplaneFormationLaunch {
                     req.list_configuration_ids()
                         .context("Context: failed to retrieve Formation Configuration IDs\n")?,
                 );
-                let mut active_configs = ActiveConfigurations::new();
-                for uuid in &cfg_uuids {
-                    #[cfg_attr(not(feature = "unstable"), allow(unused_mut))]
-                    let mut cfg = ActiveConfiguration::builder().uuid(*uuid);
-                    #[cfg(feature = "unstable")]
-                    {
-                        cfg = cfg.traffic_weight(1.0);
-                    }
-                    active_configs.add_configuration_mut(cfg.build()?);
-                }
                 pb.set_message("Adding Formation Configurations to remote Instance...");
-                req.set_active_configurations(&active_configs, false)
+                req.set_active_configurations(&active_configurations(&cfg_uuids)?, false)
                     .context("Context: failed to start Formation\n")?;
                 for id in cfgs_ids {
                     ctx.db.formations.add_in_air_by_name(&formation_name, id);
                 }
             }
+            if !grounded {
+                record(ctx, &formation_name, "launch", &active_configurations(&cfg_uuids)?)?;
+            }
             pb.set_message("Getting Formation URL...");
             let domain = req.get_metadata()?.url;
 
@@ -247,15 +300,28 @@ impl CliCommand for SeaplaneFormationLaunch {
 
             cli_print!("The remote Formation Instance URL is ");
             cli_println!(@Green, "{domain}");
-            cli_println!(
-                "(hint: it may take up to a minute for the Formation to become fully online)"
-            );
             if !has_public_endpoints {
                 cli_println!("(hint: there are no public endpoints configured, the Formation will not be reachable from the public internet)");
             }
-            cli_print!("(hint: check the status of this Formation Instance with '");
-            cli_print!(@Green, "seaplane formation status {formation_name}");
-            cli_println!("')");
+
+            if let Some(timeout) = wait {
+                // The remote Formation Instance exists whether or not it comes up, so save the
+                // local state first in case waiting fails
+                ctx.persist_formations()?;
+                let state = wait_for(ctx, timeout, |pb| {
+                    progress.update(&req.get_containers()?);
+                    pb.set_message(progress.message());
+                    Ok(progress.state())
+                })?;
+                wait_result(ctx, &formation_name, state, timeout, &progress)?;
+            } else {
+                cli_println!(
+                    "(hint: it may take up to a minute for the Formation to become fully online)"
+                );
+                cli_print!("(hint: check the status of this Formation Instance with '");
+                cli_print!(@Green, "seaplane formation status {formation_name}");
+                cli_println!("')");
+            }
         }
 
         ctx.persist_formations()?;
@@ -270,6 +336,162 @@ impl CliCommand for SeaplaneFormationLaunch {
         ctx.args.fetch = matches.get_flag("fetch");
         let fctx = ctx.formation_ctx.get_mut_or_init();
         fctx.grounded = matches.get_flag("grounded");
+        fctx.wait = if matches.get_flag("wait") {
+            matches.get_one::<Duration>("timeout").copied()
+        } else {
+            None
+        };
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
         Ok(())
     }
 }
+
+/// All of `uuids` as active configurations with the same traffic weight
+fn active_configurations(uuids: &[Uuid]) -> Result<ActiveConfigurations> {
+    let mut active_configs = ActiveConfigurations::new();
+    for uuid in uuids {
+        #[cfg_attr(not(feature = "unstable"), allow(unused_mut))]
+        let mut cfg = ActiveConfiguration::builder().uuid(*uuid);
+        #[cfg(feature = "unstable")]
+        {
+            cfg = cfg.traffic_weight(1.0);
+        }
+        active_configs.add_configuration_mut(cfg.build()?);
+    }
+    Ok(active_configs)
+}
+
+/// The v2 Compute API has no Formation Configurations, so launching a Formation Plan creates the
+/// remote Formation Instance from its Flights
+#[cfg(feature = "compute_api_v2")]
+fn launch_v2(
+    ctx: &mut Ctx,
+    req: &mut FormationsV2Req,
+    idx: usize,
+    grounded: bool,
+    wait: Option<Duration>,
+    pb: &Pb,
+) -> Result<()> {
+    let formation = ctx.db.formations.get_formation(idx).unwrap();
+    let formation_name = formation.name.as_ref().unwrap().clone();
+    if grounded {
+        return Err(CliErrorKind::OneOff(format!(
+            "Formation Plan '{formation_name}' uses the v2 Compute API which cannot launch Formations as Grounded"
+        ))
+        .into_err());
+    }
+
+    let model = ctx.db.formations.v2_model(formation)?;
+    let ids = formation.configs();
+    req.set_name(&formation_name)?;
+    pb.set_message("Creating new Formation Instance...");
+    req.create(&model)?;
+    for id in ids {
+        ctx.db.formations.add_in_air_by_name(&formation_name, id);
+    }
+
+    pb.finish_and_clear();
+    cli_print!("Successfully Launched remote Formation Instance '");
+    cli_print!(@Green, "{formation_name}");
+    cli_print!("' with gateway Flight '");
+    cli_print!(@Green, "{}", model.gateway_flight());
+    cli_println!("'");
+
+    if let Some(timeout) = wait {
+        // The remote Formation Instance exists whether or not it comes up, so save the local state
+        // first in case waiting fails
+        ctx.persist_formations()?;
+        let mut status = FormationStatus::new(&formation_name);
+        let state = wait_for(ctx, timeout, |pb| {
+            let model = req.status()?;
+            pb.set_message(
+                model
+                    .flights
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name, f.health.as_ref()))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            );
+            let state = if model
+                .flights
+                .iter()
+                .any(|f| f.health == FlightHealthStatus::Unhealthy)
+            {
+                LaunchState::Failed
+            } else if !model.flights.is_empty()
+                && model
+                    .flights
+                    .iter()
+                    .all(|f| f.health == FlightHealthStatus::Healthy)
+            {
+                LaunchState::Running
+            } else {
+                LaunchState::Pending
+            };
+            status = FormationStatus::from_v2(model);
+            Ok(state)
+        })?;
+        return wait_result(ctx, &formation_name, state, timeout, &status);
+    }
+
+    cli_println!("(hint: it may take up to a minute for the Formation to become fully online)");
+    cli_print!("(hint: check the status of this Formation Instance with '");
+    cli_print!(@Green, "seaplane formation status {formation_name}");
+    cli_println!("')");
+
+    Ok(())
+}
+
+/// Calls `poll` until it returns something other than `LaunchState::Pending`, or until `timeout`
+/// passes
+pub(super) fn wait_for(
+    ctx: &Ctx,
+    timeout: Duration,
+    mut poll: impl FnMut(&Pb) -> Result<LaunchState>,
+) -> Result<LaunchState> {
+    let pb = Pb::new(ctx);
+    let start = Instant::now();
+    loop {
+        let state = poll(&pb)?;
+        let elapsed = start.elapsed();
+        if state != LaunchState::Pending || elapsed >= timeout {
+            pb.finish_and_clear();
+            return Ok(state);
+        }
+        thread::sleep(WAIT_POLL_INTERVAL.min(timeout - elapsed));
+    }
+}
+
+/// Reports the outcome of `--wait`, printing `summary` unless the Formation Instance is running
+pub(super) fn wait_result(
+    ctx: &Ctx,
+    name: &str,
+    state: LaunchState,
+    timeout: Duration,
+    summary: &dyn Output,
+) -> Result<()> {
+    if state == LaunchState::Running {
+        cli_print!("Remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("' is running");
+        return Ok(());
+    }
+
+    match ctx.args.out_format {
+        OutputFormat::Json => summary.print_json(ctx)?,
+        OutputFormat::Table => summary.print_table(ctx)?,
+    }
+    let msg = if state == LaunchState::Failed {
+        format!("remote Formation Instance '{name}' failed to start")
+    } else {
+        format!(
+            "remote Formation Instance '{name}' was not running after waiting {}s",
+            timeout.as_secs()
+        )
+    };
+    Err(CliErrorKind::OneOff(msg)
+        .into_err()
+        .context("(hint: check the status of this Formation Instance with '")
+        .with_color_context(|| (Color::Green, format!("seaplane formation status {name}")))
+        .context("')\n"))
+}
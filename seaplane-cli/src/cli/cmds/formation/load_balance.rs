@@ -1,6 +1,26 @@
-use clap::Command;
+use clap::{value_parser, ArgMatches, Command};
+use serde_json::json;
 
-use crate::{cli::CliCommand, error::Result, Ctx};
+use super::history::record;
+use crate::{
+    api::FormationsReq,
+    cli::{validator::validate_formation_name, CliCommand},
+    error::{CliErrorKind, Result},
+    ops::formation::{TrafficWeightSpec, TrafficWeights},
+    printer::Output,
+    Ctx, OutputFormat,
+};
+
+static LONG_ABOUT: &str =
+    "Control how traffic balances between various configurations of a Formation
+
+Without --set the current traffic weight of each active configuration is shown, along with the
+percentage of traffic it receives.
+
+Using --set CFG=WEIGHT changes the weight of one or more configurations in a single call, where CFG is
+the UUID (or an unambiguous prefix of it) of an active configuration. Configurations which aren't
+mentioned keep their current weight. Weights are proportional, and are normalized to add up to 100
+before being sent so that each weight is the percentage of traffic the configuration receives.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationLoadBalance;
@@ -10,9 +30,106 @@ impl SeaplaneFormationLoadBalance {
         Command::new("load-balance")
             .visible_alias("lb")
             .about("Control how traffic balances between various configurations of a Formation")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(
+                arg!(--set =["CFG=WEIGHT"]...)
+                    .value_parser(value_parser!(TrafficWeightSpec))
+                    .help("Change the traffic weight of a configuration (supports COMMA separated list, or multiple uses)"),
+            )
+            .arg(
+                arg!(--("dry-run"))
+                    .requires("set")
+                    .help("Only show the traffic split before and after the change, without changing it"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
     }
 }
 
 impl CliCommand for SeaplaneFormationLoadBalance {
-    fn run(&self, _ctx: &mut Ctx) -> Result<()> { todo!("impl SeaplaneFormationLoadBalance::run") }
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let formation_ctx = ctx.formation_ctx.get_or_init();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let before = TrafficWeights::from(&req.get_active_configurations()?);
+        if before.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "remote Formation Instance '{name}' has no active configurations"
+            ))
+            .into_err());
+        }
+
+        if formation_ctx.traffic_weights.is_empty() {
+            return match ctx.args.out_format {
+                OutputFormat::Json => before.print_json(ctx),
+                OutputFormat::Table => before.print_table(ctx),
+            };
+        }
+
+        let mut after = before.clone();
+        for spec in &formation_ctx.traffic_weights {
+            after.set(spec)?;
+        }
+        after.normalize()?;
+
+        if formation_ctx.dry_run {
+            match ctx.args.out_format {
+                OutputFormat::Json => {
+                    cli_println!("{}", json!({ "before": before, "after": after }));
+                }
+                OutputFormat::Table => {
+                    cli_print!("Current traffic split of Formation Instance '");
+                    cli_print!(@Green, "{name}");
+                    cli_println!("':");
+                    before.print_table(ctx)?;
+                    cli_println!("");
+                    cli_println!("New traffic split:");
+                    after.print_table(ctx)?;
+                    cli_print!("\n(hint: run without '");
+                    cli_print!(@Yellow, "--dry-run");
+                    cli_println!("' to apply it)");
+                }
+            }
+            return Ok(());
+        }
+
+        let model = after.to_model()?;
+        req.set_active_configurations(&model, false)?;
+        record(ctx, &name, "load-balance", &model)?;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => after.print_json(ctx)?,
+            OutputFormat::Table => {
+                cli_print!("Successfully updated the traffic split of Formation Instance '");
+                cli_print!(@Green, "{name}");
+                cli_println!("':");
+                after.print_table(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.traffic_weights = matches
+            .get_many::<TrafficWeightSpec>("set")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        fctx.dry_run = matches.get_flag("dry-run");
+
+        Ok(())
+    }
 }
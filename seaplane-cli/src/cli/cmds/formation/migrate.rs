@@ -0,0 +1,195 @@
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::{
+    v1::FormationConfiguration as FormationConfigurationModel,
+    v2::{ConversionError, DroppedField, Formation as FormationModelV2},
+};
+
+use crate::{
+    cli::{
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::formation::{ComputeApiVersion, FormationConfiguration},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Convert a local Formation Plan to another version of the Compute API
+
+The v2 Compute API only knows about the name and container image of each Flight, plus a gateway
+Flight which receives the public traffic of the Formation. Everything else a v1 Formation Plan
+describes, such as the minimum and maximum number of instances, endpoints, and allowed providers or
+regions, has no equivalent and is dropped. Use --dry-run to see which fields would be dropped without
+changing anything.
+
+Converting a v1 Formation Plan picks the gateway Flight from its public endpoints, or its only Flight
+when there are none.
+
+Only Formation Plans with a single configuration and no remote Formation Instances can be migrated.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationMigrate;
+
+impl SeaplaneFormationMigrate {
+    pub fn command() -> Command {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        Command::new("migrate")
+            .about("Convert a local Formation Plan to another version of the Compute API")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME|ID"] required)
+                    .value_parser(validator)
+                    .help("The name or ID of the Formation Plan to migrate, must be unambiguous"),
+            )
+            .arg(
+                arg!(--to =["VERSION"] required)
+                    .value_parser(value_parser!(ComputeApiVersion))
+                    .help("The version of the Compute API to migrate the Formation Plan to"),
+            )
+            .arg(arg!(--("dry-run")).help(
+                "Only show which fields would be dropped, without changing the Formation Plan",
+            ))
+    }
+}
+
+impl CliCommand for SeaplaneFormationMigrate {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let formation_ctx = ctx.formation_ctx.get_or_init().clone();
+        let to = formation_ctx.api_version;
+
+        let indices = ctx
+            .db
+            .formations
+            .formation_indices_of_matches(&formation_ctx.name_id);
+        match indices.len() {
+            0 => errors::no_matching_item(formation_ctx.name_id.clone(), false, false)?,
+            1 => (),
+            _ => errors::ambiguous_item(formation_ctx.name_id.clone(), false)?,
+        }
+
+        let idx = indices[0];
+        let formation = ctx.db.formations.get_formation(idx).unwrap();
+        let name = formation.name.clone().unwrap_or_default();
+        if formation.api_version == to {
+            cli_print!("Formation Plan '");
+            cli_print!(@Green, "{name}");
+            cli_println!("' already uses the {to} Compute API");
+            return Ok(());
+        }
+        if !formation.in_air.is_empty() || !formation.grounded.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "cannot migrate Formation Plan '{name}' which has a remote Formation Instance"
+            ))
+            .into_err()
+            .context("(hint: delete the remote Formation Instance first with '")
+            .with_color_context(|| {
+                (Color::Green, format!("seaplane formation delete {name} --no-local"))
+            })
+            .context("')\n"));
+        }
+        let ids = formation.configs();
+        if ids.len() != 1 {
+            return Err(CliErrorKind::OneOff(format!(
+                "cannot migrate Formation Plan '{name}' which has {} configurations, only a single configuration can be migrated",
+                ids.len()
+            ))
+            .into_err());
+        }
+        let old_id = ids[0];
+
+        let (cfg, gateway_flight, dropped) = match to {
+            ComputeApiVersion::V2 => {
+                let cfg = ctx.db.formations.get_configuration(&old_id).unwrap();
+                let (model, dropped) = split(FormationModelV2::try_from(cfg.model.clone()))
+                    .context("(hint: the gateway Flight is the one all public endpoints point to, or the one serving the '")
+                    .color_context(Color::Yellow, "/")
+                    .context("' route)\n")?;
+                let gateway_flight = model.gateway_flight().to_owned();
+                // Going back to the local v1 configuration only drops the gateway Flight, which
+                // we keep on the Formation itself
+                let (cfg, _) = split(FormationConfigurationModel::try_from(model))?;
+                (cfg, Some(gateway_flight), dropped)
+            }
+            ComputeApiVersion::V1 => {
+                let model = ctx.db.formations.v2_model(formation)?;
+                let (cfg, dropped) = split(FormationConfigurationModel::try_from(model))?;
+                (cfg, None, dropped)
+            }
+        };
+
+        if !dropped.is_empty() {
+            cli_print!("The following fields of Formation Plan '");
+            cli_print!(@Green, "{name}");
+            cli_println!(
+                "' can't be expressed in the {to} Compute API and {} dropped:",
+                if formation_ctx.dry_run { "would be" } else { "were" }
+            );
+            for field in &dropped {
+                cli_print!("    ");
+                cli_print!(@Yellow, "{}", field.field);
+                cli_println!(" = {}", field.value);
+            }
+        }
+
+        if formation_ctx.dry_run {
+            cli_print!("Formation Plan '");
+            cli_print!(@Green, "{name}");
+            cli_print!("' can be migrated to the {to} Compute API");
+            if let Some(gateway) = &gateway_flight {
+                cli_print!(" with gateway Flight '");
+                cli_print!(@Green, "{gateway}");
+                cli_print!("'");
+            }
+            cli_println!("");
+            cli_print!("(hint: run without '");
+            cli_print!(@Yellow, "--dry-run");
+            cli_println!("' to migrate it)");
+            return Ok(());
+        }
+
+        // Configurations may be shared between Formations, so the converted one gets a new ID
+        let cfg = FormationConfiguration::new(cfg);
+        let new_id = cfg.id;
+        ctx.db.formations.configurations.push(cfg);
+        let formation = ctx.db.formations.get_formation_mut(idx).unwrap();
+        formation.replace_id(&old_id, new_id);
+        formation.api_version = to;
+        formation.gateway_flight = gateway_flight;
+        if !ctx
+            .db
+            .formations
+            .formations()
+            .any(|f| f.configs().contains(&old_id))
+        {
+            ctx.db.formations.remove_configuration(&old_id);
+        }
+
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully migrated local Formation Plan '");
+        cli_print!(@Green, "{name}");
+        cli_println!("' to the {to} Compute API");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.name_id = matches.get_one::<String>("formation").unwrap().to_string();
+        fctx.api_version = *matches.get_one("to").unwrap();
+        fctx.dry_run = matches.get_flag("dry-run");
+
+        Ok(())
+    }
+}
+
+/// Splits the result of a conversion into the converted model and the fields which were dropped
+fn split<T>(res: std::result::Result<T, ConversionError<T>>) -> Result<(T, Vec<DroppedField>)> {
+    match res {
+        Ok(converted) => Ok((converted, Vec::new())),
+        Err(ConversionError::Lossy { converted, dropped }) => Ok((converted, dropped)),
+        Err(ConversionError::Invalid(e)) => Err(CliError::from(e)),
+    }
+}
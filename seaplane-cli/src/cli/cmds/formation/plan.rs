@@ -1,17 +1,23 @@
+#[cfg(feature = "compute_api_v2")]
+use clap::value_parser;
 use clap::{ArgMatches, Command};
-use const_format::concatcp;
 
 #[cfg(not(any(feature = "ui_tests", feature = "semantic_ui_tests")))]
 use crate::cli::cmds::flight::SeaplaneFlightPlan;
+#[cfg(feature = "compute_api_v2")]
+use crate::ops::formation::ComputeApiVersion;
 use crate::{
     cli::{
         cmds::formation::{common, SeaplaneFormationFetch, SeaplaneFormationLaunch},
-        specs::{FLIGHT_SPEC, REGION_SPEC},
+        specs::{region_spec, FLIGHT_SPEC},
         CliCommand,
     },
     context::{Ctx, FlightCtx},
     error::{CliErrorKind, Context, Result},
-    ops::formation::{Formation, FormationConfiguration},
+    ops::{
+        formation::{Formation, FormationConfiguration},
+        preset::Preset,
+    },
     printer::Color,
 };
 
@@ -36,14 +42,28 @@ pub struct SeaplaneFormationPlan;
 
 impl SeaplaneFormationPlan {
     pub fn command() -> Command {
-        Command::new("plan")
-            .after_help(concatcp!(FLIGHT_SPEC, "\n\n", REGION_SPEC))
+        #[cfg_attr(not(feature = "compute_api_v2"), allow(unused_mut))]
+        let mut app = Command::new("plan")
+            .after_help(format!("{FLIGHT_SPEC}\n\n{}", region_spec(false)))
+            .after_long_help(format!("{FLIGHT_SPEC}\n\n{}", region_spec(true)))
             .visible_aliases(["create", "add"])
             .about("Create a Seaplane Formation")
             .long_about(LONG_ABOUT)
             .args(common::args())
             .arg(arg!(--force).help("Override any existing Formation with the same NAME"))
-            .arg(arg!(--fetch|sync|synchronize - ('F')).help("Fetch remote instances prior to creating this plan to check for conflicts (by default only local references are considered)"))
+            .arg(arg!(--fetch|sync|synchronize - ('F')).help("Fetch remote instances prior to creating this plan to check for conflicts (by default only local references are considered)"));
+
+        #[cfg(feature = "compute_api_v2")]
+        {
+            app = app
+                .arg(arg!(--("api-version") =["VERSION"=>"v1"])
+                    .value_parser(value_parser!(ComputeApiVersion))
+                    .help("The version of the Compute API the Formation belongs to"))
+                .arg(arg!(--("gateway-flight") =["NAME"])
+                    .help("The Flight which receives the public traffic of a v2 Formation (may be omitted when there is only a single Flight)"));
+        }
+
+        app
     }
 }
 
@@ -59,6 +79,13 @@ impl CliCommand for SeaplaneFormationPlan {
 
         let formation_ctx = ctx.formation_ctx.get_or_init();
 
+        #[cfg(feature = "compute_api_v2")]
+        {
+            if formation_ctx.api_version == ComputeApiVersion::V2 {
+                formation_ctx.ensure_v2_compatible()?;
+            }
+        }
+
         // Check for duplicates and suggest `seaplane formation edit`
         let name = &formation_ctx.name_id;
         if ctx.db.formations.contains_name(name) {
@@ -92,6 +119,8 @@ impl CliCommand for SeaplaneFormationPlan {
 
         // Add the new formation
         let mut new_formation = Formation::new(&formation_ctx.name_id);
+        new_formation.api_version = formation_ctx.api_version;
+        new_formation.gateway_flight = formation_ctx.gateway_flight.clone();
 
         let cfg = formation_ctx.configuration_model(ctx)?;
         let formation_cfg = FormationConfiguration::new(cfg);
@@ -195,6 +224,15 @@ impl CliCommand for SeaplaneFormationPlan {
                 &ctx.db.flights,
             )?;
 
+        if let Some(name) = matches.get_one::<String>("preset") {
+            let preset = Preset::resolve(ctx, name)?;
+            let cfg_ctx = &mut ctx.formation_ctx.get_mut_or_init().cfg_ctx;
+            cfg_ctx.providers_allowed.extend(preset.providers_allowed);
+            cfg_ctx.providers_denied.extend(preset.providers_denied);
+            cfg_ctx.regions_allowed.extend(preset.regions_allowed);
+            cfg_ctx.regions_denied.extend(preset.regions_denied);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,101 @@
+use clap::{value_parser, ArgMatches, Command};
+
+use super::history::{now, record};
+use crate::{
+    api::FormationsReq,
+    cli::{validator::validate_formation_name, CliCommand},
+    error::{CliErrorKind, Context, Result},
+    ops::formation::DeploymentHistory,
+    printer::Color,
+    Ctx,
+};
+
+static LONG_ABOUT: &str =
+    "Re-activate the configurations of an earlier deployment of a remote Formation Instance
+
+The deployments come from the local log shown by 'seaplane formation history'. Without --to the
+Formation Instance returns to the deployment before the latest one. The configurations and traffic
+weights of the deployment replace all currently active configurations, and the rollback itself is
+added to the log.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationRollback;
+
+impl SeaplaneFormationRollback {
+    pub fn command() -> Command {
+        Command::new("rollback")
+            .about("Re-activate the configurations of an earlier deployment of a remote Formation Instance")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(
+                arg!(--to =["N"])
+                    .value_parser(value_parser!(usize))
+                    .help("The number of the deployment to return to, as shown by 'seaplane formation history' (default: the one before the latest)"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationRollback {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let history =
+            DeploymentHistory { deployments: ctx.db.formations.deployments(&name), now: now() };
+        let n = match ctx.formation_ctx.get_or_init().rollback_to {
+            Some(n) => n,
+            None if history.deployments.len() > 1 => history.deployments.len() - 1,
+            None => {
+                return Err(CliErrorKind::OneOff(format!(
+                    "there is no earlier deployment of remote Formation Instance '{name}' to roll back to"
+                ))
+                .into_err()
+                .context("(hint: see the recorded deployments with '")
+                .with_color_context(|| (Color::Green, format!("seaplane formation history {name}")))
+                .context("')\n"));
+            }
+        };
+        let deployment = history
+            .get(n)
+            .context("(hint: see the recorded deployments with '")
+            .with_color_context(|| (Color::Green, format!("seaplane formation history {name}")))
+            .context("')\n")?;
+        if deployment.configurations.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "remote Formation Instance '{name}' had no active configurations after deployment {n}"
+            ))
+            .into_err()
+            .context("(hint: land it with '")
+            .with_color_context(|| (Color::Green, format!("seaplane formation land {name}")))
+            .context("')\n"));
+        }
+        let model = deployment.to_model()?;
+        let uuids: Vec<_> = deployment
+            .configurations
+            .iter()
+            .map(|cfg| cfg.uuid)
+            .collect();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        req.set_active_configurations(&model, false)
+            .context("Context: failed to re-activate the configurations\n")?;
+        record(ctx, &name, format!("rollback --to {n}"), &model)?;
+
+        cli_print!("Successfully rolled back remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("' to deployment {n} with Configuration UUIDs:");
+        for uuid in uuids {
+            cli_println!(@Green, "{uuid}");
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        ctx.formation_ctx.get_mut_or_init().rollback_to = matches.get_one::<usize>("to").copied();
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::thread;
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::v1::{
+    Container as ContainerModel, ContainerStatus, Containers as ContainersModel,
+};
+use uuid::Uuid;
+
+use super::history::record;
+use crate::{
+    api::FormationsReq,
+    cli::{
+        validator::{validate_duration, validate_formation_name},
+        CliCommand,
+    },
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{uuid_of_prefix, TrafficWeights},
+    printer::{Color, Pb},
+    Ctx,
+};
+
+static LONG_ABOUT: &str =
+    "Gradually move traffic of a Formation Instance to one of its configurations
+
+The configuration given by --to receives each percentage of --steps in turn, while the currently
+active configurations share the rest of the traffic in proportion to their current weights. Once
+the last step reaches 100 the other configurations are deactivated.
+
+Between steps the command waits for --interval, then checks the containers of the new
+configuration. If any of them stopped or exited the previous traffic weights are restored and the
+command fails.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationRollout;
+
+impl SeaplaneFormationRollout {
+    pub fn command() -> Command {
+        Command::new("rollout")
+            .about("Gradually move traffic of a Formation Instance to one of its configurations")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(
+                arg!(--to =["CFG"] required)
+                    .help("The UUID (or an unambiguous prefix of it) of the configuration to move traffic to"),
+            )
+            .arg(
+                arg!(--steps =["PERCENT"]...)
+                    .value_parser(value_parser!(u8).range(1..=100))
+                    .default_value("10,25,50,100")
+                    .help("The percentage of traffic to send to the configuration at each step (supports COMMA separated list, or multiple uses)"),
+            )
+            .arg(
+                arg!(--interval =["DURATION"=>"5m"])
+                    .value_parser(validate_duration)
+                    .help("How long to wait between steps, such as '30s', '5m' or '1h'"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationRollout {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let rollout = &ctx.formation_ctx.get_or_init().rollout;
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let target = uuid_of_prefix(&req.list_configuration_ids()?, &rollout.to)?;
+        let before = TrafficWeights::from(&req.get_active_configurations()?);
+        if before.iter().all(|tw| tw.configuration_id == target) {
+            return Err(CliErrorKind::OneOff(format!(
+                "remote Formation Instance '{name}' has no other active configurations to move traffic from"
+            ))
+            .into_err());
+        }
+
+        let last = rollout.steps.len() - 1;
+        for (i, &step) in rollout.steps.iter().enumerate() {
+            req.set_active_configurations(&before.rollout_step(target, step).to_model()?, false)?;
+            cli_print!("Sent {step}% of traffic to configuration ");
+            cli_println!(@Green, "{target}");
+            if i == last {
+                break;
+            }
+
+            let pb = Pb::new(ctx);
+            pb.set_message(format!(
+                "Waiting {}s before checking the health of configuration {target}...",
+                rollout.interval.as_secs()
+            ));
+            thread::sleep(rollout.interval);
+            pb.finish_and_clear();
+
+            let containers = req.get_containers()?;
+            let failed = failed_containers(&containers, target);
+            if !failed.is_empty() {
+                for c in &failed {
+                    cli_print!("Container {} of Flight '", c.container_id);
+                    cli_print!(@Yellow, "{}", c.flight_name);
+                    match c.exit_status {
+                        Some(status) => cli_println!("' exited with status {status}"),
+                        None => cli_println!("' stopped"),
+                    }
+                }
+                let model = before.to_model()?;
+                req.set_active_configurations(&model, false)?;
+                record(ctx, &name, format!("rollout --to {target} (rolled back)"), &model)?;
+                return Err(CliErrorKind::OneOff(format!(
+                    "rolled back remote Formation Instance '{name}' to its previous traffic weights because configuration {target} is unhealthy"
+                ))
+                .into_err()
+                .context("(hint: check the containers with '")
+                .with_color_context(|| (Color::Green, format!("seaplane formation status {name}")))
+                .context("')\n"));
+            }
+        }
+
+        // The last step is what was sent, which need not be 100%
+        let model = before
+            .rollout_step(target, rollout.steps[last])
+            .to_model()?;
+        record(ctx, &name, format!("rollout --to {target}"), &model)?;
+
+        cli_print!("Successfully rolled out configuration ");
+        cli_print!(@Green, "{target}");
+        cli_print!(" to remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_println!("'");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        let rollout = &mut ctx.formation_ctx.get_mut_or_init().rollout;
+        rollout.to = matches.get_one::<String>("to").unwrap().to_owned();
+        rollout.steps = matches.get_many::<u8>("steps").unwrap().copied().collect();
+        rollout.interval = *matches.get_one("interval").unwrap();
+
+        if rollout.steps.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(CliErrorKind::InvalidCliValue(
+                Some("steps"),
+                rollout
+                    .steps
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+            .into_err()
+            .context("(hint: each step must send more traffic than the previous one)\n"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the containers of the configuration `cfg` which stopped or exited
+fn failed_containers(containers: &ContainersModel, cfg: Uuid) -> Vec<&ContainerModel> {
+    containers
+        .iter()
+        .filter(|c| c.configuration_id == cfg)
+        .filter(|c| c.status == ContainerStatus::Stopped || c.exit_status.is_some())
+        .collect()
+}
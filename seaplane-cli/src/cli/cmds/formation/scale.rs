@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::v1::FormationConfiguration as FormationConfigurationModel;
+use uuid::Uuid;
+
+use super::{
+    history::record,
+    launch::{wait_for, wait_result},
+};
+use crate::{
+    api::FormationsReq,
+    cli::{
+        validator::{validate_duration, validate_formation_name},
+        CliCommand,
+    },
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{uuid_of_prefix, LaunchProgress, TrafficWeights},
+    printer::Color,
+    Ctx,
+};
+
+static LONG_ABOUT: &str =
+    "Change the minimum and maximum number of containers of a Flight in a remote Formation Instance
+
+A new configuration is made from the active configuration containing the Flight, with only the
+limits of that Flight changed. The new configuration is added to the Formation Instance and takes
+the place (and traffic weight) of the old one, which is left as an inactive configuration.
+
+With --remove-old the command then waits until every Flight of the new configuration has at least
+its minimum number of containers running, and removes the old configuration. If the new
+configuration isn't running before --timeout passes the old configuration is kept.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationScale;
+
+impl SeaplaneFormationScale {
+    pub fn command() -> Command {
+        Command::new("scale")
+            .about("Change the minimum and maximum number of containers of a Flight in a remote Formation Instance")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME"] required)
+                    .value_parser(validate_formation_name)
+                    .help("The name of the remote Formation Instance"),
+            )
+            .arg(arg!(flight =["FLIGHT"] required).help("The name of the Flight to scale"))
+            .arg(
+                arg!(--min|minimum =["NUM"] required)
+                    .value_parser(value_parser!(u64))
+                    .help("The minimum number of containers of the Flight"),
+            )
+            .arg(
+                arg!(--max|maximum =["NUM"])
+                    .value_parser(value_parser!(u64))
+                    .help("The maximum number of containers of the Flight (default: the current maximum)"),
+            )
+            .arg(
+                arg!(--("no-max"))
+                    .conflicts_with("max")
+                    .help("Remove the maximum number of containers of the Flight"),
+            )
+            .arg(
+                arg!(--configuration =["UUID"])
+                    .help("The UUID (or an unambiguous prefix of it) of the active configuration to scale, when the Flight is in more than one"),
+            )
+            .arg(
+                arg!(--("remove-old"))
+                    .help("Remove the old configuration once the new one is running"),
+            )
+            .arg(
+                arg!(--timeout =["DURATION"=>"10m"])
+                    .requires("remove-old")
+                    .value_parser(validate_duration)
+                    .help("How long to wait with --remove-old before failing, such as '30s', '5m' or '1h'"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationScale {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.clone().unwrap();
+        let fctx = ctx.formation_ctx.get_or_init();
+        let scale = fctx.scale.clone();
+        let configuration = fctx.configuration.clone();
+
+        let mut req = FormationsReq::new(ctx, Some(&name))?;
+        let mut weights = TrafficWeights::from(&req.get_active_configurations()?);
+        let (old, mut model) = configuration_of_flight(
+            &mut req,
+            &name,
+            &weights,
+            &scale.flight,
+            configuration.as_deref(),
+        )?;
+
+        let mut flights = model.flights().to_vec();
+        // re unwrap: the configuration was picked because it has this Flight
+        let flight = flights
+            .iter_mut()
+            .find(|f| f.name() == scale.flight)
+            .unwrap();
+        let maximum = scale.maximum.unwrap_or_else(|| flight.maximum());
+        if matches!(maximum, Some(max) if max < scale.minimum) {
+            return Err(CliErrorKind::InvalidCliValue(Some("min"), scale.minimum.to_string())
+                .into_err()
+                .context("the minimum cannot be greater than the maximum of the Flight\n")
+                .context("(hint: raise the maximum with '")
+                .color_context(Color::Yellow, "--max")
+                .context("' or remove it with '")
+                .color_context(Color::Yellow, "--no-max")
+                .context("')\n"));
+        }
+        flight.set_minimum(scale.minimum);
+        flight.set_maximum(maximum);
+        model.set_flights(flights);
+
+        let new = req.add_configuration(&model, false)?;
+        weights.replace(old, new);
+        let active = weights.to_model()?;
+        req.set_active_configurations(&active, false)
+            .context("Context: failed to activate the scaled configuration\n")?;
+        record(ctx, &name, format!("scale {}", scale.flight), &active)?;
+
+        cli_print!("Successfully scaled Flight '");
+        cli_print!(@Green, "{}", scale.flight);
+        cli_print!("' of remote Formation Instance '");
+        cli_print!(@Green, "{name}");
+        cli_print!("' with new configuration ");
+        cli_println!(@Green, "{new}");
+
+        if let Some(timeout) = scale.remove_old {
+            let mut progress = LaunchProgress::default();
+            progress.add_configuration(new, &model);
+            let state = wait_for(ctx, timeout, |pb| {
+                progress.update(&req.get_containers()?);
+                pb.set_message(progress.message());
+                Ok(progress.state())
+            })?;
+            wait_result(ctx, &name, state, timeout, &progress)
+                .context("(hint: the old configuration ")
+                .with_color_context(|| (Color::Yellow, old.to_string()))
+                .context(" was kept)\n")?;
+
+            req.remove_configuration(old, false)?;
+            cli_print!("Removed old configuration ");
+            cli_println!(@Green, "{old}");
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("formation").cloned();
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.configuration = matches.get_one::<String>("configuration").cloned();
+        let scale = &mut fctx.scale;
+        scale.flight = matches.get_one::<String>("flight").unwrap().to_owned();
+        scale.minimum = *matches.get_one::<u64>("min").unwrap();
+        scale.maximum = if matches.get_flag("no-max") {
+            Some(None)
+        } else {
+            matches.get_one::<u64>("max").map(|max| Some(*max))
+        };
+        scale.remove_old = if matches.get_flag("remove-old") {
+            matches.get_one::<Duration>("timeout").copied()
+        } else {
+            None
+        };
+        Ok(())
+    }
+}
+
+/// Returns the UUID and model of the single active configuration of the Formation Instance `name`
+/// containing `flight`, only looking at the configuration starting with `prefix` if given
+fn configuration_of_flight(
+    req: &mut FormationsReq,
+    name: &str,
+    weights: &TrafficWeights,
+    flight: &str,
+    prefix: Option<&str>,
+) -> Result<(Uuid, FormationConfigurationModel)> {
+    let uuids: Vec<Uuid> = match prefix {
+        Some(prefix) => {
+            vec![uuid_of_prefix(weights.iter().map(|tw| &tw.configuration_id), prefix)?]
+        }
+        None => weights.iter().map(|tw| tw.configuration_id).collect(),
+    };
+
+    let mut found = Vec::new();
+    for uuid in uuids {
+        let model = req.get_configuration(uuid)?;
+        if model.flights().iter().any(|f| f.name() == flight) {
+            found.push((uuid, model));
+        }
+    }
+
+    match found.len() {
+        0 => Err(CliErrorKind::NoMatchingItem(flight.to_owned())
+            .into_err()
+            .context("(hint: scale one of the Flights listed by '")
+            .with_color_context(|| (Color::Green, format!("seaplane formation status {name}")))
+            .context("')\n")),
+        1 => Ok(found.remove(0)),
+        _ => Err(CliErrorKind::AmbiguousItem(flight.to_owned())
+            .into_err()
+            .context("(hint: the Flight is in more than one active configuration, pick one with '")
+            .color_context(Color::Yellow, "--configuration")
+            .context("')\n")),
+    }
+}
@@ -1,15 +1,22 @@
+use std::{
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use clap::{value_parser, ArgMatches, Command};
 
+#[cfg(feature = "compute_api_v2")]
+use crate::api::FormationsV2Req;
 use crate::{
     api::FormationsReq,
     cli::{
         cmds::formation::SeaplaneFormationFetch,
-        validator::{validate_formation_name, validate_name_id},
+        validator::{validate_formation_name, validate_name_id, validate_nonzero_duration},
         CliCommand,
     },
     error::Result,
-    ops::formation::FormationStatus,
-    printer::{Output, Pb},
+    ops::formation::{ComputeApiVersion, FormationStatus, StatusWatch},
+    printer::{stdout_can_redraw, Output, Pb},
     Ctx, OutputFormat,
 };
 
@@ -17,7 +24,14 @@ static LONG_ABOUT: &str = "Show the status of a remote Formation Instance
 
 This command will display the status of one or more Formation Instances such as how many actual
 containers are running compared to the minimum and maximums per Flight Plan that the configuration
-defines.";
+defines.
+
+Formation Instances of the v2 Compute API instead report the health of each Flight.
+
+With --watch the status is refreshed every --interval until interrupted with Ctrl+C. When the
+output is a terminal the status is redrawn in place, any counts which changed since the previous
+refresh are highlighted, and each Flight shows how long ago it last changed. Otherwise, or when
+color is disabled, each refresh is printed after the previous one.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationStatus;
@@ -39,6 +53,13 @@ impl SeaplaneFormationStatus {
                     .help("Change the output format"),
             )
             .arg(arg!(--("no-fetch")).help("Skip fetching and synchronizing of remote instances"))
+            .arg(arg!(--watch).help("Keep refreshing the status until interrupted"))
+            .arg(
+                arg!(--interval =["DURATION"=>"5s"])
+                    .requires("watch")
+                    .value_parser(validate_nonzero_duration)
+                    .help("How often to refresh with --watch, such as '5s' or '1m'"),
+            )
     }
 }
 
@@ -60,45 +81,32 @@ impl CliCommand for SeaplaneFormationStatus {
             ctx.args.stateless = old_stateless;
         }
 
-        let pb = Pb::new(ctx);
-
-        let names = if let Some(name) = ctx.args.name_id.as_deref() {
-            vec![name]
-        } else {
-            ctx.db.formations.remote_names()
+        let interval = match ctx.formation_ctx.get_or_init().watch {
+            Some(interval) => interval,
+            None => {
+                let statuses = statuses(ctx)?;
+                return match ctx.args.out_format {
+                    OutputFormat::Json => statuses.print_json(ctx),
+                    OutputFormat::Table => statuses.print_table(ctx),
+                };
+            }
         };
 
-        let mut statuses: Vec<FormationStatus> = Vec::new();
-
-        let mut req = FormationsReq::new_delay_token(ctx)?;
-        for name in names {
-            pb.set_message(format!("Gathering {name} container info..."));
-            req.set_name(name)?;
-            let mut f_status = FormationStatus::new(name);
-            for container in req.get_containers()?.iter() {
-                if let Some(cfg) = ctx
-                    .db
-                    .formations
-                    .get_configuration_by_uuid(container.configuration_id)
-                {
-                    if let Some(flight) = cfg.get_flight(&container.flight_name) {
-                        f_status.add_container(container, flight.minimum(), flight.maximum());
-                    }
+        let mut watch = StatusWatch::new(stdout_can_redraw());
+        loop {
+            let statuses = statuses(ctx)?;
+            match ctx.args.out_format {
+                OutputFormat::Json => statuses.print_json(ctx)?,
+                OutputFormat::Table => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or_default();
+                    watch.print(&statuses, interval.as_secs(), now);
                 }
             }
-            // TODO it stinks that we have to do this here and it's not automatic
-            f_status.update_status();
-            statuses.push(f_status);
-        }
-
-        pb.finish_and_clear();
-
-        match ctx.args.out_format {
-            OutputFormat::Json => statuses.print_json(ctx)?,
-            OutputFormat::Table => statuses.print_table(ctx)?,
+            thread::sleep(interval);
         }
-
-        Ok(())
     }
 
     fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
@@ -107,6 +115,64 @@ impl CliCommand for SeaplaneFormationStatus {
             .get_one::<String>("formation")
             .map(ToOwned::to_owned);
         ctx.args.fetch = !matches.get_flag("no-fetch");
+        ctx.formation_ctx.get_mut_or_init().watch = if matches.get_flag("watch") {
+            matches.get_one::<Duration>("interval").copied()
+        } else {
+            None
+        };
         Ok(())
     }
 }
+
+/// Gathers the status of the Formation Instance given on the command line, or of every remote
+/// Formation Instance
+fn statuses(ctx: &Ctx) -> Result<Vec<FormationStatus>> {
+    let pb = Pb::new(ctx);
+
+    let names = if let Some(name) = ctx.args.name_id.as_deref() {
+        vec![name]
+    } else {
+        ctx.db.formations.remote_names()
+    };
+
+    let mut statuses: Vec<FormationStatus> = Vec::new();
+
+    let mut req = FormationsReq::new_delay_token(ctx)?;
+    #[cfg(feature = "compute_api_v2")]
+    let mut req_v2 = FormationsV2Req::new_delay_token(ctx)?;
+    for name in names {
+        let api_version = ctx.db.formations.api_version_of(name);
+        if api_version == ComputeApiVersion::V2 {
+            api_version.ensure_supported(name)?;
+            #[cfg(feature = "compute_api_v2")]
+            {
+                pb.set_message(format!("Gathering {name} Flight health..."));
+                req_v2.set_name(name)?;
+                statuses.push(FormationStatus::from_v2(req_v2.status()?));
+                continue;
+            }
+        }
+
+        pb.set_message(format!("Gathering {name} container info..."));
+        req.set_name(name)?;
+        let mut f_status = FormationStatus::new(name);
+        for container in req.get_containers()?.iter() {
+            if let Some(cfg) = ctx
+                .db
+                .formations
+                .get_configuration_by_uuid(container.configuration_id)
+            {
+                if let Some(flight) = cfg.get_flight(&container.flight_name) {
+                    f_status.add_container(container, flight.minimum(), flight.maximum());
+                }
+            }
+        }
+        // TODO it stinks that we have to do this here and it's not automatic
+        f_status.update_status();
+        statuses.push(f_status);
+    }
+
+    pb.finish_and_clear();
+
+    Ok(statuses)
+}
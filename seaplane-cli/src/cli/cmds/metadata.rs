@@ -1,17 +1,23 @@
 mod common;
 mod delete;
+mod diff;
+mod edit;
 mod get;
 mod list;
 mod set;
+mod watch;
 
 use clap::{value_parser, ArgMatches, Command};
 
 pub use self::{
     common::SeaplaneMetadataCommonArgMatches,
     delete::SeaplaneMetadataDelete,
+    diff::SeaplaneMetadataDiff,
+    edit::SeaplaneMetadataEdit,
     get::SeaplaneMetadataGet,
     list::SeaplaneMetadataList,
     set::{SeaplaneMetadataSet, SeaplaneMetadataSetArgMatches},
+    watch::SeaplaneMetadataWatch,
 };
 use crate::{cli::CliCommand, printer::OutputFormat};
 
@@ -34,6 +40,9 @@ impl SeaplaneMetadata {
             .subcommand(SeaplaneMetadataSet::command())
             .subcommand(SeaplaneMetadataDelete::command())
             .subcommand(SeaplaneMetadataList::command())
+            .subcommand(SeaplaneMetadataDiff::command())
+            .subcommand(SeaplaneMetadataEdit::command())
+            .subcommand(SeaplaneMetadataWatch::command())
     }
 }
 
@@ -47,6 +56,9 @@ impl CliCommand for SeaplaneMetadata {
             Some(("set", m)) => Some((Box::new(SeaplaneMetadataSet), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneMetadataDelete), m)),
             Some(("list", m)) => Some((Box::new(SeaplaneMetadataList), m)),
+            Some(("diff", m)) => Some((Box::new(SeaplaneMetadataDiff), m)),
+            Some(("edit", m)) => Some((Box::new(SeaplaneMetadataEdit), m)),
+            Some(("watch", m)) => Some((Box::new(SeaplaneMetadataWatch), m)),
             _ => None,
         }
     }
@@ -9,6 +9,21 @@ const LONG_HUMAN_READABLE: &str = "Safely decode and truncate output for human r
 
 Implies --decode-safe --values-width-limit 256";
 
+const LONG_KEY_FILE: &str =
+    "A JSON keyring file with the data keys used to encrypt or decrypt values
+
+Values are encrypted with the 'active' key, and decrypted with whichever key they were
+encrypted with. Keys are 32 random bytes encoded as base64 (i.e. `openssl rand -base64 32`)
+and the algorithm is either 'aes-256-gcm' or 'xchacha20-poly1305'. For example:
+
+{
+  \"active\": \"2023-02\",
+  \"keys\": [
+    { \"id\": \"2023-02\", \"algorithm\": \"xchacha20-poly1305\", \"key\": \"...\" },
+    { \"id\": \"2022-08\", \"algorithm\": \"aes-256-gcm\", \"key\": \"...\" }
+  ]
+}";
+
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
 /// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
 /// arguments as `seaplane account token` even though both produce an `ArgMatches`.
@@ -51,6 +66,12 @@ pub fn base64() -> Arg {
     arg!(--base64 - ('B')).help("The keys/values are already encoded in URL safe Base64")
 }
 
+pub fn key_file() -> Arg {
+    arg!(--("key-file") = ["PATH"])
+        .help("A JSON keyring file with the data keys used to encrypt or decrypt values")
+        .long_help(LONG_KEY_FILE)
+}
+
 pub fn single_key() -> Arg {
     arg!(key =["KEY"] required ).help("The key of the metadata key-value pair")
 }
@@ -1,5 +1,5 @@
 use clap::{ArgMatches, Command};
-use serde_json::json;
+use serde_json::{json, Map};
 
 use crate::{
     api::MetadataReq,
@@ -9,6 +9,12 @@ use crate::{
     printer::OutputFormat,
 };
 
+static LONG_CHUNKS: &str =
+    "Only remove the chunks under the key(s) which don't belong to the value currently stored there
+
+Large values set with `seaplane metadata set KEY @path` are split into chunks. Chunks of an
+interrupted upload are left behind, and can be removed with this flag without touching the value.";
+
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneMetadataDelete;
 
@@ -18,17 +24,31 @@ impl SeaplaneMetadataDelete {
             .visible_aliases(["del", "remove", "rm"])
             .about("Delete one or more metadata key-value pairs")
             .args(common::args())
+            .arg(
+                arg!(--chunks)
+                    .help("Only remove the chunks under the key(s) which don't belong to the value currently stored there")
+                    .long_help(LONG_CHUNKS),
+            )
     }
 }
 
 impl CliCommand for SeaplaneMetadataDelete {
     fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        if ctx.md_ctx.get_or_init().chunks {
+            return cleanup_chunks(ctx);
+        }
+
         let mut len = 0;
         let mut req = MetadataReq::new(ctx)?;
         for kv in ctx.md_ctx.get_mut().unwrap().kvs.iter_mut() {
             let key = kv.key.to_string();
             req.set_key(key.clone())?;
-            req.delete_value()?;
+            // Chunked values are removed along with all of their chunks
+            if req.get_chunk_manifest()?.is_some() {
+                req.delete_value_chunked()?;
+            } else {
+                req.delete_value()?;
+            }
             if ctx.args.out_format == OutputFormat::Table {
                 cli_println!("Removed {key}");
             }
@@ -50,7 +70,39 @@ impl CliCommand for SeaplaneMetadataDelete {
     fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
         ctx.md_ctx
             .init(MetadataCtx::from_md_common(&SeaplaneMetadataCommonArgMatches(matches))?);
+        ctx.md_ctx.get_mut_or_init().chunks = matches.get_flag("chunks");
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
         Ok(())
     }
 }
+
+/// Removes the orphaned chunks under every key, leaving the values themselves alone
+fn cleanup_chunks(ctx: &Ctx) -> Result<()> {
+    let mut total = 0;
+    let mut removed = Map::new();
+    let mut req = MetadataReq::new(ctx)?;
+    for key in ctx.md_ctx.get_or_init().kvs.keys() {
+        let key = key.to_string();
+        req.set_key(key.clone())?;
+        let len = req.cleanup_chunks()?;
+        if ctx.args.out_format == OutputFormat::Table {
+            cli_println!(
+                "Removed {len} orphaned chunk{} of {key}",
+                if len == 1 { "" } else { "s" }
+            );
+        }
+        total += len;
+        removed.insert(key, len.into());
+    }
+
+    if ctx.args.out_format == OutputFormat::Table {
+        cli_println!(
+            "\nSuccessfully removed {total} orphaned chunk{}",
+            if total == 1 { "" } else { "s" }
+        );
+    } else {
+        cli_println!("{}", json!({ "removed_chunks": removed }));
+    }
+
+    Ok(())
+}
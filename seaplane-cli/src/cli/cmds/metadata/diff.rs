@@ -0,0 +1,116 @@
+use std::fs::File;
+
+use clap::{ArgMatches, Command};
+use seaplane::api::shared::v1::{Directory, RangeQueryContext};
+
+use crate::{
+    api::MetadataReq,
+    cli::{cmds::metadata::common, CliCommand},
+    context::{Ctx, MetadataCtx},
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::metadata::{KeyValues, KeyValuesDiff},
+    printer::{Color, Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Compare the metadata key-value pairs of two directories
+
+Either compare DIR against a second directory OTHER_DIR (showing what differs in OTHER_DIR), or
+compare a local snapshot previously saved with `seaplane metadata list DIR --format json` against
+the current contents of DIR by using --against.
+
+Keys are compared relative to their directory. Added and removed keys are listed, and changed
+values are displayed as a unified diff of the values decoded the same way as --decode-safe.
+
+With --exit-code the command exits with a non-zero status when any differences are found, so it
+can be used to gate a promotion in CI.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneMetadataDiff;
+
+impl SeaplaneMetadataDiff {
+    pub fn command() -> Command {
+        Command::new("diff")
+            .about("Compare two metadata directories, or a directory and a local snapshot")
+            .long_about(LONG_ABOUT)
+            .arg(arg!(dir =["DIR"] required).help("The directory to compare"))
+            .arg(
+                arg!(other_dir = ["OTHER_DIR"])
+                    .required_unless_present("against")
+                    .conflicts_with("against")
+                    .help("The directory to compare DIR against"),
+            )
+            .arg(arg!(--against - ('a') = ["PATH"]).help(
+                "A JSON snapshot (from `metadata list --format json`) to compare DIR against",
+            ))
+            .arg(
+                arg!(--("exit-code"))
+                    .help("Exit with a non-zero status when there are differences"),
+            )
+            .arg(common::base64())
+    }
+}
+
+impl CliCommand for SeaplaneMetadataDiff {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let diff = {
+            let mdctx = ctx.md_ctx.get_or_init();
+            let dir = mdctx.directory.clone().unwrap();
+
+            let mut req = MetadataReq::new(ctx)?;
+            let mut fetch_dir = |dir: &Directory| -> Result<KeyValues> {
+                let mut range = RangeQueryContext::new();
+                range.set_directory(dir.clone());
+                req.set_dir(range)?;
+                let mut kvs = KeyValues::from_model(req.get_all_pages()?);
+                kvs.strip_dir(&dir.decode())?;
+                Ok(kvs)
+            };
+
+            // When comparing two directories DIR is the "old" side, but when comparing against a
+            // snapshot the snapshot is the "old" side and DIR the "new" one
+            let (old, new) = if let Some(other) = &mdctx.other_directory {
+                (fetch_dir(&dir)?, fetch_dir(other)?)
+            } else {
+                let path = mdctx.snapshot.as_ref().unwrap();
+                let f = File::open(path)
+                    .map_err(CliError::from)
+                    .context("\n\tpath: ")
+                    .with_color_context(|| (Color::Yellow, path))?;
+                let mut kvs: KeyValues = serde_json::from_reader(f)
+                    .map_err(CliError::from)
+                    .context("\n\tsnapshot: ")
+                    .with_color_context(|| (Color::Yellow, path))?;
+                kvs.strip_dir(&dir.decode())?;
+                (kvs, fetch_dir(&dir)?)
+            };
+
+            KeyValuesDiff::new(&old, &new)?
+        };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => diff.print_json(ctx)?,
+            OutputFormat::Table => diff.print_table(ctx)?,
+        }
+
+        if ctx.md_ctx.get_or_init().exit_code && !diff.is_empty() {
+            return Err(CliErrorKind::OneOff("the key-value pairs differ".into()).into_err());
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.md_ctx.init(MetadataCtx::default());
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let mdctx = ctx.md_ctx.get_mut().unwrap();
+        mdctx.base64 = matches.get_flag("base64");
+        mdctx.directory = maybe_base64_arg!(matches, "dir", matches.get_flag("base64"))
+            .map(Directory::from_encoded);
+        mdctx.other_directory = maybe_base64_arg!(matches, "other_dir", matches.get_flag("base64"))
+            .map(Directory::from_encoded);
+        mdctx.snapshot = matches.get_one::<String>("against").cloned();
+        mdctx.exit_code = matches.get_flag("exit-code");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    process::Command as ProcessCommand,
+};
+
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::MetadataReq,
+    cli::{cmds::metadata::common, CliCommand},
+    context::{Ctx, MetadataCtx},
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::metadata::{KeyValue, KeyValues},
+    printer::{Color, Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Edit a metadata value in your editor
+
+The value is fetched, decoded and written to a temporary file which is opened with the editor
+defined by $VISUAL or $EDITOR (falling back to 'vi'). Once the editor exits the value is only
+written back if it was changed.
+
+If the remote value was changed by someone else while the editor was open, you will be asked
+before it is overwritten (unless --force is used). If you decline, the edited value is kept in
+the temporary file so that no work is lost.";
+
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneMetadataEdit;
+
+impl SeaplaneMetadataEdit {
+    pub fn command() -> Command {
+        Command::new("edit")
+            .about("Edit a metadata value in $VISUAL or $EDITOR")
+            .long_about(LONG_ABOUT)
+            .arg(common::single_key())
+            .arg(common::base64())
+            .arg(
+                arg!(--force)
+                    .help("Overwrite the value even if it was changed remotely while editing"),
+            )
+    }
+}
+
+/// Returns the user's preferred editor command split into the program and its arguments
+fn editor() -> Vec<String> {
+    let cmd = env::var("VISUAL")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| env::var("EDITOR").ok().filter(|s| !s.trim().is_empty()))
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_owned());
+    cmd.split_whitespace().map(ToOwned::to_owned).collect()
+}
+
+/// Asks the user a yes/no question on STDIN, anything other than `y` or `yes` means no
+fn confirm(question: &str) -> Result<bool> {
+    cli_eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+impl CliCommand for SeaplaneMetadataEdit {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = MetadataReq::new(ctx)?;
+        let mdctx = ctx.md_ctx.get_or_init();
+        let key = mdctx.kvs.keys().next().unwrap();
+        let display_key = key.decoded_safe()?;
+        req.set_key(key.to_string())?;
+
+        let original = req.get_value()?.decode();
+
+        let mut tmp = tempfile::Builder::new().prefix("seaplane-md-").tempfile()?;
+        tmp.write_all(&original)?;
+        tmp.flush()?;
+
+        let editor = editor();
+        let status = ProcessCommand::new(&editor[0])
+            .args(&editor[1..])
+            .arg(tmp.path())
+            .status()
+            .map_err(CliError::from)
+            .context("\n\teditor: ")
+            .with_color_context(|| (Color::Yellow, editor.join(" ")))?;
+        if !status.success() {
+            return Err(CliErrorKind::EditorFailed(editor.join(" ")).into_err());
+        }
+
+        let edited = fs::read(tmp.path())?;
+        if edited == original {
+            if ctx.args.out_format == OutputFormat::Table {
+                cli_println!("No changes made to {display_key}");
+            }
+            return Ok(());
+        }
+
+        // Make sure we're not clobbering changes someone else made while the editor was open
+        if req.get_value()?.decode() != original && !ctx.args.force {
+            let overwrite = confirm(&format!(
+                "The value of '{display_key}' was changed remotely while editing, overwrite it?"
+            ))?;
+            if !overwrite {
+                let (_, path) = tmp.keep().map_err(|e| CliError::from(e.error))?;
+                return Err(CliErrorKind::OneOff(format!(
+                    "not overwriting '{display_key}', the edited value was kept in {}",
+                    path.display()
+                ))
+                .into_err());
+            }
+        }
+
+        req.put_value_unencoded(&edited)?;
+
+        match ctx.args.out_format {
+            OutputFormat::Table => cli_println!("Success"),
+            OutputFormat::Json => {
+                let engine = ::base64::engine::fast_portable::FastPortable::from(
+                    &::base64::alphabet::URL_SAFE,
+                    ::base64::engine::fast_portable::NO_PAD,
+                );
+                let mut kvs = KeyValues::default();
+                kvs.push(KeyValue::new(key.to_string(), base64::encode_engine(&edited, &engine)));
+                kvs.print_json(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.md_ctx
+            .init(MetadataCtx::from_md_common(&common::SeaplaneMetadataCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.args.force = matches.get_flag("force");
+        Ok(())
+    }
+}
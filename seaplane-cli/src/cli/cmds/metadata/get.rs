@@ -1,4 +1,7 @@
+use std::fs;
+
 use clap::{ArgMatches, Command};
+use seaplane::api::metadata::v1::{is_sealed, ChunkManifest, Value};
 
 use crate::{
     api::MetadataReq,
@@ -11,7 +14,14 @@ use crate::{
 static LONG_ABOUT: &str = "Retrieve a metadata key-value pair
 
 Keys and values will be displayed in base64 encoded format by default because they may contain
-arbitrary binary data. Use --decode to output the decoded values instead.";
+arbitrary binary data. Use --decode to output the decoded values instead.
+
+Use --output-file to write the decoded value to a file instead. Values which were stored in chunks
+(such as large files set with `seaplane metadata set KEY @path`) are reassembled and verified when
+written to a file, otherwise only their manifest is displayed.
+
+Values encrypted with `seaplane metadata set --encrypt` are decrypted when --key-file is used,
+otherwise they are displayed in their encrypted form.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneMetadataGet;
@@ -26,6 +36,10 @@ impl SeaplaneMetadataGet {
             .arg(common::single_key())
             .arg(common::base64())
             .args(common::display_args())
+            .arg(common::key_file())
+            .arg(arg!(--("output-file") - ('o') = ["PATH"]).help(
+                "Write the decoded value to PATH, reassembling it if it was stored in chunks",
+            ))
             .group(common::keys_or_values())
             .mut_arg("no-header", |a| a.hide(true))
             .mut_arg("only-keys", |a| a.hide(true))
@@ -38,12 +52,34 @@ impl CliCommand for SeaplaneMetadataGet {
         let kvs = {
             let mut req = MetadataReq::new(ctx)?;
             let mdctx = ctx.md_ctx.get_mut_or_init();
+            let keyring = mdctx.keyring()?;
             for kv in mdctx.kvs.iter_mut() {
                 req.set_key(kv.key.to_string())?;
-                kv.set_value(
-                    // The key is already in Base64 so no need to convert
-                    req.get_value()?.to_string(),
-                );
+                let mut value = req.get_value()?;
+                if let Some(keyring) = &keyring {
+                    let bytes = value.decode();
+                    if is_sealed(&bytes) {
+                        value = Value::from_unencoded(keyring.open(&bytes)?);
+                    }
+                }
+                if let Some(path) = &mdctx.output_file {
+                    let size = if ChunkManifest::from_value(&value).is_some() {
+                        req.get_value_chunked(path)?.size
+                    } else {
+                        let decoded = value.decode();
+                        fs::write(path, &decoded)?;
+                        decoded.len() as u64
+                    };
+                    if ctx.args.out_format == OutputFormat::Table {
+                        cli_println!("Wrote {size} bytes to {path}");
+                    }
+                }
+                // The key is already in Base64 so no need to convert
+                kv.set_value(value.to_string());
+            }
+
+            if mdctx.output_file.is_some() && ctx.args.out_format == OutputFormat::Table {
+                return Ok(());
             }
 
             mdctx.kvs.clone()
@@ -62,6 +98,8 @@ impl CliCommand for SeaplaneMetadataGet {
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
         let mut mdctx = ctx.md_ctx.get_mut_or_init();
         mdctx.decode = matches.get_flag("decode");
+        mdctx.output_file = matches.get_one::<String>("output-file").cloned();
+        mdctx.key_file = matches.get_one::<String>("key-file").cloned();
         mdctx.no_header = true;
         mdctx.no_keys = true;
         mdctx.no_values = false;
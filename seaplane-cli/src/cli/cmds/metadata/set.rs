@@ -9,6 +9,19 @@ use crate::{
     printer::{Output, OutputFormat},
 };
 
+static LONG_VALUE: &str =
+    "The value (@path will load the value from a path and @- will load the value from STDIN)
+
+Files loaded with @path which are too large to be stored as a single value are split into chunks
+and streamed into the store. The key will then hold a small manifest describing the chunks, use
+`seaplane metadata get KEY --output-file PATH` to retrieve the full value.";
+
+static LONG_ENCRYPT: &str = "Encrypt the value with the active key of --key-file before storing it
+
+Only the encrypted value ever leaves this machine. Encrypted values are decrypted again by
+`seaplane metadata get KEY --key-file PATH`. Values are always encrypted as a whole, so large
+files are not split into chunks.";
+
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
 /// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
 /// arguments as `seaplane account token` even though both produce an `ArgMatches`.
@@ -25,7 +38,15 @@ impl SeaplaneMetadataSet {
             .about("Set a metadata key-value pair")
             .arg(common::base64())
             .arg(arg!(key =["KEY"] required ).help("The key to set"))
-            .arg(arg!(value =["VALUE"] required ).help("The value (@path will load the value from a path and @- will load the value from STDIN)"))
+            .arg(arg!(value =["VALUE"] required ).help("The value (@path will load the value from a path and @- will load the value from STDIN)").long_help(LONG_VALUE))
+            .arg(
+                arg!(--encrypt - ('e'))
+                    .requires("key-file")
+                    .help("Encrypt the value with the active key of --key-file before storing it")
+                    .long_help(LONG_ENCRYPT),
+            )
+            // Without --encrypt the key file would be silently ignored and the value stored as is
+            .arg(common::key_file().requires("encrypt"))
     }
 }
 
@@ -33,11 +54,37 @@ impl CliCommand for SeaplaneMetadataSet {
     fn run(&self, ctx: &mut Ctx) -> Result<()> {
         let mut req = MetadataReq::new(ctx)?;
         let mdctx = ctx.md_ctx.get_mut_or_init();
+        let keyring = if mdctx.encrypt { mdctx.keyring()? } else { None };
         for kv in mdctx.kvs.iter_mut() {
             let key = kv.key.to_string();
             let value = kv.value.to_string();
             req.set_key(&key)?;
-            req.put_value(Value::from_encoded(value.clone()))?;
+            if let Some(path) = &mdctx.value_path {
+                let manifest = req.put_value_chunked(path)?;
+                // The key now holds the manifest rather than the value itself
+                kv.set_value(Value::from_unencoded(serde_json::to_vec(&manifest)?).to_string());
+            } else {
+                // Replacing a chunked value with a regular one would leave its chunks behind
+                let previous = req.get_chunk_manifest()?;
+                if let Some(keyring) = &keyring {
+                    let sealed =
+                        Value::from_unencoded(keyring.seal(&Value::from_encoded(value).decode())?);
+                    // Only ever display the encrypted value
+                    kv.set_value(sealed.to_string());
+                    req.put_value(sealed)?;
+                } else {
+                    req.put_value(Value::from_encoded(value))?;
+                }
+                // The new value is already stored, so this is only worth a warning
+                if let Some(previous) = previous {
+                    if let Err(e) = req.delete_chunks(&previous) {
+                        cli_warnln!(
+                            "failed to remove the chunks of the previous value of {key}: {e}"
+                        );
+                        cli_warnln!(@noprefix, "(hint: remove them with 'seaplane metadata delete --chunks {key} --base64')");
+                    }
+                }
+            }
             if ctx.args.out_format == OutputFormat::Table {
                 cli_println!("Success");
             }
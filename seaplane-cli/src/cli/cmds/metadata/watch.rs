@@ -0,0 +1,161 @@
+use std::{process::Command as ProcessCommand, thread, time::Duration};
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::{
+    metadata::v1::{Key, WatchEvent, WatchTarget, Watcher},
+    shared::v1::Directory,
+};
+
+use crate::{
+    api::MetadataReq,
+    cli::{cmds::metadata::common, CliCommand},
+    context::{Ctx, MetadataCtx},
+    error::{CliError, Context, Result},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Watch a metadata key or directory for changes
+
+The key (or every key under the directory when the argument ends with '/') is checked for
+changes every --interval seconds. Each change is printed as a single line of JSON with the
+'event' (added, modified or deleted) and the base64 encoded 'key' and 'value'.
+
+When --exec is used the command is run through the shell for each change instead, with the
+following environment variables set:
+
+    SEAPLANE_EVENT        added, modified or deleted
+    SEAPLANE_KEY          The base64 encoded key
+    SEAPLANE_KEY_DECODED  The decoded key (only if it is valid UTF-8)
+    SEAPLANE_VALUE        The base64 encoded value (not set for deleted keys)
+
+A command that fails is reported, but does not stop watching.";
+
+#[cfg(not(windows))]
+const SHELL: [&str; 2] = ["sh", "-c"];
+#[cfg(windows)]
+const SHELL: [&str; 2] = ["cmd", "/C"];
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneMetadataWatch;
+
+impl SeaplaneMetadataWatch {
+    pub fn command() -> Command {
+        Command::new("watch")
+            .about("Watch a metadata key or directory for changes")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(key =["KEY|DIR"] required)
+                    .help("The key to watch, or the directory to watch when it ends with '/'"),
+            )
+            .arg(common::base64())
+            .arg(
+                arg!(--interval - ('i') =["SECS"=>"5"])
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("The number of seconds between checks for changes"),
+            )
+            .arg(arg!(--exec - ('x') = ["CMD"]).help("Run CMD through the shell for each change"))
+            .arg(
+                arg!(--count - ('n') = ["NUM"])
+                    .value_parser(value_parser!(usize))
+                    .help("Stop watching after NUM changes"),
+            )
+            .arg(arg!(--initial).help("Report the keys that already exist as added when starting"))
+    }
+}
+
+impl CliCommand for SeaplaneMetadataWatch {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = MetadataReq::new(ctx)?;
+        let mdctx = ctx.md_ctx.get_or_init();
+        let target = match &mdctx.directory {
+            Some(dir) => WatchTarget::Directory(dir.clone()),
+            None => {
+                WatchTarget::Key(Key::from_encoded(mdctx.kvs.keys().next().unwrap().to_string()))
+            }
+        };
+        let mut watcher = Watcher::new(target).emit_initial(mdctx.initial);
+        let interval = Duration::from_secs(mdctx.interval);
+
+        let mut seen = 0;
+        let mut first = true;
+        loop {
+            match req.poll_watcher(&mut watcher) {
+                Ok(events) => {
+                    for event in events {
+                        match &mdctx.exec {
+                            Some(cmd) => exec(cmd, &event),
+                            None => cli_println!("{}", serde_json::to_string(&event)?),
+                        }
+                        seen += 1;
+                        if mdctx.count.map(|c| seen >= c).unwrap_or(false) {
+                            return Ok(());
+                        }
+                    }
+                }
+                // If the very first check fails something is most likely wrong with the key or
+                // credentials, after that we assume the failure is temporary
+                Err(e) if first => return Err(e),
+                Err(e) => cli_warnln!("failed to check for changes: {e}"),
+            }
+            first = false;
+            thread::sleep(interval);
+        }
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.md_ctx
+            .init(MetadataCtx::from_md_common(&common::SeaplaneMetadataCommonArgMatches(matches))?);
+        let mdctx = ctx.md_ctx.get_mut().unwrap();
+
+        // A trailing '/' on the decoded key means the whole directory is watched
+        let key = mdctx.kvs.keys().next().unwrap().decoded()?;
+        if let Some(dir) = key.strip_suffix(b"/") {
+            mdctx.directory = Some(Directory::from_unencoded(dir));
+        }
+        mdctx.interval = *matches.get_one::<u64>("interval").unwrap();
+        mdctx.exec = matches.get_one::<String>("exec").cloned();
+        mdctx.count = matches.get_one::<usize>("count").copied();
+        mdctx.initial = matches.get_flag("initial");
+        Ok(())
+    }
+}
+
+/// Runs `cmd` through the shell with the details of `event` in the environment
+fn exec(cmd: &str, event: &WatchEvent) {
+    let name = match event {
+        WatchEvent::Added { .. } => "added",
+        WatchEvent::Modified { .. } => "modified",
+        WatchEvent::Deleted { .. } => "deleted",
+    };
+    let mut command = ProcessCommand::new(SHELL[0]);
+    command
+        .args(&SHELL[1..])
+        .arg(cmd)
+        .env("SEAPLANE_EVENT", name)
+        .env("SEAPLANE_KEY", event.key().encoded())
+        .env_remove("SEAPLANE_KEY_DECODED")
+        .env_remove("SEAPLANE_VALUE");
+    if let Ok(key) = String::from_utf8(event.key().decode()) {
+        if !key.contains('\0') {
+            command.env("SEAPLANE_KEY_DECODED", key);
+        }
+    }
+    if let Some(value) = event.value() {
+        command.env("SEAPLANE_VALUE", value.encoded());
+    }
+
+    let res = command
+        .status()
+        .map_err(CliError::from)
+        .context("\n\tcommand: ")
+        .with_color_context(|| (Color::Yellow, cmd));
+    match res {
+        Ok(status) if status.success() => (),
+        Ok(status) => {
+            cli_warn!(@prefix, "'");
+            cli_warn!(@Yellow, "{cmd}");
+            cli_warnln!(@noprefix, "' failed ({status})");
+        }
+        Err(e) => e.print(),
+    }
+}
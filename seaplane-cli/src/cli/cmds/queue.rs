@@ -0,0 +1,53 @@
+mod common;
+mod list;
+mod pop;
+mod push;
+
+use clap::{value_parser, ArgMatches, Command};
+
+pub use self::{
+    common::SeaplaneQueueCommonArgMatches, list::SeaplaneQueueList, pop::SeaplaneQueuePop,
+    push::SeaplaneQueuePush,
+};
+use crate::{cli::CliCommand, printer::OutputFormat};
+
+static LONG_ABOUT: &str = "Operate on distributed work queues
+
+A queue is a metadata directory where each key-value pair is a task. Tasks are handed out in
+roughly the order they were pushed. Popping a task claims it by acquiring a lock named after the
+task, which hides it from other consumers until the lock's TTL expires or the task is completed.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneQueue;
+
+impl SeaplaneQueue {
+    pub fn command() -> Command {
+        Command::new("queue")
+            .about("Operate on distributed work queues")
+            .long_about(LONG_ABOUT)
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .arg(
+                arg!(--format =["FORMAT"=>"table"] global)
+                    .help("Change the output format")
+                    .value_parser(value_parser!(OutputFormat)),
+            )
+            .subcommand(SeaplaneQueuePush::command())
+            .subcommand(SeaplaneQueuePop::command())
+            .subcommand(SeaplaneQueueList::command())
+    }
+}
+
+impl CliCommand for SeaplaneQueue {
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match &matches.subcommand() {
+            Some(("push", m)) => Some((Box::new(SeaplaneQueuePush), m)),
+            Some(("pop", m)) => Some((Box::new(SeaplaneQueuePop), m)),
+            Some(("list", m)) => Some((Box::new(SeaplaneQueueList), m)),
+            _ => None,
+        }
+    }
+}
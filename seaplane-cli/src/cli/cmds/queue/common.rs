@@ -0,0 +1,12 @@
+use clap::{Arg, ArgMatches};
+
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
+/// values of arguments were used or not. i.e. `seaplane queue push` may not have the same
+/// arguments as `seaplane account token` even though both produce an `ArgMatches`.
+#[allow(missing_debug_implementations)]
+pub struct SeaplaneQueueCommonArgMatches<'a>(pub &'a ArgMatches);
+
+pub fn queue() -> Arg {
+    arg!(queue =["QUEUE"] required)
+        .help("The name of the queue (the metadata directory of its tasks)")
+}
@@ -0,0 +1,52 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::build_queue,
+    cli::{
+        cmds::queue::{common, SeaplaneQueueCommonArgMatches},
+        CliCommand,
+    },
+    context::{Ctx, QueueCtx},
+    error::Result,
+    ops::queue::Tasks,
+    printer::{Output, OutputFormat},
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneQueueList;
+
+impl SeaplaneQueueList {
+    pub fn command() -> Command {
+        Command::new("list")
+            .visible_alias("ls")
+            .about("List the tasks in a queue, including those that are claimed")
+            .arg(common::queue())
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the heading when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneQueueList {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let directory = ctx.queue_ctx.get_or_init().directory.clone().unwrap();
+        let queue = build_queue(ctx, directory.clone())?;
+        let tasks = Tasks::new(&directory, queue.list()?);
+
+        match ctx.args.out_format {
+            OutputFormat::Json => tasks.print_json(ctx)?,
+            OutputFormat::Table => tasks.print_table(ctx)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.queue_ctx
+            .init(QueueCtx::from_queue_common(&SeaplaneQueueCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.queue_ctx.get_mut().unwrap().no_header = matches.get_flag("no-header");
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use clap::{value_parser, ArgMatches, Command};
+use serde_json::json;
+
+use crate::{
+    api::build_queue,
+    cli::{
+        cmds::queue::{common, SeaplaneQueueCommonArgMatches},
+        CliCommand,
+    },
+    context::{Ctx, QueueCtx},
+    error::{CliErrorKind, Result},
+    ops::queue::task_id,
+    printer::OutputFormat,
+};
+
+static LONG_ABOUT: &str = "Take the next task from a queue and print its value
+
+By default the task is removed from the queue as soon as it is printed. With --keep the task is
+only claimed: it is hidden from other consumers for --ttl seconds, after which it is handed out
+again unless it was deleted from the queue (i.e. with 'seaplane metadata delete') in the meantime.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneQueuePop;
+
+impl SeaplaneQueuePop {
+    pub fn command() -> Command {
+        Command::new("pop")
+            .about("Take the next task from a queue and print its value")
+            .long_about(LONG_ABOUT)
+            .arg(common::queue())
+            .arg(
+                arg!(--ttl - ('T') =["SECS"=>"30"])
+                    .value_parser(value_parser!(u32).range(1..))
+                    .help("How long in seconds a kept task is hidden from other consumers"),
+            )
+            .arg(arg!(--keep).help("Leave the task in the queue, claimed for --ttl seconds"))
+    }
+}
+
+impl CliCommand for SeaplaneQueuePop {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let (directory, ttl, keep) = {
+            let queue_ctx = ctx.queue_ctx.get_or_init();
+            (queue_ctx.directory.clone().unwrap(), queue_ctx.ttl, queue_ctx.keep)
+        };
+        let queue = build_queue(ctx, directory.clone())?;
+        let task = match queue.pop(ttl)? {
+            Some(task) => task,
+            None => {
+                return Err(CliErrorKind::OneOff(format!(
+                    "there are no unclaimed tasks in queue '{}'",
+                    String::from_utf8_lossy(&directory.decode())
+                ))
+                .into_err())
+            }
+        };
+
+        let id = task_id(&directory, task.key());
+        let value = String::from_utf8_lossy(&task.value().decode()).into_owned();
+        let lock_id = task.lock().id().encoded().to_owned();
+        if !keep {
+            queue.complete(task)?;
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => {
+                if keep {
+                    cli_println!("{}", json!({ "id": id, "value": value, "lock_id": lock_id }))
+                } else {
+                    cli_println!("{}", json!({ "id": id, "value": value }))
+                }
+            }
+            OutputFormat::Table => {
+                cli_println!("{value}");
+                if keep {
+                    cli_eprint!("(hint: task '{id}' is claimed for {ttl} seconds by lock ID '");
+                    cli_eprint!(@Green, "{lock_id}");
+                    cli_eprintln!("')");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.queue_ctx
+            .init(QueueCtx::from_queue_common(&SeaplaneQueueCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let queue_ctx = ctx.queue_ctx.get_mut().unwrap();
+        queue_ctx.ttl = *matches.get_one::<u32>("ttl").unwrap();
+        queue_ctx.keep = matches.get_flag("keep");
+        Ok(())
+    }
+}
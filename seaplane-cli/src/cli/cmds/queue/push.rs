@@ -0,0 +1,52 @@
+use clap::{ArgMatches, Command};
+use serde_json::json;
+
+use crate::{
+    api::build_queue,
+    cli::{
+        cmds::queue::{common, SeaplaneQueueCommonArgMatches},
+        CliCommand,
+    },
+    context::{Ctx, QueueCtx},
+    error::Result,
+    ops::queue::task_id,
+    printer::OutputFormat,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneQueuePush;
+
+impl SeaplaneQueuePush {
+    pub fn command() -> Command {
+        Command::new("push")
+            .about("Add a task to the end of a queue")
+            .arg(common::queue())
+            .arg(arg!(value =["VALUE"] required).help("The value of the task"))
+    }
+}
+
+impl CliCommand for SeaplaneQueuePush {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let (directory, value) = {
+            let queue_ctx = ctx.queue_ctx.get_or_init();
+            (queue_ctx.directory.clone().unwrap(), queue_ctx.value.clone().unwrap())
+        };
+        let queue = build_queue(ctx, directory.clone())?;
+        let id = task_id(&directory, &queue.push(value)?);
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", json!({ "id": id })),
+            OutputFormat::Table => cli_println!("{id}"),
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.queue_ctx
+            .init(QueueCtx::from_queue_common(&SeaplaneQueueCommonArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.queue_ctx.get_mut().unwrap().value = matches.get_one::<String>("value").cloned();
+        Ok(())
+    }
+}
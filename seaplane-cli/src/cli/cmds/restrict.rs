@@ -1,16 +1,24 @@
+mod apply;
+mod check;
 pub mod common;
 mod delete;
+mod export;
 mod get;
 mod list;
+mod plan;
 mod set;
 
 use clap::{ArgMatches, Command};
 
 pub use self::{
+    apply::SeaplaneRestrictApply,
+    check::SeaplaneRestrictCheck,
     common::SeaplaneRestrictCommonArgMatches,
     delete::SeaplaneRestrictDelete,
+    export::SeaplaneRestrictExport,
     get::SeaplaneRestrictGet,
     list::{SeaplaneRestrictList, SeaplaneRestrictListArgMatches},
+    plan::SeaplaneRestrictPlan,
     set::{SeaplaneRestrictSet, SeaplaneRestrictSetArgMatches},
 };
 use crate::cli::CliCommand;
@@ -28,6 +36,10 @@ impl SeaplaneRestrict {
             .subcommand(SeaplaneRestrictList::command())
             .subcommand(SeaplaneRestrictSet::command())
             .subcommand(SeaplaneRestrictDelete::command())
+            .subcommand(SeaplaneRestrictPlan::command())
+            .subcommand(SeaplaneRestrictApply::command())
+            .subcommand(SeaplaneRestrictExport::command())
+            .subcommand(SeaplaneRestrictCheck::command())
     }
 }
 
@@ -41,6 +53,10 @@ impl CliCommand for SeaplaneRestrict {
             Some(("list", m)) => Some((Box::new(SeaplaneRestrictList), m)),
             Some(("set", m)) => Some((Box::new(SeaplaneRestrictSet), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneRestrictDelete), m)),
+            Some(("plan", m)) => Some((Box::new(SeaplaneRestrictPlan), m)),
+            Some(("apply", m)) => Some((Box::new(SeaplaneRestrictApply), m)),
+            Some(("export", m)) => Some((Box::new(SeaplaneRestrictExport), m)),
+            Some(("check", m)) => Some((Box::new(SeaplaneRestrictCheck), m)),
             _ => None,
         }
     }
@@ -0,0 +1,82 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RestrictReq,
+    cli::{
+        cmds::restrict::{common, SeaplaneRestrictPlan},
+        CliCommand,
+    },
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    ops::restrict::policy::PlanAction,
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Create, update or delete restrictions to match a policy file
+
+See 'seaplane restrict plan --help' for the format of policy files. Existing restrictions that are
+not in the policy file are left alone unless --prune is used.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictApply;
+
+impl SeaplaneRestrictApply {
+    pub fn command() -> Command {
+        Command::new("apply")
+            .about("Create, update or delete restrictions to match a policy file")
+            .long_about(LONG_ABOUT)
+            .args(common::policy_args())
+            .args(common::display_args())
+            .mut_arg("no-header", |a| a.hide(true))
+    }
+}
+
+impl CliCommand for SeaplaneRestrictApply {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let plan = SeaplaneRestrictPlan::plan(ctx)?;
+        let decode = ctx.restrict_ctx.get_or_init().decode;
+
+        let mut req = RestrictReq::new(ctx)?;
+        for change in &plan.changes {
+            let api = change.api.to_string().to_lowercase();
+            req.set_api(&api)?;
+            req.set_directory(change.directory.to_string())?;
+            match change.action {
+                PlanAction::Create | PlanAction::Update => {
+                    req.set_restriction(change.details.clone())?
+                }
+                PlanAction::Delete => req.delete_restriction()?,
+            }
+
+            if ctx.args.out_format == OutputFormat::Table {
+                let dir = if decode {
+                    String::from_utf8_lossy(&change.directory.decode()).into_owned()
+                } else {
+                    change.directory.to_string()
+                };
+                let done = match change.action {
+                    PlanAction::Create => "Created",
+                    PlanAction::Update => "Updated",
+                    PlanAction::Delete => "Deleted",
+                };
+                cli_println!("{done} a restriction on directory {dir} in {api} API");
+            }
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => plan.print_json(ctx)?,
+            OutputFormat::Table if plan.is_empty() => plan.print_table(ctx)?,
+            OutputFormat::Table => (),
+        }
+        plan.hint_unmanaged();
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx
+            .init(RestrictCtx::from_restrict_policy(matches));
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::{api::ApiErrorKind, error::SeaplaneError};
+use serde_json::json;
+
+use crate::{
+    api::RestrictReq,
+    cli::{
+        cmds::restrict::{
+            common::{self, Provider, Region},
+            SeaplaneRestrictCommonArgMatches,
+        },
+        specs::region_spec,
+        CliCommand,
+    },
+    context::{Ctx, RestrictCtx},
+    error::{CliErrorKind, Result},
+    ops::EncodedString,
+    printer::OutputFormat,
+};
+
+static LONG_ABOUT: &str =
+    "Check whether a restriction allows placing data with a provider in a region
+
+Providers and regions are checked separately, and a placement is only allowed if both are. A
+provider (or region) is allowed unless it is denied, or some providers (or regions) are explicitly
+allowed and it isn't one of them. A directory without a restriction allows any placement.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictCheck;
+
+impl SeaplaneRestrictCheck {
+    pub fn command() -> Command {
+        Command::new("check")
+            .about("Check whether a restriction allows placing data with a provider in a region")
+            .long_about(LONG_ABOUT)
+            .arg(common::api())
+            .arg(common::directory())
+            .arg(common::base64())
+            .arg(
+                arg!(--provider =["PROVIDER"] required ignore_case)
+                    .help("The provider of the placement")
+                    .value_parser(value_parser!(Provider)),
+            )
+            .arg(
+                arg!(--region =["REGION"] required ignore_case)
+                    .help("The region of the placement (See REGION SPEC below)")
+                    .value_parser(value_parser!(Region)),
+            )
+            .args(common::display_args())
+            .mut_arg("no-header", |a| a.hide(true))
+            .after_help(region_spec(false))
+            .after_long_help(region_spec(true))
+    }
+}
+
+impl CliCommand for SeaplaneRestrictCheck {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = RestrictReq::new(ctx)?;
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
+        let api = restrict_ctx.api.as_ref().unwrap();
+        let mut dir = restrict_ctx.directory.as_ref().unwrap().to_string();
+        let provider = restrict_ctx.provider.as_ref().unwrap();
+        let region = restrict_ctx.region.as_ref().unwrap();
+
+        req.set_api(api)?;
+        req.set_directory(&dir)?;
+        let details = match req.get_restriction() {
+            Ok(restriction) => Some(restriction.details),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
+                    if ae.kind == ApiErrorKind::NotFound) =>
+            {
+                None
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut reasons = Vec::new();
+        if let Some(details) = &details {
+            if details.providers_denied.contains(provider) {
+                reasons.push(format!("provider {provider} is denied"));
+            } else if !details.permits_provider(provider) {
+                reasons.push(format!("provider {provider} is not one of the allowed providers"));
+            }
+            if details.regions_denied.contains(region) {
+                reasons.push(format!("region {region} is denied"));
+            } else if !details.permits_region(region) {
+                reasons.push(format!("region {region} is not one of the allowed regions"));
+            }
+        }
+        let permitted = reasons.is_empty();
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!(
+                "{}",
+                json!({
+                    "api": api,
+                    "directory": dir,
+                    "provider": provider,
+                    "region": region,
+                    "restricted": details.is_some(),
+                    "permitted": permitted,
+                    "reasons": reasons,
+                })
+            ),
+            OutputFormat::Table => {
+                if restrict_ctx.decode {
+                    let es = EncodedString::new(dir);
+                    dir = String::from_utf8_lossy(&es.decoded()?).to_string()
+                };
+                if details.is_none() {
+                    cli_println!(
+                        "There is no restriction on directory {dir} in {api} API, placement with \
+                         {provider} in {region} is allowed"
+                    );
+                } else if permitted {
+                    cli_print!("Placement with {provider} in {region} is ");
+                    cli_print!(@Green, "allowed");
+                    cli_println!(" by the restriction on directory {dir} in {api} API");
+                } else {
+                    cli_print!("Placement with {provider} in {region} is ");
+                    cli_print!(@Red, "not allowed");
+                    cli_println!(" by the restriction on directory {dir} in {api} API:");
+                    for reason in &reasons {
+                        cli_println!("  - {reason}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        let mut restrict_ctx =
+            RestrictCtx::from_restrict_common(&SeaplaneRestrictCommonArgMatches(matches))?;
+        let provider = matches.get_one::<Provider>("provider").unwrap();
+        restrict_ctx.provider = Some(provider.into_model().ok_or_else(|| {
+            CliErrorKind::InvalidCliValue(Some("provider"), "all".into()).into_err()
+        })?);
+        let region = matches.get_one::<Region>("region").unwrap();
+        restrict_ctx.region = Some(region.into_model().ok_or_else(|| {
+            CliErrorKind::InvalidCliValue(Some("region"), "all".into()).into_err()
+        })?);
+        restrict_ctx.decode = matches.get_flag("decode");
+        ctx.restrict_ctx.init(restrict_ctx);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
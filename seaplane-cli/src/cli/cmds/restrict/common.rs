@@ -1,7 +1,28 @@
+use std::time::{Duration, Instant};
+
 use clap::{builder::PossibleValue, value_parser, Arg, ArgMatches};
-use seaplane::api::shared::v1::{Provider as ProviderModel, Region as RegionModel};
+use seaplane::{
+    api::{restrict::v1::Restriction, shared::v1::Provider as ProviderModel},
+    error::SeaplaneError,
+};
+
+pub use crate::cli::Region;
+use crate::{
+    api::RestrictReq,
+    context::Ctx,
+    error::{CliErrorKind, Context, Result as CliResult},
+    ops::preset::LONG_PRESET,
+    printer::{Color, Pb},
+    OutputFormat,
+};
+
+/// How often the state of a restriction is checked while waiting for it to be enforced
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
-use crate::OutputFormat;
+static LONG_WAIT: &str = "Wait for the restriction to be enforced before exiting
+
+If the restriction is still not enforced after --timeout seconds the command fails with
+the exit status 124.";
 
 const LONG_DECODE: &str = "Decode the directories before printing them
 
@@ -100,149 +121,6 @@ impl<'a> Into<ProviderModel> for &'a Provider {
     }
 }
 
-// TODO: @needs-decision @pre-1.0 we need to come back and address how many aliases there are and
-// their sort order for the CLI's "possible values" message. They're currently "grouped" in region
-// aliases, but for one it's too many values, also it makes the sort look wild in the help message.
-// The Compute API only uses the XA, XC, XE values, but those are the least user friendly.
-/// We provide a shim between the Seaplane Region so we can do some additional UX work
-#[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumString)]
-#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
-pub enum Region {
-    XA,
-    Asia,
-    XC,
-    PRC,
-    PeoplesRepublicOfChina,
-    XE,
-    Europe,
-    EU,
-    XF,
-    Africa,
-    XN,
-    NorthAmerica,
-    NAmerica,
-    XO,
-    Oceania,
-    XQ,
-    Antarctica,
-    XS,
-    SAmerica,
-    SouthAmerica,
-    XU,
-    UK,
-    UnitedKingdom,
-    All,
-}
-
-impl clap::ValueEnum for Region {
-    fn value_variants<'a>() -> &'a [Self] {
-        use Region::*;
-        &[
-            XA, // Asia,
-            XC, // PRC, PeoplesRepublicOfChina,
-            XE, // Europe, EU,
-            XF, // Africa,
-            XN, // NorthAmerica, NAmerica,
-            XO, // Oceania,
-            XQ, // Antarctica,
-            XS, // SAmerica, SouthAmerica,
-            XU, // UK, UnitedKingdom,
-            All,
-        ]
-    }
-
-    fn to_possible_value<'a>(&self) -> Option<PossibleValue> {
-        use Region::*;
-        match self {
-            XA => Some(PossibleValue::new("xa").alias("asia")),
-            Asia => None,
-            XC => Some(PossibleValue::new("xc").aliases([
-                "prc",
-                "china",
-                "peoples-republic-of-china",
-                "peoples_republic_of_china",
-                "peoplesrepublicofchina",
-            ])),
-            PRC => None,
-            PeoplesRepublicOfChina => None,
-            XE => Some(PossibleValue::new("xe").aliases(["europe", "eu"])),
-            Europe => None,
-            EU => None,
-            XF => Some(PossibleValue::new("xf").alias("africa")),
-            Africa => None,
-            XN => Some(PossibleValue::new("xn").aliases([
-                "namerica",
-                "northamerica",
-                "n-america",
-                "north-america",
-                "n_america",
-                "north_america",
-            ])),
-            NorthAmerica => None,
-            NAmerica => None,
-            XO => Some(PossibleValue::new("xo").alias("oceania")),
-            Oceania => None,
-            XQ => Some(PossibleValue::new("xq").alias("antarctica")),
-            Antarctica => None,
-            XS => Some(PossibleValue::new("xs").aliases([
-                "samerica",
-                "southamerica",
-                "s-america",
-                "south-america",
-                "s_america",
-                "south_america",
-            ])),
-            SAmerica => None,
-            SouthAmerica => None,
-            XU => Some(PossibleValue::new("xu").aliases([
-                "uk",
-                "unitedkingdom",
-                "united-kingdom",
-                "united_kingdom",
-            ])),
-            UK => None,
-            UnitedKingdom => None,
-            All => Some(PossibleValue::new("all")),
-        }
-    }
-
-    fn from_str(input: &str, _ignore_case: bool) -> Result<Self, String> {
-        // Because we use strum(ascii_ignore_case) for our FromStr impl we unconditionally ignore
-        // case and can ignore clap's hint
-        input.parse().map_err(|e| format!("{e}"))
-    }
-}
-
-impl Region {
-    pub fn into_model(&self) -> Option<RegionModel> {
-        if self == &Region::All {
-            None
-        } else {
-            Some(self.into())
-        }
-    }
-}
-
-#[allow(clippy::from_over_into)]
-impl<'a> Into<RegionModel> for &'a Region {
-    fn into(self) -> RegionModel {
-        use Region::*;
-        match self {
-            XA | Asia => RegionModel::XA,
-            XC | PRC | PeoplesRepublicOfChina => RegionModel::XC,
-            XE | Europe | EU => RegionModel::XE,
-            XF | Africa => RegionModel::XF,
-            XN | NorthAmerica | NAmerica => RegionModel::XN,
-            XO | Oceania => RegionModel::XO,
-            XQ | Antarctica => RegionModel::XQ,
-            XS | SAmerica | SouthAmerica => RegionModel::XS,
-            XU | UK | UnitedKingdom => RegionModel::XU,
-            All => panic!("Region::All cannot be converted into seaplane::api::shared::v1::Region"),
-        }
-    }
-}
-
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces
 /// errors in checking if values of arguments were used or not. i.e. `seaplane
 /// formation create` may not have the same arguments as `seaplane account
@@ -270,6 +148,10 @@ pub fn display_args() -> Vec<Arg> {
 
 pub fn restriction_details() -> Vec<Arg> {
     vec![
+    arg!(--preset =["NAME"])
+    .display_order(0)
+    .help("A named placement preset to expand into provider and region values")
+    .long_help(LONG_PRESET),
     arg!(--provider|providers =["PROVIDER"=>"all"]... ignore_case)
     .display_order(1)
     .next_line_help(true)
@@ -297,6 +179,54 @@ pub fn restriction_details() -> Vec<Arg> {
     ]
 }
 
+pub fn policy_args() -> Vec<Arg> {
+    vec![
+        arg!(--file - ('f') =["PATH"] required)
+            .help("The restriction policy file (TOML, or YAML with a .yaml or .yml extension)"),
+        arg!(--prune).help("Delete existing restrictions that are not in the policy file"),
+    ]
+}
+
+pub fn wait_args() -> Vec<Arg> {
+    vec![
+        arg!(--wait)
+            .help("Wait for the restriction to be enforced before exiting")
+            .long_help(LONG_WAIT),
+        arg!(--timeout =["SECS"=>"60"])
+            .requires("wait")
+            .value_parser(value_parser!(u64).range(1..))
+            .help("How long in seconds to wait for the restriction to be enforced"),
+    ]
+}
+
+/// Polls the restriction `req` points at until it is enforced, showing the progress with a
+/// spinner. `dir` is the directory as it should be displayed.
+pub fn wait_until_enforced(ctx: &Ctx, req: &mut RestrictReq, dir: &str) -> CliResult<Restriction> {
+    let timeout = ctx.restrict_ctx.get_or_init().timeout;
+    let pb = Pb::new(ctx);
+    let start = Instant::now();
+    let res = req.wait_until_enforced_with(
+        Duration::from_secs(timeout),
+        WAIT_POLL_INTERVAL,
+        |restriction| {
+            pb.set_message(format!(
+                "Waiting for the restriction on directory {dir} to be enforced ({}, {}s)...",
+                restriction.state,
+                start.elapsed().as_secs()
+            ))
+        },
+    );
+    pb.finish_and_clear();
+
+    res.map_err(|e| match e.kind() {
+        CliErrorKind::Seaplane(SeaplaneError::Timeout(_)) => e
+            .context("(hint: the restriction may still be enforced later, check on it with '")
+            .color_context(Color::Yellow, "seaplane restrict get")
+            .context("')\n"),
+        _ => e,
+    })
+}
+
 pub fn base64() -> Arg {
     arg!(--base64 - ('B')).help("The directory is already encoded in URL safe Base64")
 }
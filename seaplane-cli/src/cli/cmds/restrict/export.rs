@@ -0,0 +1,80 @@
+use std::fs;
+
+use clap::{value_parser, ArgMatches, Command};
+
+use crate::{
+    api::RestrictReq,
+    cli::CliCommand,
+    context::{Ctx, RestrictCtx},
+    error::{CliError, Context, Result},
+    ops::restrict::policy::{Policy, PolicyFormat},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Write the existing restrictions as a policy file
+
+The policy file can be kept in version control and applied again with 'seaplane restrict apply'.
+Without --file the policy is printed to standard output.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictExport;
+
+impl SeaplaneRestrictExport {
+    pub fn command() -> Command {
+        Command::new("export")
+            .about("Write the existing restrictions as a policy file")
+            .long_about(LONG_ABOUT)
+            .arg(arg!(api = ["API"]).help("Only export the restrictions of this API"))
+            .arg(arg!(--file - ('f') =["PATH"]).help("Write the policy to this file"))
+            .arg(
+                arg!(--("policy-format") =["FORMAT"])
+                    .value_parser(value_parser!(PolicyFormat))
+                    .help("The syntax of the policy (default: guessed from the --file extension, or toml)"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneRestrictExport {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = RestrictReq::new(ctx)?;
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
+        if let Some(api) = &restrict_ctx.api {
+            req.set_api(api)?;
+        }
+        let policy = Policy::from_model(&req.get_all_pages()?);
+
+        let path = restrict_ctx.policy_file.as_ref();
+        let format = restrict_ctx.policy_format.unwrap_or_else(|| {
+            path.map(PolicyFormat::from_path)
+                .unwrap_or(PolicyFormat::Toml)
+        });
+        let contents = policy.to_string(format)?;
+
+        match path {
+            Some(path) => {
+                fs::write(path, contents)
+                    .map_err(CliError::from)
+                    .context("\n\tpath: ")
+                    .with_color_context(|| (Color::Yellow, path))?;
+                cli_println!(
+                    "Exported {} restriction{} to {path}",
+                    policy.restrictions.len(),
+                    if policy.restrictions.len() == 1 { "" } else { "s" }
+                );
+            }
+            None => cli_print!("{contents}"),
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx.init(RestrictCtx {
+            api: matches.get_one::<String>("api").cloned(),
+            policy_file: matches.get_one::<String>("file").cloned(),
+            policy_format: matches.get_one::<PolicyFormat>("policy-format").copied(),
+            ..RestrictCtx::default()
+        });
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     cli::{cmds::restrict::common, CliCommand},
     context::{Ctx, RestrictCtx},
     error::Result,
+    ops::EncodedString,
     printer::{Output, OutputFormat},
 };
 
@@ -26,6 +27,7 @@ impl SeaplaneRestrictGet {
             .arg(common::directory())
             .arg(common::base64())
             .args(common::display_args())
+            .args(common::wait_args())
     }
 }
 
@@ -33,10 +35,20 @@ impl CliCommand for SeaplaneRestrictGet {
     fn run(&self, ctx: &mut Ctx) -> Result<()> {
         let restriction = {
             let mut req = RestrictReq::new(ctx)?;
-            let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+            let restrict_ctx = ctx.restrict_ctx.get_or_init();
+            let dir = restrict_ctx.directory.as_ref().unwrap().to_string();
             req.set_api(restrict_ctx.api.as_ref().unwrap())?;
-            req.set_directory(restrict_ctx.directory.as_ref().unwrap().to_string())?;
-            req.get_restriction()?
+            req.set_directory(&dir)?;
+            if restrict_ctx.wait {
+                let display_dir = if restrict_ctx.decode {
+                    String::from_utf8_lossy(&EncodedString::new(dir).decoded()?).to_string()
+                } else {
+                    dir
+                };
+                common::wait_until_enforced(ctx, &mut req, &display_dir)?
+            } else {
+                req.get_restriction()?
+            }
         };
         match ctx.args.out_format {
             OutputFormat::Json => restriction.print_json(ctx)?,
@@ -53,6 +65,8 @@ impl CliCommand for SeaplaneRestrictGet {
         let mut restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
         restrict_ctx.decode = matches.get_flag("decode");
         restrict_ctx.no_header = matches.get_flag("no-header");
+        restrict_ctx.wait = matches.get_flag("wait");
+        restrict_ctx.timeout = *matches.get_one::<u64>("timeout").unwrap();
         Ok(())
     }
 
@@ -0,0 +1,69 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RestrictReq,
+    cli::{cmds::restrict::common, CliCommand},
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    ops::restrict::policy::{Plan, Policy},
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Show the changes needed to make the restrictions match a policy file
+
+A policy file lists every restriction that should exist, across all APIs, under the
+'restrictions' key:
+
+    [[restrictions]]
+    api = \"config\"
+    directory = \"customers/eu\"
+    regions_allowed = [\"XE\", \"XU\"]
+    providers_denied = [\"GCP\"]
+
+Directories that are not valid UTF-8 can be given as URL safe base64 with 'directory_base64'
+instead. Existing restrictions that are not in the policy file are left alone unless --prune is
+used. Use 'seaplane restrict export' to create a policy file from the existing restrictions.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictPlan;
+
+impl SeaplaneRestrictPlan {
+    pub fn command() -> Command {
+        Command::new("plan")
+            .about("Show the changes needed to make the restrictions match a policy file")
+            .long_about(LONG_ABOUT)
+            .args(common::policy_args())
+            .args(common::display_args())
+    }
+
+    /// Compares the policy file in the `RestrictCtx` against the existing restrictions
+    pub(super) fn plan(ctx: &mut Ctx) -> Result<Plan> {
+        let (policy, prune) = {
+            let restrict_ctx = ctx.restrict_ctx.get_or_init();
+            (Policy::load(restrict_ctx.policy_file.as_ref().unwrap())?, restrict_ctx.prune)
+        };
+        let mut req = RestrictReq::new(ctx)?;
+        Plan::new(&policy, req.get_all_pages()?, prune)
+    }
+}
+
+impl CliCommand for SeaplaneRestrictPlan {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let plan = Self::plan(ctx)?;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => plan.print_json(ctx)?,
+            OutputFormat::Table => plan.print_table(ctx)?,
+        }
+        plan.hint_unmanaged();
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx
+            .init(RestrictCtx::from_restrict_policy(matches));
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
@@ -3,10 +3,10 @@ use serde_json::json;
 
 use crate::{
     api::RestrictReq,
-    cli::{cmds::restrict::common, specs::REGION_SPEC, CliCommand},
+    cli::{cmds::restrict::common, specs::region_spec, CliCommand},
     context::{Ctx, RestrictCtx},
     error::Result,
-    ops::EncodedString,
+    ops::{preset::Preset, EncodedString},
     printer::OutputFormat,
 };
 
@@ -28,10 +28,12 @@ impl SeaplaneRestrictSet {
             .arg(common::directory())
             .arg(common::base64())
             .args(common::display_args())
+            .args(common::wait_args())
             .next_display_order(0)
             .next_help_heading("RESTRICTION DETAILS")
             .args(common::restriction_details())
-            .after_help(REGION_SPEC)
+            .after_help(region_spec(false))
+            .after_long_help(region_spec(true))
             .mut_arg("no-header", |a| a.hide(true))
     }
 }
@@ -39,23 +41,41 @@ impl SeaplaneRestrictSet {
 impl CliCommand for SeaplaneRestrictSet {
     fn run(&self, ctx: &mut Ctx) -> Result<()> {
         let mut req = RestrictReq::new(ctx)?;
-        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
         let api = restrict_ctx.api.as_ref().unwrap();
-        let mut dir = restrict_ctx.directory.as_ref().unwrap().to_string();
+        let dir = restrict_ctx.directory.as_ref().unwrap().to_string();
         let details = restrict_ctx.restriction_details()?;
 
         req.set_api(api)?;
         req.set_directory(&dir)?;
         req.set_restriction(details)?;
 
+        let display_dir = if restrict_ctx.decode {
+            let es = EncodedString::new(dir.clone());
+            String::from_utf8_lossy(&es.decoded()?).to_string()
+        } else {
+            dir.clone()
+        };
         if ctx.args.out_format == OutputFormat::Table {
-            if restrict_ctx.decode {
-                let es = EncodedString::new(dir);
-                dir = String::from_utf8_lossy(&es.decoded()?).to_string()
-            };
-            cli_println!("Set a restriction on directory {} in {} API", dir, api);
+            cli_println!("Set a restriction on directory {} in {} API", display_dir, api);
+        }
+
+        let state = if restrict_ctx.wait {
+            let restriction = common::wait_until_enforced(ctx, &mut req, &display_dir)?;
+            if ctx.args.out_format == OutputFormat::Table {
+                cli_println!("The restriction on directory {} is enforced", display_dir);
+            }
+            Some(restriction.state)
         } else {
-            cli_println!("{}", json!({"set_restriction": {"api": api, "directory": dir} }))
+            None
+        };
+
+        if ctx.args.out_format == OutputFormat::Json {
+            let mut set = json!({"api": api, "directory": dir});
+            if let Some(state) = state {
+                set["state"] = json!(state);
+            }
+            cli_println!("{}", json!({ "set_restriction": set }))
         }
         Ok(())
     }
@@ -64,8 +84,24 @@ impl CliCommand for SeaplaneRestrictSet {
         ctx.restrict_ctx
             .init(RestrictCtx::from_restrict_set(&SeaplaneRestrictSetArgMatches(matches))?);
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let preset = matches
+            .get_one::<String>("preset")
+            .map(|name| Preset::resolve(ctx, name))
+            .transpose()?;
         let mut restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
         restrict_ctx.decode = matches.get_flag("decode");
+        if let Some(preset) = preset {
+            restrict_ctx
+                .providers_allowed
+                .extend(preset.providers_allowed);
+            restrict_ctx
+                .providers_denied
+                .extend(preset.providers_denied);
+            restrict_ctx.regions_allowed.extend(preset.regions_allowed);
+            restrict_ctx.regions_denied.extend(preset.regions_denied);
+        }
+        restrict_ctx.wait = matches.get_flag("wait");
+        restrict_ctx.timeout = *matches.get_one::<u64>("timeout").unwrap();
         Ok(())
     }
 }
@@ -1,3 +1,7 @@
+use std::fmt::Write;
+
+use seaplane::api::shared::v1::Region as RegionModel;
+
 pub const FLIGHT_SPEC: &str = "FLIGHT SPEC
 
     The Flight may be specified in one of the following ways
@@ -14,23 +18,41 @@ pub const FLIGHT_SPEC: &str = "FLIGHT SPEC
 
     NOTE that when using @- only one Flight Plan may be provided via STDIN";
 
-pub const REGION_SPEC: &str = "REGION SPEC
+/// The REGION SPEC help section, which is built from the regions the SDK knows about. With
+/// `countries` the countries each region contains are listed too, which is meant for `--help`.
+pub fn region_spec(countries: bool) -> String {
+    let mut spec = String::from(
+        "REGION SPEC
 
     The regions are based on ISO 3166 alpha-2 continent codes with a few additions to capture
     regulatory differences along with some more intuitive or common aliases. The currently
     supported mappings are:
-
-    XA => Asia
-    XC => PRC => PeoplesRepublicofChina
-    XE => EU  => Europe
-    XF => Africa
-    XN => NAmerica => NorthAmerica
-    XO => Oceania
-    XQ => Antarctica
-    XS => SAmerica => SouthAmerica
-    XU => UK => UnitedKingdom
-
-    This list is subject to change or expand.";
+",
+    );
+
+    for region in RegionModel::KNOWN {
+        let mut names = vec![region.as_ref()];
+        names.extend(region.aliases());
+        names.push(region.name());
+        let _ = write!(spec, "\n    {}", names.join(" => "));
+    }
+
+    if countries {
+        spec.push_str(
+            "\n\n    Countries belong to the following regions by their ISO 3166-1 alpha-2 code:\n",
+        );
+        for region in RegionModel::KNOWN {
+            let codes: Vec<_> = region.countries().collect();
+            for (i, chunk) in codes.chunks(16).enumerate() {
+                let prefix = if i == 0 { region.as_ref() } else { "" };
+                let _ = write!(spec, "\n    {prefix:<4}{}", chunk.join(" "));
+            }
+        }
+    }
+
+    spec.push_str("\n\n    This list is subject to change or expand.");
+    spec
+}
 
 pub const IMAGE_SPEC: &str = r#"IMAGE SPEC
 
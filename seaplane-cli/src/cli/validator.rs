@@ -1,7 +1,7 @@
 //! Utility functions used to validate various argument types on the CLI. The arguments and return
 //! type are what the `clap::Arg::validator` expects.
 
-use std::{path::Path, result::Result as StdResult};
+use std::{path::Path, result::Result as StdResult, time::Duration};
 
 use crate::{
     context::{FlightCtx, DEFAULT_IMAGE_REGISTRY_URL},
@@ -16,6 +16,42 @@ pub fn validate_u64(s: &str) -> StdResult<u64, String> {
     }
 }
 
+/// Ensures a valid duration such as `90`, `30s`, `5m` or `1h30m`, where a plain number is in
+/// seconds
+pub fn validate_duration(s: &str) -> StdResult<Duration, String> {
+    let err = || format!("invalid duration '{s}', expected a value like '30s', '5m' or '1h30m'");
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    if s.is_empty() {
+        return Err(err());
+    }
+    let mut secs = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let unit_idx = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(err)?;
+        let n: u64 = rest[..unit_idx].parse().map_err(|_| err())?;
+        let mult = match rest.as_bytes()[unit_idx] {
+            b's' => 1,
+            b'm' => 60,
+            b'h' => 60 * 60,
+            _ => return Err(err()),
+        };
+        secs += n * mult;
+        rest = &rest[unit_idx + 1..];
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Ensures a valid duration like [`validate_duration`] which is also longer than zero
+pub fn validate_nonzero_duration(s: &str) -> StdResult<Duration, String> {
+    let d = validate_duration(s)?;
+    if d.is_zero() {
+        return Err(format!("duration '{s}' must be longer than zero"));
+    }
+    Ok(d)
+}
+
 /// Ensures a valid Endpoint
 pub fn validate_endpoint(s: &str) -> StdResult<Endpoint, String> {
     match s.parse::<Endpoint>() {
@@ -222,6 +258,17 @@ pub fn validate_id(s: &str) -> StdResult<String, &'static str> {
 
 fn is_hex_char(c: char) -> bool { matches!(c, 'a'..='f' | 'A'..='F' | '0'..='9') }
 
+/// Ensures a valid feature flag name, which is a single segment of a metadata key
+pub fn validate_flag_name(name: &str) -> StdResult<String, &'static str> {
+    if name.is_empty() {
+        return Err("flag name cannot be empty");
+    }
+    if name.contains('/') {
+        return Err("illegal character in flag name; must not contain '/'");
+    }
+    Ok(name.to_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +287,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn durations() {
+        assert_eq!(validate_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(validate_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(validate_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(validate_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert!(validate_duration("").is_err());
+        assert!(validate_duration("m").is_err());
+        assert!(validate_duration("5").is_ok());
+        assert!(validate_duration("5d").is_err());
+        assert!(validate_duration("5m3").is_err());
+        assert_eq!(validate_duration("0s"), Ok(Duration::ZERO));
+        assert!(validate_nonzero_duration("0s").is_err());
+        assert!(validate_nonzero_duration("0").is_err());
+        assert_eq!(validate_nonzero_duration("1s"), Ok(Duration::from_secs(1)));
+    }
+
     #[test]
     fn invalid_flight_names() {
         assert!(validate_flight_name("").is_err());
@@ -28,6 +28,7 @@
 //! See also the CONFIGURATION_SPEC.md in this repository
 
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -76,6 +77,10 @@ pub struct RawConfig {
 
     #[serde(default, skip_serializing_if = "RawDangerZoneConfig::is_empty")]
     pub danger_zone: RawDangerZoneConfig,
+
+    /// Named placement presets, keyed by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, RawPresetConfig>,
 }
 
 impl RawConfig {
@@ -154,6 +159,7 @@ impl RawConfig {
         {
             self.danger_zone.allow_invalid_certs = new_cfg.danger_zone.allow_invalid_certs;
         }
+        self.presets.extend(new_cfg.presets);
         self.loaded_from.extend(new_cfg.loaded_from);
         Ok(())
     }
@@ -259,6 +265,25 @@ pub struct RawDangerZoneConfig {
     pub allow_invalid_certs: bool,
 }
 
+/// A named placement preset, whose values are the same as the CLI's `--provider`, `--region`,
+/// `--exclude-provider` and `--exclude-region` values
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RawPresetConfig {
+    #[serde(default)]
+    pub providers: Vec<String>,
+
+    #[serde(default)]
+    pub exclude_providers: Vec<String>,
+
+    #[serde(default)]
+    pub regions: Vec<String>,
+
+    #[serde(default)]
+    pub exclude_regions: Vec<String>,
+}
+
 impl RawDangerZoneConfig {
     // Returns `true` if config table is all default values
     pub fn is_empty(&self) -> bool { self == &RawDangerZoneConfig::default() }
@@ -403,6 +428,32 @@ mod test {
         )
     }
 
+    #[test]
+    fn deser_presets() {
+        let cfg_str = r#"
+        [presets.gdpr]
+        regions = ["xe", "uk"]
+        exclude-providers = ["gcp"]
+        "#;
+
+        let cfg: RawConfig = toml::from_str(cfg_str).unwrap();
+
+        assert_eq!(
+            cfg,
+            RawConfig {
+                presets: BTreeMap::from([(
+                    "gdpr".into(),
+                    RawPresetConfig {
+                        regions: vec!["xe".into(), "uk".into()],
+                        exclude_providers: vec!["gcp".into()],
+                        ..Default::default()
+                    }
+                )]),
+                ..Default::default()
+            }
+        )
+    }
+
     #[cfg(feature = "allow_insecure_urls")]
     #[test]
     fn deser_insecure_urls() {
@@ -22,6 +22,8 @@
 //! many commands. It also contains specialized contexts that contain values only relevant to those
 //! commands or processes that need them. These specialized contexts should be lazily derived.
 
+pub mod flags;
+pub use flags::FlagsCtx;
 pub mod flight;
 pub use flight::FlightCtx;
 pub mod formation;
@@ -30,8 +32,13 @@ pub mod metadata;
 pub use metadata::MetadataCtx;
 pub mod locks;
 pub use locks::LocksCtx;
+pub mod queue;
+pub use queue::QueueCtx;
 pub mod restrict;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use clap_complete::Shell;
 use once_cell::unsync::OnceCell;
@@ -39,7 +46,7 @@ use reqwest::Url;
 pub use restrict::RestrictCtx;
 
 use crate::{
-    config::RawConfig,
+    config::{RawConfig, RawPresetConfig},
     error::{CliErrorKind, Context, Result},
     fs::{self, FromDisk, ToDisk},
     ops::{flight::Flights, formation::Formations},
@@ -125,6 +132,12 @@ pub struct Ctx {
     /// Context relate to exclusively to Restrict operations and commands
     pub restrict_ctx: LateInit<RestrictCtx>,
 
+    /// Context relate to exclusively to feature flag operations and commands
+    pub flags_ctx: LateInit<FlagsCtx>,
+
+    /// Context relate to exclusively to work queue operations and commands
+    pub queue_ctx: LateInit<QueueCtx>,
+
     /// Where the configuration files were loaded from
     pub conf_files: Vec<PathBuf>,
 
@@ -146,6 +159,9 @@ pub struct Ctx {
     /// The container image registry to infer if not provided
     pub registry: String,
 
+    /// The placement presets defined in the configuration files
+    pub presets: BTreeMap<String, RawPresetConfig>,
+
     /// Set the base URL for the request
     pub compute_url: Option<Url>,
     pub identity_url: Option<Url>,
@@ -194,6 +210,20 @@ impl Clone for Ctx {
             } else {
                 LateInit::default()
             },
+            flags_ctx: if self.flags_ctx.get().is_some() {
+                let li = LateInit::default();
+                li.init(self.flags_ctx.get().cloned().unwrap());
+                li
+            } else {
+                LateInit::default()
+            },
+            queue_ctx: if self.queue_ctx.get().is_some() {
+                let li = LateInit::default();
+                li.init(self.queue_ctx.get().cloned().unwrap());
+                li
+            } else {
+                LateInit::default()
+            },
             conf_files: self.conf_files.clone(),
             args: self.args.clone(),
             db: self.db.clone(),
@@ -201,6 +231,7 @@ impl Clone for Ctx {
             did_init: self.did_init,
             disable_pb: self.disable_pb,
             registry: self.registry.clone(),
+            presets: self.presets.clone(),
             compute_url: self.compute_url.clone(),
             identity_url: self.identity_url.clone(),
             metadata_url: self.metadata_url.clone(),
@@ -220,6 +251,8 @@ impl Default for Ctx {
             md_ctx: LateInit::default(),
             locks_ctx: LateInit::default(),
             restrict_ctx: LateInit::default(),
+            flags_ctx: LateInit::default(),
+            queue_ctx: LateInit::default(),
             conf_files: Vec::new(),
             args: Args::default(),
             db: Db::default(),
@@ -233,6 +266,7 @@ impl Default for Ctx {
             insecure_urls: false,
             invalid_certs: false,
             registry: DEFAULT_IMAGE_REGISTRY_URL.into(),
+            presets: BTreeMap::new(),
         }
     }
 }
@@ -255,6 +289,7 @@ impl From<RawConfig> for Ctx {
                 .unwrap_or_else(|| DEFAULT_IMAGE_REGISTRY_URL.into())
                 .trim_end_matches('/')
                 .to_string(),
+            presets: cfg.presets,
             compute_url: cfg.api.compute_url,
             identity_url: cfg.api.identity_url,
             metadata_url: cfg.api.metadata_url,
@@ -0,0 +1,33 @@
+use seaplane::{api::shared::v1::Directory, flags::Flag};
+
+use crate::{cli::cmds::flags::SeaplaneFlagsCommonArgMatches, error::Result};
+
+/// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
+/// values into a single structure that can be used later to build models for the API or local
+/// structs for serializing
+#[derive(Debug, Default, Clone)]
+pub struct FlagsCtx {
+    /// The directory the flags are stored under
+    pub directory: Option<Directory>,
+    /// The name of the flag
+    pub name: Option<String>,
+    /// The flag to store
+    pub flag: Option<Flag>,
+    /// Turn the flag on (`Some(true)`), off (`Some(false)`) or the opposite of what it is now
+    pub enable: Option<bool>,
+    /// Skip the headers in --format=table
+    pub no_header: bool,
+}
+
+impl FlagsCtx {
+    /// Builds a FlagsCtx from ArgMatches
+    pub fn from_flags_common(matches: &SeaplaneFlagsCommonArgMatches) -> Result<FlagsCtx> {
+        let matches = matches.0;
+        Ok(FlagsCtx {
+            directory: matches
+                .get_one::<String>("dir")
+                .map(|dir| Directory::from_unencoded(dir.trim_end_matches('/'))),
+            ..FlagsCtx::default()
+        })
+    }
+}
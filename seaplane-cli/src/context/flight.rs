@@ -110,7 +110,8 @@ impl FlightCtx {
                 }
                 // @TODO technically archFOOBAR=.. is valid... oh well
                 arch if part.starts_with("arch") => {
-                    fctx.architecture.push(parse_item!(arch)?.parse()?);
+                    fctx.architecture
+                        .push(parse_item!(arch)?.parse::<Architecture>()?.into_model());
                 }
                 "api-permission" | "api-permissions" => {
                     fctx.api_permission = true;
@@ -214,7 +215,7 @@ impl FlightCtx {
         // Add all the architectures. In the CLI they're a Vec but in the Model they're a HashSet
         // which is the reason for the slightly awkward loop
         for arch in &self.architecture {
-            flight_model = flight_model.add_architecture(*arch);
+            flight_model = flight_model.add_architecture(arch.clone());
         }
 
         // Create a new Flight struct we can add to our local JSON "DB"
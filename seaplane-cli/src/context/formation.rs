@@ -1,15 +1,21 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use seaplane::api::{
     compute::v1::FormationConfiguration as FormationConfigurationModel,
     shared::v1::{Provider as ProviderModel, Region as RegionModel},
 };
 
+#[cfg(feature = "unstable")]
+use crate::ops::formation::{ContainerGroupBy, TrafficWeightSpec};
 use crate::{
     cli::{cmds::formation::SeaplaneFormationPlanArgMatches, Provider, Region},
     context::Ctx,
     error::{CliError, CliErrorKind, Context, Result},
-    ops::{flight::Flights, formation::Endpoint, generate_formation_name},
+    ops::{
+        flight::Flights,
+        formation::{ComputeApiVersion, Endpoint},
+        generate_formation_name,
+    },
     printer::Color,
 };
 
@@ -41,6 +47,37 @@ pub struct FormationCtx {
     pub local: bool,
     pub grounded: bool,
     pub recursive: bool,
+    pub api_version: ComputeApiVersion,
+    /// The Flight receiving public traffic, only used by the v2 Compute API
+    pub gateway_flight: Option<String>,
+    /// Only report what would change, without changing anything
+    pub dry_run: bool,
+    /// How long `formation launch --wait` waits for the Formation to be running, if at all
+    pub wait: Option<Duration>,
+    /// How often `formation status --watch` refreshes, if at all
+    pub watch: Option<Duration>,
+    /// The number of the deployment `formation rollback` returns to, or `None` for the one before
+    /// the latest
+    pub rollback_to: Option<usize>,
+    /// The traffic weights to change with `formation load-balance`
+    #[cfg(feature = "unstable")]
+    pub traffic_weights: Vec<TrafficWeightSpec>,
+    #[cfg(feature = "unstable")]
+    pub rollout: RolloutCtx,
+    #[cfg(feature = "unstable")]
+    pub scale: ScaleCtx,
+    /// The UUID or local ID (or a prefix of either) of a single configuration of the Formation
+    #[cfg(feature = "unstable")]
+    pub configuration: Option<String>,
+    /// What to aggregate `formation container-statistics` by
+    #[cfg(feature = "unstable")]
+    pub group_by: Option<ContainerGroupBy>,
+    /// How long `formation land --drain` waits after moving traffic away, if draining at all
+    #[cfg(feature = "unstable")]
+    pub drain: Option<Duration>,
+    /// The UUIDs (or prefixes of them) of the configurations `formation land --drain` lands
+    #[cfg(feature = "unstable")]
+    pub drain_configurations: Vec<String>,
     // TODO: make multiple possible
     pub cfg_ctx: FormationCfgCtx,
 }
@@ -55,6 +92,26 @@ impl Default for FormationCtx {
             local: true,
             grounded: false,
             recursive: false,
+            api_version: ComputeApiVersion::default(),
+            gateway_flight: None,
+            dry_run: false,
+            wait: None,
+            watch: None,
+            rollback_to: None,
+            #[cfg(feature = "unstable")]
+            traffic_weights: Vec::new(),
+            #[cfg(feature = "unstable")]
+            rollout: RolloutCtx::default(),
+            #[cfg(feature = "unstable")]
+            scale: ScaleCtx::default(),
+            #[cfg(feature = "unstable")]
+            configuration: None,
+            #[cfg(feature = "unstable")]
+            group_by: None,
+            #[cfg(feature = "unstable")]
+            drain: None,
+            #[cfg(feature = "unstable")]
+            drain_configurations: Vec::new(),
         }
     }
 }
@@ -98,6 +155,11 @@ impl FormationCtx {
 
         self.grounded = matches.get_flag("grounded");
         self.launch = matches.get_flag("launch");
+        #[cfg(feature = "compute_api_v2")]
+        {
+            self.api_version = matches.get_one("api-version").copied().unwrap_or_default();
+            self.gateway_flight = matches.get_one::<String>("gateway-flight").cloned();
+        }
         self.cfg_ctx
             .flights
             .extend(flight_names.iter().map(|s| s.to_string()));
@@ -151,6 +213,56 @@ impl FormationCtx {
         Ok(())
     }
 
+    /// Returns an error if any values were given which can't be expressed in the v2 Compute API, or
+    /// if the gateway Flight can't be determined
+    #[cfg(feature = "compute_api_v2")]
+    pub fn ensure_v2_compatible(&self) -> Result<()> {
+        let cfg = &self.cfg_ctx;
+        let unsupported: Vec<&str> = [
+            ("--affinity", cfg.affinities.is_empty()),
+            ("--connection", cfg.connections.is_empty()),
+            ("--provider", cfg.providers_allowed.is_empty()),
+            ("--exclude-provider", cfg.providers_denied.is_empty()),
+            ("--region", cfg.regions_allowed.is_empty()),
+            ("--exclude-region", cfg.regions_denied.is_empty()),
+            ("--public-endpoint", cfg.public_endpoints.is_empty()),
+            ("--formation-endpoint", cfg.formation_endpoints.is_empty()),
+            ("--flight-endpoint", cfg.flight_endpoints.is_empty()),
+        ]
+        .into_iter()
+        .filter_map(|(arg, empty)| if empty { None } else { Some(arg) })
+        .collect();
+
+        if !unsupported.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "the v2 Compute API does not support {}",
+                unsupported.join(", ")
+            ))
+            .into_err()
+            .context("(hint: remove them, or use '")
+            .color_context(Color::Green, "--api-version v1")
+            .context("')\n"));
+        }
+
+        match &self.gateway_flight {
+            Some(gateway) if !cfg.flights.contains(gateway) => {
+                Err(CliErrorKind::InvalidCliValue(Some("gateway-flight"), gateway.clone())
+                    .into_err()
+                    .context(
+                        "(hint: the gateway Flight must be one of the included Flight Plans)\n",
+                    ))
+            }
+            None if cfg.flights.len() > 1 => Err(CliErrorKind::OneOff(
+                "a gateway Flight is required when a v2 Formation has more than one Flight".into(),
+            )
+            .into_err()
+            .context("(hint: choose one with '")
+            .color_context(Color::Green, "--gateway-flight NAME")
+            .context("')\n")),
+            _ => Ok(()),
+        }
+    }
+
     /// Creates a new seaplane::api::compute::v1::FormationConfiguration from the contained values
     pub fn configuration_model(&self, ctx: &Ctx) -> Result<FormationConfigurationModel> {
         // Create the new Formation model from the CLI inputs
@@ -166,17 +278,17 @@ impl FormationCtx {
         }
 
         // TODO: clean this up...yuck
-        for &item in &self.cfg_ctx.providers_allowed {
-            f_model = f_model.add_allowed_provider(item);
+        for item in &self.cfg_ctx.providers_allowed {
+            f_model = f_model.add_allowed_provider(item.clone());
         }
-        for &item in &self.cfg_ctx.providers_denied {
-            f_model = f_model.add_denied_provider(item);
+        for item in &self.cfg_ctx.providers_denied {
+            f_model = f_model.add_denied_provider(item.clone());
         }
-        for &item in &self.cfg_ctx.regions_allowed {
-            f_model = f_model.add_allowed_region(item);
+        for item in &self.cfg_ctx.regions_allowed {
+            f_model = f_model.add_allowed_region(item.clone());
         }
-        for &item in &self.cfg_ctx.regions_denied {
-            f_model = f_model.add_denied_region(item);
+        for item in &self.cfg_ctx.regions_denied {
+            f_model = f_model.add_denied_region(item.clone());
         }
         for item in &self.cfg_ctx.public_endpoints {
             f_model = f_model.add_public_endpoint(item.key(), item.value());
@@ -222,3 +334,31 @@ pub struct FormationCfgCtx {
     pub formation_endpoints: Vec<Endpoint>,
     pub flight_endpoints: Vec<Endpoint>,
 }
+
+/// The options of `formation rollout`
+#[cfg(feature = "unstable")]
+#[derive(Default, Debug, Clone)]
+pub struct RolloutCtx {
+    /// The UUID, or a prefix of it, of the configuration receiving the traffic
+    pub to: String,
+    /// The percentage of traffic sent to the configuration at each step
+    pub steps: Vec<u8>,
+    /// How long to wait between steps before checking the health of the configuration
+    pub interval: Duration,
+}
+
+/// The options of `formation scale`
+#[cfg(feature = "unstable")]
+#[derive(Default, Debug, Clone)]
+pub struct ScaleCtx {
+    /// The name of the Flight to scale
+    pub flight: String,
+    /// The new minimum number of containers of the Flight
+    pub minimum: u64,
+    /// The new maximum number of containers of the Flight, where `Some(None)` removes the maximum
+    /// and `None` keeps the current one
+    pub maximum: Option<Option<u64>>,
+    /// How long to wait for the new configuration to be running before removing the old one, if
+    /// removing it at all
+    pub remove_old: Option<Duration>,
+}
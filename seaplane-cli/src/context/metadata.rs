@@ -3,7 +3,10 @@ use std::{
     io::{self, Read},
 };
 
-use seaplane::api::{metadata::v1::Key, shared::v1::Directory};
+use seaplane::api::{
+    metadata::v1::{Key, Keyring, DEFAULT_CHUNK_SIZE},
+    shared::v1::Directory,
+};
 
 use crate::{
     cli::cmds::metadata::{SeaplaneMetadataCommonArgMatches, SeaplaneMetadataSetArgMatches},
@@ -40,6 +43,32 @@ pub struct MetadataCtx {
     pub keys_width_limit: usize,
     /// Max width of values
     pub values_width_limit: usize,
+    /// The directory to compare `directory` against
+    pub other_directory: Option<Directory>,
+    /// A path to a JSON snapshot (as produced by `metadata list --format json`) to compare
+    /// `directory` against
+    pub snapshot: Option<String>,
+    /// Fail when `metadata diff` finds any differences
+    pub exit_code: bool,
+    /// A path to a file which is too large to be stored as a single value, and will instead be
+    /// streamed into the store in chunks
+    pub value_path: Option<String>,
+    /// A path to write the (possibly chunked) value to instead of printing it
+    pub output_file: Option<String>,
+    /// Encrypt values with the active key of `key_file` before storing them
+    pub encrypt: bool,
+    /// A path to a JSON keyring used to encrypt or decrypt values
+    pub key_file: Option<String>,
+    /// Only remove the chunks under the keys which don't belong to their current value
+    pub chunks: bool,
+    /// Seconds between checks for changes when watching
+    pub interval: u64,
+    /// A shell command to run for every change when watching
+    pub exec: Option<String>,
+    /// Stop watching after this many changes
+    pub count: Option<usize>,
+    /// Report existing keys as added when starting to watch
+    pub initial: bool,
 }
 
 impl MetadataCtx {
@@ -77,6 +106,33 @@ impl MetadataCtx {
         let base64 = matches.get_flag("base64");
         let raw_key = matches.get_one::<String>("key").unwrap();
         let raw_value = matches.get_one::<String>("value").unwrap();
+        let encrypt = matches.get_flag("encrypt");
+        let key_file = matches.get_one::<String>("key-file").cloned();
+
+        // Large files are not read into memory here, but streamed into the store in chunks later.
+        // Encrypted values are always sealed as a whole.
+        if let Some(path) = raw_value
+            .strip_prefix('@')
+            .filter(|p| *p != "-" && !base64 && !encrypt)
+        {
+            let len = File::open(path)
+                .and_then(|f| f.metadata())
+                .map_err(CliError::from)
+                .context("\n\tpath: ")
+                .with_color_context(|| (Color::Yellow, path))?
+                .len();
+            if len > DEFAULT_CHUNK_SIZE as u64 {
+                let mut kvs = KeyValues::default();
+                kvs.push(KeyValue::from_key_unencoded(raw_key));
+                return Ok(MetadataCtx {
+                    kvs,
+                    base64: true,
+                    value_path: Some(path.to_owned()),
+                    ..MetadataCtx::default()
+                });
+            }
+        }
+
         let value = if let Some(val) = raw_value.strip_prefix('@') {
             if val == "-" {
                 let mut buf: Vec<u8> = Vec::new();
@@ -121,6 +177,23 @@ impl MetadataCtx {
         let mut kvs = KeyValues::default();
         kvs.push(kv);
 
-        Ok(MetadataCtx { kvs, base64: true, ..MetadataCtx::default() })
+        Ok(MetadataCtx { kvs, base64: true, encrypt, key_file, ..MetadataCtx::default() })
+    }
+
+    /// Loads the keyring from `key_file` if one was provided
+    pub fn keyring(&self) -> Result<Option<Keyring>> {
+        let path = match &self.key_file {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let f = File::open(path)
+            .map_err(CliError::from)
+            .context("\n\tpath: ")
+            .with_color_context(|| (Color::Yellow, path))?;
+        let keyring = serde_json::from_reader(f)
+            .map_err(CliError::from)
+            .context("\n\tkey file: ")
+            .with_color_context(|| (Color::Yellow, path))?;
+        Ok(Some(keyring))
     }
 }
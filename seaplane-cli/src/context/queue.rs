@@ -0,0 +1,45 @@
+use seaplane::{api::shared::v1::Directory, queue::DEFAULT_VISIBILITY_TIMEOUT};
+
+use crate::{cli::cmds::queue::SeaplaneQueueCommonArgMatches, error::Result};
+
+/// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
+/// values into a single structure that can be used later to build models for the API or local
+/// structs for serializing
+#[derive(Debug, Clone)]
+pub struct QueueCtx {
+    /// The directory the tasks of the queue are stored under
+    pub directory: Option<Directory>,
+    /// The value of the task to push
+    pub value: Option<String>,
+    /// How long in seconds a popped task is hidden from other consumers
+    pub ttl: u32,
+    /// Leave a popped task claimed instead of completing it
+    pub keep: bool,
+    /// Skip the headers in --format=table
+    pub no_header: bool,
+}
+
+impl Default for QueueCtx {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            value: None,
+            ttl: DEFAULT_VISIBILITY_TIMEOUT,
+            keep: false,
+            no_header: false,
+        }
+    }
+}
+
+impl QueueCtx {
+    /// Builds a QueueCtx from ArgMatches
+    pub fn from_queue_common(matches: &SeaplaneQueueCommonArgMatches) -> Result<QueueCtx> {
+        let matches = matches.0;
+        Ok(QueueCtx {
+            directory: matches
+                .get_one::<String>("queue")
+                .map(|queue| Directory::from_unencoded(queue.trim_end_matches('/'))),
+            ..QueueCtx::default()
+        })
+    }
+}
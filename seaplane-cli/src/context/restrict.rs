@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use clap::ArgMatches;
 use seaplane::api::{
     restrict::v1::{RestrictedDirectory, RestrictionDetails},
     shared::v1::{Provider as ProviderModel, Region as RegionModel},
@@ -12,6 +13,7 @@ use crate::{
         SeaplaneRestrictSetArgMatches,
     },
     error::Result,
+    ops::restrict::policy::PolicyFormat,
 };
 
 /// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
@@ -40,6 +42,20 @@ pub struct RestrictCtx {
     pub decode: bool,
     /// Skip the headers in --format=table
     pub no_header: bool,
+    /// The path of a restriction policy file
+    pub policy_file: Option<String>,
+    /// The syntax of the policy file, when it shouldn't be guessed from the file extension
+    pub policy_format: Option<PolicyFormat>,
+    /// Delete restrictions that are missing from the policy file
+    pub prune: bool,
+    /// The provider of a placement to check
+    pub provider: Option<ProviderModel>,
+    /// The region of a placement to check
+    pub region: Option<RegionModel>,
+    /// Wait for the restriction to be enforced
+    pub wait: bool,
+    /// How long in seconds to wait for the restriction to be enforced
+    pub timeout: u64,
 }
 
 impl RestrictCtx {
@@ -124,20 +140,31 @@ impl RestrictCtx {
         })
     }
 
+    /// Builds a RestictCtx from the ArgMatches of commands using a policy file
+    pub fn from_restrict_policy(matches: &ArgMatches) -> RestrictCtx {
+        RestrictCtx {
+            policy_file: matches.get_one::<String>("file").cloned(),
+            prune: matches.get_flag("prune"),
+            decode: matches.get_flag("decode"),
+            no_header: matches.get_flag("no-header"),
+            ..RestrictCtx::default()
+        }
+    }
+
     pub fn restriction_details(&self) -> Result<RestrictionDetails> {
         let mut builder = RestrictionDetails::builder();
 
         for item in &self.providers_allowed {
-            builder = builder.add_allowed_provider(*item);
+            builder = builder.add_allowed_provider(item.clone());
         }
         for item in &self.providers_denied {
-            builder = builder.add_denied_provider(*item);
+            builder = builder.add_denied_provider(item.clone());
         }
         for item in &self.regions_allowed {
-            builder = builder.add_allowed_region(*item);
+            builder = builder.add_allowed_region(item.clone());
         }
         for item in &self.regions_denied {
-            builder = builder.add_denied_region(*item);
+            builder = builder.add_denied_region(item.clone());
         }
 
         Ok(builder.build()?)
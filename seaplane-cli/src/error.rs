@@ -16,6 +16,10 @@ use crate::{
 
 pub type Result<T> = StdResult<T, CliError>;
 
+/// The exit status used when waiting on something timed out, which matches the convention of
+/// `timeout(1)` so scripts can tell it apart from other failures
+pub const TIMEOUT_EXIT_STATUS: i32 = 124;
+
 /// A trait for adding context to an error that will be printed along with the error. Contexts are
 /// useful for adding things such as hints (i.e. try --help), or additional information such as the
 /// path name on a PermissionDenied error, etc.
@@ -178,7 +182,7 @@ impl_err!(base64::DecodeError, Base64Decode);
 impl_err!(serde_json::Error, SerdeJson);
 impl_err!(toml::de::Error, TomlDe);
 impl_err!(toml::ser::Error, TomlSer);
-impl_err!(seaplane::error::SeaplaneError, Seaplane);
+impl_err!(serde_yaml::Error, SerdeYaml);
 impl_err!(seaplane::rexports::container_image_ref::ImageReferenceError, ImageReference);
 impl_err!(std::string::FromUtf8Error, InvalidUtf8);
 impl_err!(hex::FromHexError, HexDecode);
@@ -186,6 +190,13 @@ impl_err!(std::num::ParseIntError, ParseInt);
 impl_err!(strum::ParseError, StrumParse);
 impl_err!(clap::Error, Clap);
 
+impl From<SeaplaneError> for CliError {
+    fn from(e: SeaplaneError) -> Self {
+        let status = matches!(e, SeaplaneError::Timeout(_)).then_some(TIMEOUT_EXIT_STATUS);
+        CliError { kind: CliErrorKind::Seaplane(e), status, ..Default::default() }
+    }
+}
+
 impl From<io::Error> for CliError {
     fn from(e: io::Error) -> Self {
         match e.kind() {
@@ -229,6 +240,7 @@ pub enum CliErrorKind {
     Base64Decode(base64::DecodeError),
     TomlDe(toml::de::Error),
     TomlSer(toml::ser::Error),
+    SerdeYaml(serde_yaml::Error),
     HexDecode(hex::FromHexError),
     UnknownWithContext(&'static str),
     Seaplane(SeaplaneError),
@@ -253,6 +265,7 @@ pub enum CliErrorKind {
     FlightsInUse(Vec<String>),
     EndpointInvalidFlight(String),
     OneOff(String),
+    EditorFailed(String),
     Clap(clap::Error),
 }
 
@@ -344,6 +357,9 @@ impl CliErrorKind {
             TomlSer(e) => {
                 cli_eprintln!("toml: {e}")
             }
+            SerdeYaml(e) => {
+                cli_eprintln!("yaml: {e}")
+            }
             ParseInt(e) => {
                 cli_eprintln!("parse integer: {e}")
             }
@@ -377,6 +393,11 @@ impl CliErrorKind {
                 cli_println!("' values were provided and only one is allowed");
             }
             Seaplane(e) => match e {
+                SeaplaneError::UnknownDataKey(id) => {
+                    cli_eprint!("the value was encrypted with the data key '");
+                    cli_eprint!(@Yellow, "{id}");
+                    cli_eprintln!("' which is not in the key file");
+                }
                 SeaplaneError::ApiResponse(ae) => {
                     cli_eprintln!("{ae}");
                     if ae.kind == ApiErrorKind::BadRequest
@@ -387,6 +408,9 @@ impl CliErrorKind {
                         cli_eprintln!("')");
                     }
                 }
+                SeaplaneError::Timeout(d) => {
+                    cli_eprintln!("timed out after {}s", d.as_secs());
+                }
                 _ => {
                     cli_eprintln!("Seaplane API: {e}")
                 }
@@ -411,6 +435,16 @@ impl CliErrorKind {
             InlineFlightMissingValue(key) => {
                 cli_eprintln!("INLINE-FLIGHT-SPEC missing a value for the key {key}");
             }
+            EditorFailed(editor) => {
+                cli_eprint!("the editor '");
+                cli_eprint!(@Yellow, "{editor}");
+                cli_eprintln!("' did not exit successfully");
+                cli_eprint!("(hint: set the editor to use with '");
+                cli_eprint!(@Yellow, "$VISUAL");
+                cli_eprint!("' or '");
+                cli_eprint!(@Yellow, "$EDITOR");
+                cli_eprintln!("')");
+            }
             Clap(e) => {
                 cli_eprintln!("{e}")
             }
@@ -445,6 +479,7 @@ impl PartialEq<Self> for CliErrorKind {
             SerdeJson(_) => matches!(rhs, SerdeJson(_)),
             TomlSer(_) => matches!(rhs, TomlSer(_)),
             TomlDe(_) => matches!(rhs, TomlDe(_)),
+            SerdeYaml(_) => matches!(rhs, SerdeYaml(_)),
             Unknown => matches!(rhs, Unknown),
             UnknownWithContext(_) => matches!(rhs, UnknownWithContext(_)),
             ExistingValue(_) => matches!(rhs, ExistingValue(_)),
@@ -463,6 +498,7 @@ impl PartialEq<Self> for CliErrorKind {
             InlineFlightMissingValue(_) => matches!(rhs, InlineFlightMissingValue(_)),
             ParseInt(_) => matches!(rhs, ParseInt(_)),
             FlightsInUse(_) => matches!(rhs, FlightsInUse(_)),
+            EditorFailed(_) => matches!(rhs, EditorFailed(_)),
             Clap(_) => matches!(rhs, Clap(_)),
         }
     }
@@ -498,8 +534,11 @@ impl CliError {
     pub fn exit(&self) -> ! {
         self.print();
         // TODO: solidify what should happen if an error with self.fatal = false is called here...
-        std::process::exit(self.status.unwrap_or(1))
+        std::process::exit(self.exit_status())
     }
 
+    /// The status the process exits with because of this error
+    pub fn exit_status(&self) -> i32 { self.status.unwrap_or(1) }
+
     pub fn kind(&self) -> &CliErrorKind { &self.kind }
 }
@@ -2,10 +2,13 @@
 //! that is only relevant for the CLI or purposes of consuming the API.
 
 pub mod encoded_string;
+pub mod flags;
 pub mod flight;
 pub mod formation;
 pub mod locks;
 pub mod metadata;
+pub mod preset;
+pub mod queue;
 pub mod restrict;
 
 use std::fmt;
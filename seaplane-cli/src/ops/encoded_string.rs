@@ -1,7 +1,7 @@
 use std::{fmt, result::Result as StdResult};
 
 use base64::{alphabet, engine::fast_portable};
-use serde::{ser::Serializer, Serialize};
+use serde::{de::Deserializer, ser::Serializer, Deserialize, Serialize};
 
 use crate::error::Result;
 
@@ -14,6 +14,12 @@ impl Serialize for EncodedString {
     }
 }
 
+impl<'de> Deserialize<'de> for EncodedString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        Ok(EncodedString(String::deserialize(deserializer)?))
+    }
+}
+
 impl EncodedString {
     pub fn new(s: String) -> Self { EncodedString(s) }
 
@@ -24,9 +30,17 @@ impl EncodedString {
     }
 
     /// Decodes into display-safe format
-    pub fn decoded_safe(&self) -> Result<String> {
-        let engine = fast_portable::FastPortable::from(&alphabet::URL_SAFE, fast_portable::NO_PAD);
-        Ok(stfu8::encode_u8(&base64::decode_engine(&self.0, &engine)?))
+    pub fn decoded_safe(&self) -> Result<String> { Ok(stfu8::encode_u8(&self.decoded()?)) }
+
+    /// Decodes into display-safe format like `decoded_safe`, but keeps the line breaks so the
+    /// value can be compared line by line
+    pub fn decoded_safe_lines(&self) -> Result<String> {
+        Ok(self
+            .decoded()?
+            .split(|b| *b == b'\n')
+            .map(stfu8::encode_u8)
+            .collect::<Vec<_>>()
+            .join("\n"))
     }
 }
 
@@ -54,4 +68,12 @@ mod tests {
         assert_eq!(decoded, bin());
         Ok(())
     }
+
+    #[test]
+    fn test_decoded_safe_lines() -> Result<()> {
+        assert_eq!(EncodedString(base64()).decoded_safe_lines()?, r"Hey\x01There");
+        // foo\nbar\x01
+        assert_eq!(EncodedString("Zm9vCmJhcgE".to_owned()).decoded_safe_lines()?, "foo\nbar\\x01");
+        Ok(())
+    }
 }
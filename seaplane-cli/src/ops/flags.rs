@@ -0,0 +1,82 @@
+use std::io::Write;
+
+use seaplane::flags::{FlagRule, FlagSet};
+use tabwriter::TabWriter;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    printer::{printer, Output},
+};
+
+/// The flags of a flags directory, in a form that is easy to display
+#[derive(Debug, Default, Clone)]
+pub struct Flags {
+    inner: FlagSet,
+}
+
+impl Flags {
+    pub fn new(inner: FlagSet) -> Self { Self { inner } }
+
+    /// Warns about each flag that could not be parsed, since those are left out of the output
+    pub fn warn_invalid(&self) {
+        for (name, reason) in &self.inner.invalid {
+            cli_warnln!("skipping invalid flag '{name}': {reason}");
+        }
+    }
+
+    fn impl_print_table(&self, headers: bool) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+
+        if headers {
+            writeln!(tw, "NAME\tKIND\tENABLED\tROLLOUT\tREGIONS\tDESCRIPTION")?;
+        }
+
+        for (name, flag) in &self.inner.flags {
+            let rollout = match &flag.rule {
+                FlagRule::Boolean => "everyone".to_owned(),
+                FlagRule::Percentage { percentage } => format!("{percentage}%"),
+                FlagRule::AllowList { users } => {
+                    users.iter().cloned().collect::<Vec<_>>().join(",")
+                }
+            };
+            let regions = if flag.regions.is_empty() {
+                "all".to_owned()
+            } else {
+                flag.regions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            writeln!(
+                tw,
+                "{name}\t{}\t{}\t{rollout}\t{regions}\t{}",
+                flag.rule.kind(),
+                flag.enabled,
+                flag.description.as_deref().unwrap_or("-")
+            )?;
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing flags"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Output for Flags {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(&self.inner.flags)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        self.impl_print_table(!ctx.flags_ctx.get_or_init().no_header)
+    }
+}
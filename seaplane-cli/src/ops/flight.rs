@@ -66,7 +66,7 @@ impl Flight {
 
         // Architecture
         for arch in ctx.architecture.iter().chain(self.model.architecture()) {
-            dest_builder = dest_builder.add_architecture(*arch);
+            dest_builder = dest_builder.add_architecture(arch.clone());
         }
 
         // API Permission
@@ -217,7 +217,7 @@ impl Flights {
             flight.model.set_maximum(model.maximum());
 
             for arch in model.architecture() {
-                flight.model.add_architecture(*arch);
+                flight.model.add_architecture(arch.clone());
             }
 
             #[cfg(feature = "unstable")]
@@ -1,25 +1,44 @@
+#[cfg(feature = "unstable")]
+mod container_stats;
 mod endpoint;
+mod history;
+mod progress;
+#[cfg(feature = "unstable")]
+mod traffic;
+mod watch;
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     io::Write,
     path::{Path, PathBuf},
 };
 
+#[cfg(feature = "unstable")]
+pub use container_stats::*;
 pub use endpoint::*;
+pub use history::*;
+pub use progress::*;
 use seaplane::api::compute::v1::{
     Container as ContainerModel, ContainerStatus, Flight as FlightModel,
     FormationConfiguration as FormationConfigurationModel,
 };
+#[cfg(feature = "compute_api_v2")]
+use seaplane::api::compute::v2::{
+    Flight as FlightModelV2, FlightHealthStatus, FlightStatus as FlightStatusModelV2,
+    Formation as FormationModelV2, FormationStatus as FormationStatusModelV2,
+};
 use serde::{Deserialize, Serialize};
 use tabwriter::TabWriter;
+#[cfg(feature = "unstable")]
+pub use traffic::*;
 use uuid::Uuid;
+pub use watch::*;
 
 use crate::{
     context::Ctx,
-    error::{CliError, Result},
+    error::{CliError, CliErrorKind, Context, Result},
     fs::{FromDisk, ToDisk},
     ops::Id,
-    printer::Output,
+    printer::{Color, Output},
 };
 
 // TODO: Change out the Vecs for HashMaps where the key is an ID
@@ -46,6 +65,11 @@ pub struct Formations {
     /// reference the same configuration.
     #[serde(default)]
     pub configurations: Vec<FormationConfiguration>,
+
+    /// The log of changes made to the active configurations of each remote Formation Instance,
+    /// keyed by the Formation name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub deployments: BTreeMap<String, Vec<Deployment>>,
 }
 
 impl Formations {
@@ -177,6 +201,36 @@ impl Formations {
         }
     }
 
+    /// Removes the configuration uploaded as `uuid` from the remote Formation Instance `name` in
+    /// the local state, keeping it in the local Formation Plan
+    pub fn remove_uuid_by_name(&mut self, name: &str, uuid: Uuid) {
+        if let Some(cfg) = self
+            .configurations
+            .iter_mut()
+            .find(|cfg| cfg.remote_id == Some(uuid))
+        {
+            cfg.remote_id = None;
+            let id = cfg.id;
+            for f in self.formations.iter_mut() {
+                if f.name.as_deref() == Some(name) {
+                    f.in_air.remove(&id);
+                    f.grounded.remove(&id);
+                    f.local.insert(id);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns the version of the Compute API the Formation with the given name belongs to
+    pub fn api_version_of(&self, name: &str) -> ComputeApiVersion {
+        self.formations
+            .iter()
+            .find(|f| f.name.as_deref() == Some(name))
+            .map(|f| f.api_version)
+            .unwrap_or_default()
+    }
+
     /// Returns true if there is a Formation with the given name
     pub fn contains_name(&self, name: &str) -> bool {
         self.formations
@@ -268,6 +322,38 @@ impl Formations {
     }
 }
 
+#[cfg(feature = "compute_api_v2")]
+impl Formations {
+    /// Builds the v2 Compute API model of a local Formation Plan from the Flights of its
+    /// configurations
+    pub fn v2_model(&self, formation: &Formation) -> Result<FormationModelV2> {
+        let mut builder =
+            FormationModelV2::builder().name(formation.name.as_deref().unwrap_or_default());
+        let mut seen = HashSet::new();
+        for cfg in formation
+            .configs()
+            .iter()
+            .filter_map(|id| self.get_configuration(id))
+        {
+            for flight in cfg.model.flights() {
+                if seen.insert(flight.name()) {
+                    builder = builder.add_flight(
+                        FlightModelV2::builder()
+                            .name(flight.name())
+                            .image_reference(flight.image().clone())
+                            .build()?,
+                    );
+                }
+            }
+        }
+        if let Some(gateway) = &formation.gateway_flight {
+            builder = builder.gateway_flight(gateway);
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
 impl FromDisk for Formations {
     fn set_loaded_from<P: AsRef<Path>>(&mut self, p: P) {
         self.loaded_from = Some(p.as_ref().into());
@@ -332,6 +418,44 @@ impl Output for Formations {
     }
 }
 
+/// The version of the Compute API a Formation belongs to
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    strum::EnumString,
+    strum::Display,
+    clap::ValueEnum,
+)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeApiVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl ComputeApiVersion {
+    /// Returns an error if this build of the CLI can't talk to this version of the Compute API
+    pub fn ensure_supported(self, formation: &str) -> Result<()> {
+        if self == ComputeApiVersion::V2 && cfg!(not(feature = "compute_api_v2")) {
+            return Err(CliErrorKind::OneOff(format!(
+                "Formation '{formation}' uses the v2 Compute API which this build of the CLI does not support"
+            ))
+            .into_err()
+            .context("(hint: rebuild the CLI with the '")
+            .color_context(Color::Green, "compute_api_v2")
+            .context("' feature)\n"));
+        }
+        Ok(())
+    }
+}
+
 // TODO: move ID to the key of a HashMap
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Formation {
@@ -340,6 +464,12 @@ pub struct Formation {
     pub local: HashSet<Id>,
     pub in_air: HashSet<Id>,
     pub grounded: HashSet<Id>,
+    /// Formations from before this was recorded all belong to the v1 Compute API
+    #[serde(default)]
+    pub api_version: ComputeApiVersion,
+    /// The Flight receiving public traffic, only used by the v2 Compute API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_flight: Option<String>,
 }
 
 impl Formation {
@@ -350,6 +480,8 @@ impl Formation {
             local: HashSet::new(),
             in_air: HashSet::new(),
             grounded: HashSet::new(),
+            api_version: ComputeApiVersion::default(),
+            gateway_flight: None,
         }
     }
 
@@ -436,6 +568,10 @@ pub struct FormationStatus {
     name: String,
     status: OpStatus,
     configurations: FormationConfigStatuses,
+    /// The health of each Flight, only reported by the v2 Compute API
+    #[cfg(feature = "compute_api_v2")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    flights: Vec<FlightStatusModelV2>,
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Clone, Serialize)]
@@ -542,12 +678,66 @@ impl FormationStatus {
             name: name.into(),
             status: OpStatus::default(),
             configurations: FormationConfigStatuses::default(),
+            #[cfg(feature = "compute_api_v2")]
+            flights: Vec::new(),
+        }
+    }
+
+    /// Create a new FormationStatus from the per Flight health reported by the v2 Compute API
+    #[cfg(feature = "compute_api_v2")]
+    pub fn from_v2(model: FormationStatusModelV2) -> Self {
+        let count = |health| model.flights.iter().filter(|f| f.health == health).count();
+        let healthy = count(FlightHealthStatus::Healthy);
+        let unhealthy = count(FlightHealthStatus::Unhealthy);
+        let status = if model.flights.is_empty() || unhealthy == model.flights.len() {
+            OpStatus::Down
+        } else if healthy == model.flights.len() {
+            OpStatus::Up
+        } else {
+            OpStatus::Degraded
+        };
+
+        Self {
+            name: model.name,
+            status,
+            configurations: FormationConfigStatuses::default(),
+            flights: model.flights,
+        }
+    }
+
+    #[cfg(feature = "compute_api_v2")]
+    fn print_flights(&self) {
+        // Chars we'll need: │ ├ ─ └
+        self.status.print_sym();
+        cli_print!(" Formation {}: ", self.name);
+        self.status.print();
+        cli_println!("");
+        cli_println!("│");
+
+        let longest_flight_name = self.flights.iter().map(|f| f.name.len()).max().unwrap();
+        let total_slot_size = std::cmp::max(longest_flight_name, 10);
+        cli_println!("│   FLIGHT{}HEALTH", " ".repeat(total_slot_size - 6)); // 6 = FLIGHT
+        for (i, flight) in self.flights.iter().enumerate() {
+            if i == self.flights.len() - 1 {
+                cli_print!("└─");
+            } else {
+                cli_print!("├─");
+            }
+            let status = match flight.health {
+                FlightHealthStatus::Healthy => OpStatus::Up,
+                FlightHealthStatus::Unhealthy => OpStatus::Down,
+                _ => OpStatus::Degraded,
+            };
+            status.print_sym();
+            cli_print!(" {}{}", flight.name, " ".repeat(total_slot_size - flight.name.len()));
+            status.print_msg(flight.health.as_ref());
+            cli_println!("");
         }
     }
 
     /// Add the appropriate Flights and Configurations from a given Container Instance
     pub fn add_container(&mut self, c: &ContainerModel, min: u64, max: Option<u64>) {
-        match c.status {
+        match &c.status {
             ContainerStatus::Running => self.configurations.add_running_flight(
                 c.configuration_id,
                 c.flight_name.clone(),
@@ -567,6 +757,8 @@ impl FormationStatus {
                 min,
                 max,
             ),
+            // A status this version doesn't know about can't be counted as up or down
+            _ => (),
         }
     }
 
@@ -841,6 +1033,14 @@ impl Output for FormationStatus {
     }
 
     fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        #[cfg(feature = "compute_api_v2")]
+        {
+            if !self.flights.is_empty() {
+                self.print_flights();
+                return Ok(());
+            }
+        }
+
         // Chars we'll need: │ ├ ─ └
         if !self.configurations.is_empty() {
             self.status.print_sym();
@@ -0,0 +1,344 @@
+use std::{collections::BTreeMap, io::Write};
+
+use seaplane::api::compute::v1::{
+    Container as ContainerModel, ContainerStatus, Containers as ContainersModel,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    printer::{printer, Output},
+};
+
+/// What to aggregate container statistics by
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumString, strum::Display, clap::ValueEnum)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum ContainerGroupBy {
+    Flight,
+    Configuration,
+    Region,
+    Provider,
+    Country,
+}
+
+impl ContainerGroupBy {
+    fn key(self, c: &ContainerModel) -> String {
+        let host = c.host_info.as_ref();
+        let key = match self {
+            ContainerGroupBy::Flight => Some(c.flight_name.clone()),
+            ContainerGroupBy::Configuration => Some(c.configuration_id.to_string()),
+            // Fall back to the region of the country for hosts which don't report one
+            ContainerGroupBy::Region => host
+                .and_then(|h| h.host_region.clone().or_else(|| h.host_country_region()))
+                .map(|r| r.to_string()),
+            ContainerGroupBy::Provider => host
+                .and_then(|h| h.host_provider.as_ref())
+                .map(|p| p.to_string()),
+            ContainerGroupBy::Country => host.and_then(|h| h.host_country.clone()),
+        };
+        key.unwrap_or_else(|| "unknown".into())
+    }
+}
+
+type MetricFn = fn(&ContainerRow) -> Option<u64>;
+
+/// The usage fields which are aggregated, along with how to get them from a container
+const METRICS: &[(&str, MetricFn)] = &[
+    ("uptime_secs", |r| r.uptime_secs),
+    ("cpu_secs", |r| r.container.cpu_usage),
+    ("ram_bytes", |r| r.container.ram_usage),
+    ("disk_bytes", |r| r.container.disk_usage),
+    ("public_ingress_bytes", |r| r.container.public_ingress_usage),
+    ("public_egress_bytes", |r| r.container.public_egress_usage),
+    ("private_ingress_bytes", |r| r.container.private_ingress_usage),
+    ("private_egress_bytes", |r| r.container.private_egress_usage),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerRow {
+    #[serde(flatten)]
+    pub container: ContainerModel,
+    /// Seconds between the start of the container and when it stopped, or now if it's still
+    /// running
+    pub uptime_secs: Option<u64>,
+}
+
+/// The containers of a Formation Instance, one row per container
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct ContainerStats {
+    rows: Vec<ContainerRow>,
+}
+
+impl ContainerStats {
+    /// `now` is the current time as seconds since the UNIX epoch, used for the uptime of
+    /// containers which haven't stopped
+    pub fn new(containers: ContainersModel, now: i64) -> Self {
+        Self {
+            rows: containers
+                .iter()
+                .map(|c| {
+                    let uptime_secs = c.start_time.map(|start| {
+                        let end = c.stop_time.map(|t| t.timestamp()).unwrap_or(now);
+                        u64::try_from(end - start.timestamp()).unwrap_or_default()
+                    });
+                    ContainerRow { container: c.clone(), uptime_secs }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn group_by(&self, by: ContainerGroupBy) -> GroupedContainerStats {
+        let mut groups: BTreeMap<String, Vec<&ContainerRow>> = BTreeMap::new();
+        for row in &self.rows {
+            groups.entry(by.key(&row.container)).or_default().push(row);
+        }
+
+        GroupedContainerStats {
+            by,
+            groups: groups
+                .into_iter()
+                .map(|(group, rows)| ContainerGroup {
+                    containers: rows.len(),
+                    stopped: rows
+                        .iter()
+                        .filter(|r| r.container.status == ContainerStatus::Stopped)
+                        .count(),
+                    metrics: METRICS
+                        .iter()
+                        .map(|(name, get)| {
+                            (*name, MetricSummary::new(rows.iter().filter_map(|r| get(r))))
+                        })
+                        .collect(),
+                    group,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Output for ContainerStats {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(
+            tw,
+            "CONTAINER\tFLIGHT\tCONFIGURATION\tSTATUS\tEXIT\tUPTIME (S)\tREGION\tPROVIDER\tCOUNTRY\tCPU (S)\tRAM\tDISK\tPUBLIC IN\tPUBLIC OUT\tPRIVATE IN\tPRIVATE OUT"
+        )?;
+        let opt = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        for row in &self.rows {
+            let c = &row.container;
+            writeln!(
+                tw,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                c.container_id,
+                c.flight_name,
+                &c.configuration_id.to_string()[..8],
+                c.status,
+                c.exit_status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".into()),
+                opt(row.uptime_secs),
+                ContainerGroupBy::Region.key(c),
+                ContainerGroupBy::Provider.key(c),
+                ContainerGroupBy::Country.key(c),
+                opt(c.cpu_usage),
+                opt(c.ram_usage),
+                opt(c.disk_usage),
+                opt(c.public_ingress_usage),
+                opt(c.public_egress_usage),
+                opt(c.private_ingress_usage),
+                opt(c.private_egress_usage),
+            )?;
+        }
+
+        flush_table(tw)
+    }
+}
+
+/// The count, sum and percentiles of the values of one usage field which were reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MetricSummary {
+    pub count: usize,
+    pub sum: u64,
+    pub p50: Option<u64>,
+    pub p90: Option<u64>,
+    pub p99: Option<u64>,
+}
+
+impl MetricSummary {
+    fn new(values: impl Iterator<Item = u64>) -> Self {
+        let mut values: Vec<u64> = values.collect();
+        values.sort_unstable();
+        Self {
+            count: values.len(),
+            sum: values.iter().sum(),
+            p50: percentile(&values, 50),
+            p90: percentile(&values, 90),
+            p99: percentile(&values, 99),
+        }
+    }
+}
+
+/// Nearest-rank percentile of the already sorted `values`
+fn percentile(values: &[u64], p: usize) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    let rank = (p as f64 * values.len() as f64 / 100.0).ceil() as usize;
+    Some(values[rank.max(1) - 1])
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerGroup {
+    pub group: String,
+    pub containers: usize,
+    pub stopped: usize,
+    pub metrics: BTreeMap<&'static str, MetricSummary>,
+}
+
+/// Container statistics aggregated by flight, configuration, region, provider or country
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedContainerStats {
+    #[serde(skip)]
+    by: ContainerGroupBy,
+    groups: Vec<ContainerGroup>,
+}
+
+impl Output for GroupedContainerStats {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(&self.groups)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(
+            tw,
+            "{}\tCONTAINERS\tSTOPPED\tMETRIC\tREPORTED\tSUM\tP50\tP90\tP99",
+            self.by.to_string().to_ascii_uppercase()
+        )?;
+        let opt = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        for group in &self.groups {
+            // Keep the table in the same order as METRICS rather than alphabetical
+            for (name, _) in METRICS {
+                let m = group.metrics[name];
+                writeln!(
+                    tw,
+                    "{}\t{}\t{}\t{name}\t{}\t{}\t{}\t{}\t{}",
+                    group.group,
+                    group.containers,
+                    group.stopped,
+                    m.count,
+                    m.sum,
+                    opt(m.p50),
+                    opt(m.p90),
+                    opt(m.p99),
+                )?;
+            }
+        }
+
+        flush_table(tw)
+    }
+}
+
+fn flush_table(mut tw: TabWriter<Vec<u8>>) -> Result<()> {
+    tw.flush()?;
+
+    let mut ptr = printer();
+    let page = tw
+        .into_inner()
+        .map_err(|_| CliError::bail("IO flush error writing container statistics"))?;
+    ptr.write_all(&page)?;
+    ptr.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn stats() -> ContainerStats {
+        let containers: ContainersModel = serde_json::from_value(json!([
+            {
+                "container_id": "aa8522e7-06cc-4e35-8966-484ae26e02a9",
+                "status": "running",
+                "flight_name": "web",
+                "configuration_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+                "start_time": "2022-01-01T00:00:00Z",
+                "cpu_usage": 10,
+                "host_country": "de"
+            },
+            {
+                "container_id": "bb8522e7-06cc-4e35-8966-484ae26e02a9",
+                "status": "stopped",
+                "flight_name": "web",
+                "configuration_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+                "exit_status": 1,
+                "start_time": "2022-01-01T00:00:00Z",
+                "stop_time": "2022-01-01T00:01:00Z",
+                "cpu_usage": 30,
+                "host_region": "XN",
+                "host_provider": "AWS"
+            }
+        ]))
+        .unwrap();
+        // 2022-01-01T00:10:00Z
+        ContainerStats::new(containers, 1_640_995_800)
+    }
+
+    #[test]
+    fn uptime() {
+        let uptimes: Vec<_> = stats().rows.iter().map(|r| r.uptime_secs).collect();
+        assert_eq!(uptimes, [Some(600), Some(60)]);
+    }
+
+    #[test]
+    fn percentiles() {
+        assert_eq!(percentile(&[], 50), None);
+        assert_eq!(percentile(&[7], 99), Some(7));
+        let values: Vec<u64> = (1..=10).collect();
+        assert_eq!(percentile(&values, 50), Some(5));
+        assert_eq!(percentile(&values, 90), Some(9));
+        assert_eq!(percentile(&values, 99), Some(10));
+    }
+
+    #[test]
+    fn group_by() {
+        let stats = stats();
+        let by_flight = stats.group_by(ContainerGroupBy::Flight);
+        assert_eq!(by_flight.groups.len(), 1);
+        let web = &by_flight.groups[0];
+        assert_eq!((web.containers, web.stopped), (2, 1));
+        assert_eq!(
+            web.metrics["cpu_secs"],
+            MetricSummary { count: 2, sum: 40, p50: Some(10), p90: Some(30), p99: Some(30) }
+        );
+        assert_eq!(web.metrics["ram_bytes"], MetricSummary::default());
+
+        let by_region: Vec<_> = stats
+            .group_by(ContainerGroupBy::Region)
+            .groups
+            .into_iter()
+            .map(|g| g.group)
+            .collect();
+        assert_eq!(by_region, ["XE", "XN"]);
+
+        let by_provider: Vec<_> = stats
+            .group_by(ContainerGroupBy::Provider)
+            .groups
+            .into_iter()
+            .map(|g| g.group)
+            .collect();
+        assert_eq!(by_provider, ["AWS", "unknown"]);
+    }
+}
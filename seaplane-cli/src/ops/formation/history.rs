@@ -0,0 +1,277 @@
+use std::io::Write;
+
+use seaplane::api::compute::v1::{
+    ActiveConfiguration as ActiveConfigurationModel,
+    ActiveConfigurations as ActiveConfigurationsModel,
+};
+use serde::{Deserialize, Serialize};
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use super::Formations;
+use crate::{
+    context::Ctx,
+    error::{CliError, CliErrorKind, Result},
+    printer::{printer, Output},
+};
+
+/// A configuration which was active after a [`Deployment`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeployedConfiguration {
+    pub uuid: Uuid,
+    /// The traffic weight of the configuration, if known
+    #[serde(default)]
+    pub traffic_weight: Option<f32>,
+    /// The image references of the Flights of the configuration, if known
+    #[serde(default)]
+    pub images: Vec<String>,
+}
+
+/// A change of the active configurations of a remote Formation Instance made by the CLI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deployment {
+    /// When the change was made, in seconds since the UNIX epoch
+    pub timestamp: u64,
+    /// The `seaplane formation` subcommand which made the change
+    pub command: String,
+    /// The active configurations after the change, which is empty when the Formation Instance was
+    /// landed
+    pub configurations: Vec<DeployedConfiguration>,
+}
+
+impl Deployment {
+    /// The active configurations to set in order to return to this deployment
+    pub fn to_model(&self) -> Result<ActiveConfigurationsModel> {
+        let mut model = ActiveConfigurationsModel::new();
+        for cfg in &self.configurations {
+            #[cfg_attr(not(feature = "unstable"), allow(unused_mut))]
+            let mut builder = ActiveConfigurationModel::builder().uuid(cfg.uuid);
+            #[cfg(feature = "unstable")]
+            {
+                if let Some(weight) = cfg.traffic_weight {
+                    builder = builder.traffic_weight(weight);
+                }
+            }
+            model.add_configuration_mut(builder.build()?);
+        }
+        Ok(model)
+    }
+}
+
+impl Formations {
+    /// Records that `command` left the remote Formation Instance `name` with the `active`
+    /// configurations, where `now` is the current time in seconds since the UNIX epoch
+    ///
+    /// The images of each configuration come from the local Formation Plans, or from earlier
+    /// deployments for configurations which were never in a local Plan.
+    pub fn record_deployment(
+        &mut self,
+        name: &str,
+        command: String,
+        active: &ActiveConfigurationsModel,
+        now: u64,
+    ) {
+        let configurations = active
+            .iter()
+            .map(|ac| DeployedConfiguration {
+                uuid: *ac.uuid(),
+                traffic_weight: traffic_weight(ac),
+                images: self.images_of(name, *ac.uuid()),
+            })
+            .collect();
+        self.deployments
+            .entry(name.to_owned())
+            .or_default()
+            .push(Deployment { timestamp: now, command, configurations });
+    }
+
+    /// The deployments of the remote Formation Instance `name`, oldest first
+    pub fn deployments(&self, name: &str) -> &[Deployment] {
+        self.deployments
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn images_of(&self, name: &str, uuid: Uuid) -> Vec<String> {
+        if let Some(cfg) = self.get_configuration_by_uuid(uuid) {
+            return cfg.model.flights().iter().map(|f| f.image_str()).collect();
+        }
+        self.deployments(name)
+            .iter()
+            .rev()
+            .flat_map(|d| &d.configurations)
+            .find(|cfg| cfg.uuid == uuid)
+            .map(|cfg| cfg.images.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "unstable")]
+fn traffic_weight(ac: &ActiveConfigurationModel) -> Option<f32> { ac.traffic_weight() }
+
+// Without the unstable feature the weight isn't exposed, and everything is left at the default
+#[cfg(not(feature = "unstable"))]
+fn traffic_weight(_ac: &ActiveConfigurationModel) -> Option<f32> { None }
+
+/// The deployments shown by `formation history`, numbered from 1 for the oldest
+#[derive(Debug, Clone)]
+pub struct DeploymentHistory<'a> {
+    pub deployments: &'a [Deployment],
+    /// The current time in seconds since the UNIX epoch, used for the age of each deployment
+    pub now: u64,
+}
+
+impl<'a> DeploymentHistory<'a> {
+    /// Returns deployment number `n`
+    pub fn get(&self, n: usize) -> Result<&'a Deployment> {
+        n.checked_sub(1)
+            .and_then(|i| self.deployments.get(i))
+            .ok_or_else(|| {
+                CliErrorKind::OneOff(format!(
+                    "there is no deployment {n}, only 1 to {}",
+                    self.deployments.len()
+                ))
+                .into_err()
+            })
+    }
+}
+
+impl Output for DeploymentHistory<'_> {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self.deployments)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(tw, "#\tAGE\tCOMMAND\tCONFIGURATION\tWEIGHT\tIMAGES")?;
+        for (i, d) in self.deployments.iter().enumerate() {
+            let age = age(self.now.saturating_sub(d.timestamp));
+            if d.configurations.is_empty() {
+                writeln!(tw, "{}\t{age}\t{}\t-\t-\t-", i + 1, d.command)?;
+            }
+            for (j, cfg) in d.configurations.iter().enumerate() {
+                let weight = cfg
+                    .traffic_weight
+                    .map(|w| w.to_string())
+                    .unwrap_or_else(|| "-".into());
+                let images = if cfg.images.is_empty() { "-".into() } else { cfg.images.join(",") };
+                if j == 0 {
+                    write!(tw, "{}\t{age}\t{}", i + 1, d.command)?;
+                } else {
+                    write!(tw, "\t\t")?;
+                }
+                writeln!(tw, "\t{}\t{weight}\t{images}", cfg.uuid)?;
+            }
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing deployment history"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
+/// A short human readable age such as `5m ago`
+fn age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s ago"),
+        s if s < 60 * 60 => format!("{}m ago", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h ago", s / (60 * 60)),
+        s => format!("{}d ago", s / (24 * 60 * 60)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use seaplane::api::compute::v1::{
+        Flight as FlightModel, FormationConfiguration as FormationConfigurationModel,
+    };
+
+    use super::*;
+    use crate::ops::formation::FormationConfiguration;
+
+    const LOCAL: Uuid = Uuid::from_u128(0xAA);
+    const REMOTE: Uuid = Uuid::from_u128(0xBB);
+
+    fn active(uuids: &[Uuid]) -> ActiveConfigurationsModel {
+        let mut model = ActiveConfigurationsModel::new();
+        for uuid in uuids {
+            model.add_configuration_mut(
+                ActiveConfigurationModel::builder()
+                    .uuid(*uuid)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        model
+    }
+
+    fn formations() -> Formations {
+        let model = FormationConfigurationModel::builder()
+            .add_flight(
+                FlightModel::builder()
+                    .name("web")
+                    .image("foo.com/bar:latest")
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut formations = Formations::default();
+        formations
+            .configurations
+            .push(FormationConfiguration::with_uuid(LOCAL, model));
+        formations
+    }
+
+    #[test]
+    fn records_images() {
+        let mut formations = formations();
+        formations.record_deployment("stubb", "launch".into(), &active(&[LOCAL]), 100);
+        formations.record_deployment("stubb", "land".into(), &active(&[]), 200);
+        formations.record_deployment("pequod", "launch".into(), &active(&[REMOTE]), 300);
+
+        let deployments = formations.deployments("stubb");
+        assert_eq!(deployments.len(), 2);
+        assert_eq!(deployments[0].configurations[0].images, ["foo.com/bar:latest"]);
+        assert!(deployments[1].configurations.is_empty());
+        assert!(formations.deployments("pequod")[0].configurations[0]
+            .images
+            .is_empty());
+        assert!(formations.deployments("ahab").is_empty());
+
+        // Configurations only known remotely keep the images of earlier deployments
+        formations.deployments.get_mut("pequod").unwrap()[0].configurations[0].images =
+            vec!["foo.com/baz:latest".into()];
+        formations.record_deployment("pequod", "rollback --to 1".into(), &active(&[REMOTE]), 400);
+        assert_eq!(
+            formations.deployments("pequod")[1].configurations[0].images,
+            ["foo.com/baz:latest"]
+        );
+    }
+
+    #[test]
+    fn numbered_from_one() {
+        let mut formations = formations();
+        formations.record_deployment("stubb", "launch".into(), &active(&[LOCAL]), 100);
+        let history = DeploymentHistory { deployments: formations.deployments("stubb"), now: 100 };
+        assert_eq!(history.get(1).unwrap().command, "launch");
+        assert!(history.get(0).is_err());
+        assert!(history.get(2).is_err());
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(age(59), "59s ago");
+        assert_eq!(age(61), "1m ago");
+        assert_eq!(age(2 * 60 * 60), "2h ago");
+        assert_eq!(age(3 * 24 * 60 * 60), "3d ago");
+    }
+}
@@ -0,0 +1,251 @@
+use std::io::Write;
+
+use seaplane::api::compute::v1::{
+    ContainerStatus, Containers as ContainersModel,
+    FormationConfiguration as FormationConfigurationModel,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    printer::{printer, Output},
+};
+
+/// Where a launching Formation Instance is at, as far as `formation launch --wait` is concerned
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LaunchState {
+    /// Some Flights don't have their minimum number of containers running yet
+    Pending,
+    /// Every Flight has at least its minimum number of containers running
+    Running,
+    /// At least one container stopped with a non-zero exit status, or with the v2 Compute API a
+    /// Flight is unhealthy
+    Failed,
+}
+
+/// A container which stopped with a non-zero exit status
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedContainer {
+    pub container_id: Uuid,
+    pub exit_status: i32,
+}
+
+/// The containers of a single Flight of a launched configuration compared to its minimum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlightProgress {
+    pub configuration_id: Uuid,
+    pub name: String,
+    pub minimum: u64,
+    pub running: u64,
+    pub starting: u64,
+    pub stopped: u64,
+    pub failed: Vec<FailedContainer>,
+}
+
+impl FlightProgress {
+    fn new(configuration_id: Uuid, name: String, minimum: u64) -> Self {
+        Self {
+            configuration_id,
+            name,
+            minimum,
+            running: 0,
+            starting: 0,
+            stopped: 0,
+            failed: Vec::new(),
+        }
+    }
+}
+
+/// The progress of the Flights of the configurations launched by `formation launch --wait`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct LaunchProgress {
+    flights: Vec<FlightProgress>,
+}
+
+impl LaunchProgress {
+    /// Wait for every Flight of the configuration `uuid` to reach its minimum
+    pub fn add_configuration(&mut self, uuid: Uuid, model: &FormationConfigurationModel) {
+        self.flights.extend(
+            model
+                .flights()
+                .iter()
+                .map(|f| FlightProgress::new(uuid, f.name().to_owned(), f.minimum())),
+        );
+    }
+
+    /// Recount the containers of each Flight, ignoring those of any other configuration
+    pub fn update(&mut self, containers: &ContainersModel) {
+        for flight in &mut self.flights {
+            *flight = FlightProgress::new(
+                flight.configuration_id,
+                std::mem::take(&mut flight.name),
+                flight.minimum,
+            );
+        }
+        for c in containers.iter() {
+            let flight = match self
+                .flights
+                .iter_mut()
+                .find(|f| f.configuration_id == c.configuration_id && f.name == c.flight_name)
+            {
+                Some(flight) => flight,
+                None => continue,
+            };
+            match c.status {
+                ContainerStatus::Running => flight.running += 1,
+                ContainerStatus::Started => flight.starting += 1,
+                ContainerStatus::Stopped => {
+                    flight.stopped += 1;
+                    if let Some(exit_status) = c.exit_status.filter(|s| *s != 0) {
+                        flight
+                            .failed
+                            .push(FailedContainer { container_id: c.container_id, exit_status });
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub fn state(&self) -> LaunchState {
+        if self.flights.iter().any(|f| !f.failed.is_empty()) {
+            LaunchState::Failed
+        } else if self.flights.iter().all(|f| f.running >= f.minimum) {
+            LaunchState::Running
+        } else {
+            LaunchState::Pending
+        }
+    }
+
+    /// A single line summary of each Flight, suitable for a progress bar
+    pub fn message(&self) -> String {
+        self.flights
+            .iter()
+            .map(|f| {
+                format!(
+                    "{}: {}/{} running, {} starting, {} stopped",
+                    f.name, f.running, f.minimum, f.starting, f.stopped
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl Output for LaunchProgress {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(tw, "CONFIGURATION\tFLIGHT\tRUNNING\tMINIMUM\tSTARTING\tSTOPPED")?;
+        for f in &self.flights {
+            writeln!(
+                tw,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                f.configuration_id, f.name, f.running, f.minimum, f.starting, f.stopped
+            )?;
+        }
+        tw.flush()?;
+
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing launch progress"))?;
+        {
+            let mut ptr = printer();
+            ptr.write_all(&page)?;
+            ptr.flush()?;
+        }
+
+        for f in &self.flights {
+            for c in &f.failed {
+                cli_print!("Container {} of Flight '", c.container_id);
+                cli_print!(@Yellow, "{}", f.name);
+                cli_println!("' exited with status {}", c.exit_status);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use seaplane::api::compute::v1::Flight as FlightModel;
+    use serde_json::json;
+
+    use super::*;
+
+    const CFG: Uuid = Uuid::from_u128(0xAA);
+
+    fn progress() -> LaunchProgress {
+        let model = FormationConfigurationModel::builder()
+            .add_flight(
+                FlightModel::builder()
+                    .name("web")
+                    .image("foo.com/bar:latest")
+                    .minimum(2)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut progress = LaunchProgress::default();
+        progress.add_configuration(CFG, &model);
+        progress
+    }
+
+    fn containers(statuses: &[(&str, Option<i32>)]) -> ContainersModel {
+        serde_json::from_value(json!(statuses
+            .iter()
+            .enumerate()
+            .map(|(i, (status, exit_status))| json!({
+                "container_id": Uuid::from_u128(i as u128),
+                "status": status,
+                "flight_name": "web",
+                "configuration_id": CFG,
+                "exit_status": exit_status,
+            }))
+            .collect::<Vec<_>>()))
+        .unwrap()
+    }
+
+    #[test]
+    fn launch_state() {
+        let mut progress = progress();
+        progress.update(&containers(&[("running", None), ("started", None)]));
+        assert_eq!(progress.state(), LaunchState::Pending);
+        assert_eq!(progress.message(), "web: 1/2 running, 1 starting, 0 stopped");
+
+        progress.update(&containers(&[("running", None), ("running", None), ("stopped", Some(0))]));
+        assert_eq!(progress.state(), LaunchState::Running);
+
+        progress.update(&containers(&[("running", None), ("stopped", Some(137))]));
+        assert_eq!(progress.state(), LaunchState::Failed);
+        assert_eq!(
+            progress.flights[0].failed,
+            [FailedContainer { container_id: Uuid::from_u128(1), exit_status: 137 }]
+        );
+    }
+
+    #[test]
+    fn ignores_other_configurations() {
+        let mut progress = progress();
+        let other: ContainersModel = serde_json::from_value(json!([{
+            "container_id": Uuid::from_u128(1),
+            "status": "stopped",
+            "flight_name": "web",
+            "configuration_id": Uuid::from_u128(0xbb),
+            "exit_status": 1,
+        }]))
+        .unwrap();
+        progress.update(&other);
+        assert_eq!(progress.state(), LaunchState::Pending);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{io::Write, result::Result as StdResult, str::FromStr};
+
+use seaplane::api::compute::v1::{
+    ActiveConfiguration as ActiveConfigurationModel,
+    ActiveConfigurations as ActiveConfigurationsModel,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, CliErrorKind, Result},
+    printer::{printer, Output},
+};
+
+/// A `CFG=WEIGHT` pair used to change the traffic weight of a single configuration, where `CFG` is
+/// the UUID (or an unambiguous prefix of it) of an active configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrafficWeightSpec {
+    pub cfg: String,
+    pub weight: f32,
+}
+
+impl FromStr for TrafficWeightSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        let (cfg, weight) = s
+            .split_once('=')
+            .ok_or_else(|| String::from("expected a value in the form of CFG=WEIGHT"))?;
+        if cfg.is_empty() {
+            return Err(String::from("missing configuration UUID before '='"));
+        }
+        let weight: f32 = weight
+            .parse()
+            .map_err(|_| format!("invalid traffic weight '{weight}'"))?;
+        if !weight.is_finite() || weight < 0. {
+            return Err(format!("traffic weight can't be negative or infinite, found '{weight}'"));
+        }
+        Ok(Self { cfg: cfg.to_owned(), weight })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrafficWeight {
+    pub configuration_id: Uuid,
+    pub traffic_weight: f32,
+}
+
+/// The traffic weights of all active configurations of a Formation Instance
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct TrafficWeights {
+    inner: Vec<TrafficWeight>,
+}
+
+impl From<&ActiveConfigurationsModel> for TrafficWeights {
+    fn from(model: &ActiveConfigurationsModel) -> Self {
+        Self {
+            inner: model
+                .iter()
+                .map(|ac| TrafficWeight {
+                    configuration_id: *ac.uuid(),
+                    // The API treats a missing weight the same as the default weight of 1
+                    traffic_weight: ac.traffic_weight().unwrap_or(1.),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TrafficWeights {
+    pub fn is_empty(&self) -> bool { self.inner.is_empty() }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TrafficWeight> { self.inner.iter() }
+
+    fn total(&self) -> f32 { self.inner.iter().map(|tw| tw.traffic_weight).sum() }
+
+    /// Sets the weight of the single configuration whose UUID starts with `spec.cfg`
+    pub fn set(&mut self, spec: &TrafficWeightSpec) -> Result<()> {
+        let uuid = uuid_of_prefix(self.inner.iter().map(|tw| &tw.configuration_id), &spec.cfg)?;
+        for tw in self
+            .inner
+            .iter_mut()
+            .filter(|tw| tw.configuration_id == uuid)
+        {
+            tw.traffic_weight = spec.weight;
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, uuid: Uuid) -> bool {
+        self.inner.iter().any(|tw| tw.configuration_id == uuid)
+    }
+
+    /// Adds `uuid` with the average weight of the other configurations so that it receives an even
+    /// share of the traffic, or the default weight of 1 if there are no other configurations
+    pub fn activate(&mut self, uuid: Uuid) {
+        if self.contains(uuid) {
+            return;
+        }
+        let weight =
+            if self.inner.is_empty() { 1. } else { self.total() / self.inner.len() as f32 };
+        self.inner
+            .push(TrafficWeight { configuration_id: uuid, traffic_weight: weight });
+    }
+
+    pub fn deactivate(&mut self, uuid: Uuid) {
+        self.inner.retain(|tw| tw.configuration_id != uuid);
+    }
+
+    /// Gives `new` the place and weight of `old`, which is deactivated
+    pub fn replace(&mut self, old: Uuid, new: Uuid) {
+        self.deactivate(new);
+        for tw in self
+            .inner
+            .iter_mut()
+            .filter(|tw| tw.configuration_id == old)
+        {
+            tw.configuration_id = new;
+        }
+    }
+
+    /// Returns the weights with `percent` of the traffic sent to `target`, and the rest split
+    /// between the other configurations in proportion to their current weight. At 100% the other
+    /// configurations are left out entirely.
+    ///
+    /// `target` is added if it isn't one of the configurations yet.
+    pub fn rollout_step(&self, target: Uuid, percent: u8) -> Self {
+        let percent = f32::from(percent.min(100));
+        let others: Vec<_> = self
+            .inner
+            .iter()
+            .filter(|tw| tw.configuration_id != target)
+            .collect();
+        let others_total: f32 = others.iter().map(|tw| tw.traffic_weight).sum();
+
+        let mut inner = vec![TrafficWeight { configuration_id: target, traffic_weight: percent }];
+        if percent < 100. {
+            inner.extend(others.into_iter().map(|tw| TrafficWeight {
+                configuration_id: tw.configuration_id,
+                traffic_weight: if others_total > 0. {
+                    (100. - percent) * tw.traffic_weight / others_total
+                } else {
+                    0.
+                },
+            }));
+        }
+        Self { inner }
+    }
+
+    /// Returns the weights with no traffic sent to the `drained` configurations, and the rest split
+    /// between the other configurations in proportion to their current weight. The drained
+    /// configurations stay active with a weight of zero.
+    pub fn drain(&self, drained: &[Uuid]) -> Result<Self> {
+        let mut tws = self.clone();
+        for tw in &mut tws.inner {
+            if drained.contains(&tw.configuration_id) {
+                tw.traffic_weight = 0.;
+            }
+        }
+        tws.normalize()?;
+        Ok(tws)
+    }
+
+    /// Scales the weights so they sum up to 100, which makes each weight the percentage of traffic
+    /// the configuration receives
+    pub fn normalize(&mut self) -> Result<()> {
+        let total = self.total();
+        if total <= 0. {
+            return Err(CliErrorKind::OneOff(
+                "at least one configuration must have a traffic weight greater than zero".into(),
+            )
+            .into_err());
+        }
+        for tw in &mut self.inner {
+            tw.traffic_weight = tw.traffic_weight * 100. / total;
+        }
+        Ok(())
+    }
+
+    pub fn to_model(&self) -> Result<ActiveConfigurationsModel> {
+        let mut model = ActiveConfigurationsModel::new();
+        for tw in &self.inner {
+            model.add_configuration_mut(
+                ActiveConfigurationModel::builder()
+                    .uuid(tw.configuration_id)
+                    .traffic_weight(tw.traffic_weight)
+                    .build()?,
+            );
+        }
+        Ok(model)
+    }
+
+    fn impl_print_table(&self) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        writeln!(tw, "CONFIGURATION\tWEIGHT\tTRAFFIC")?;
+
+        let total = self.total();
+        for w in &self.inner {
+            let percent = if total > 0. { w.traffic_weight * 100. / total } else { 0. };
+            writeln!(tw, "{}\t{}\t{percent:.1}%", w.configuration_id, w.traffic_weight)?;
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing traffic weights"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Output for TrafficWeights {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> { self.impl_print_table() }
+}
+
+/// Returns the single UUID which starts with `prefix`
+pub fn uuid_of_prefix<'a, I>(uuids: I, prefix: &str) -> Result<Uuid>
+where
+    I: IntoIterator<Item = &'a Uuid>,
+{
+    let lower = prefix.to_ascii_lowercase();
+    let mut matches = uuids
+        .into_iter()
+        .filter(|uuid| uuid.to_string().starts_with(&lower));
+    match (matches.next(), matches.next()) {
+        (Some(uuid), None) => Ok(*uuid),
+        (None, _) => Err(CliErrorKind::NoMatchingItem(prefix.to_owned()).into_err()),
+        (Some(_), Some(_)) => Err(CliErrorKind::AmbiguousItem(prefix.to_owned()).into_err()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn weights(ws: &[(&str, f32)]) -> TrafficWeights {
+        TrafficWeights {
+            inner: ws
+                .iter()
+                .map(|(id, w)| TrafficWeight {
+                    configuration_id: id.parse().unwrap(),
+                    traffic_weight: *w,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_spec() {
+        assert_eq!(
+            "abc=2.5".parse::<TrafficWeightSpec>(),
+            Ok(TrafficWeightSpec { cfg: "abc".into(), weight: 2.5 })
+        );
+        assert!("abc".parse::<TrafficWeightSpec>().is_err());
+        assert!("=1".parse::<TrafficWeightSpec>().is_err());
+        assert!("abc=-1".parse::<TrafficWeightSpec>().is_err());
+        assert!("abc=NaN".parse::<TrafficWeightSpec>().is_err());
+    }
+
+    #[test]
+    fn set_and_normalize() {
+        let mut tws = weights(&[
+            ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+            ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+        ]);
+        tws.set(&"AB=3".parse().unwrap()).unwrap();
+        tws.normalize().unwrap();
+        assert_eq!(
+            tws,
+            weights(&[
+                ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 25.),
+                ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 75.),
+            ])
+        );
+
+        // No match, and ambiguous
+        assert!(tws.set(&"c=1".parse().unwrap()).is_err());
+        assert!(tws.set(&"a=1".parse().unwrap()).is_err());
+
+        tws.set(&"aa=0".parse().unwrap()).unwrap();
+        tws.set(&"ab=0".parse().unwrap()).unwrap();
+        assert!(tws.normalize().is_err());
+    }
+
+    #[test]
+    fn activate_deactivate() {
+        let mut tws = weights(&[
+            ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 30.),
+            ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 10.),
+        ]);
+        let uuid: Uuid = "bb8522e7-06cc-4e35-8966-484ae26e02a9".parse().unwrap();
+        tws.activate(uuid);
+        tws.activate(uuid);
+        assert_eq!(
+            tws,
+            weights(&[
+                ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 30.),
+                ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 10.),
+                ("bb8522e7-06cc-4e35-8966-484ae26e02a9", 20.),
+            ])
+        );
+        tws.deactivate(uuid);
+        assert!(!tws.contains(uuid));
+
+        let mut tws = TrafficWeights::default();
+        tws.activate(uuid);
+        assert_eq!(tws, weights(&[("bb8522e7-06cc-4e35-8966-484ae26e02a9", 1.)]));
+    }
+
+    #[test]
+    fn drain() {
+        let tws = weights(&[
+            ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 2.),
+            ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+            ("bb8522e7-06cc-4e35-8966-484ae26e02a9", 3.),
+        ]);
+        let drained: Uuid = "ab8522e7-06cc-4e35-8966-484ae26e02a9".parse().unwrap();
+        assert_eq!(
+            tws.drain(&[drained]).unwrap(),
+            weights(&[
+                ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 40.),
+                ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 0.),
+                ("bb8522e7-06cc-4e35-8966-484ae26e02a9", 60.),
+            ])
+        );
+
+        let all: Vec<Uuid> = tws.iter().map(|tw| tw.configuration_id).collect();
+        assert!(tws.drain(&all).is_err());
+    }
+
+    #[test]
+    fn replace() {
+        let mut tws = weights(&[
+            ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 3.),
+            ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+        ]);
+        let old: Uuid = "aa8522e7-06cc-4e35-8966-484ae26e02a9".parse().unwrap();
+        let new: Uuid = "bb8522e7-06cc-4e35-8966-484ae26e02a9".parse().unwrap();
+        tws.replace(old, new);
+        assert_eq!(
+            tws,
+            weights(&[
+                ("bb8522e7-06cc-4e35-8966-484ae26e02a9", 3.),
+                ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+            ])
+        );
+    }
+
+    #[test]
+    fn rollout_steps() {
+        let old = weights(&[
+            ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 3.),
+            ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 1.),
+        ]);
+        let target: Uuid = "bb8522e7-06cc-4e35-8966-484ae26e02a9".parse().unwrap();
+        assert_eq!(
+            old.rollout_step(target, 20),
+            weights(&[
+                ("bb8522e7-06cc-4e35-8966-484ae26e02a9", 20.),
+                ("aa8522e7-06cc-4e35-8966-484ae26e02a9", 60.),
+                ("ab8522e7-06cc-4e35-8966-484ae26e02a9", 20.),
+            ])
+        );
+        assert_eq!(
+            old.rollout_step(target, 100),
+            weights(&[("bb8522e7-06cc-4e35-8966-484ae26e02a9", 100.)])
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "compute_api_v2")]
+use seaplane::api::compute::v2::FlightHealthStatus;
+use uuid::Uuid;
+
+#[cfg(feature = "compute_api_v2")]
+use super::OpStatus;
+use super::{FlightStatus, FormationConfigStatus, FormationStatus};
+
+/// ANSI escapes which clear the terminal and move the cursor to the top left corner
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// The width of each column after the Flight name
+const COLUMN: usize = 12;
+
+/// What `formation status --watch` compares between refreshes to decide if a Flight changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FlightState {
+    Containers {
+        running: u64,
+        starting: u64,
+        stopped: u64,
+    },
+    /// The health reported by the v2 Compute API
+    #[cfg(feature = "compute_api_v2")]
+    Health(String),
+}
+
+/// A Flight is identified by its Formation, configuration (which v2 Formations don't have) and name
+type FlightKey = (String, Option<Uuid>, String);
+
+#[derive(Debug, Clone)]
+struct Seen {
+    state: FlightState,
+    /// When the state last changed in seconds since the UNIX epoch, or `None` if it hasn't changed
+    /// since the watch started
+    changed_at: Option<u64>,
+}
+
+/// Prints the status tree of `formation status --watch`, highlighting the counts which changed
+/// since the previous refresh
+#[derive(Debug, Default)]
+pub struct StatusWatch {
+    redraw: bool,
+    printed: bool,
+    seen: HashMap<FlightKey, Seen>,
+    next: HashMap<FlightKey, Seen>,
+}
+
+impl StatusWatch {
+    /// With `redraw` the terminal is cleared before each refresh, otherwise each refresh is printed
+    /// after the previous one
+    pub fn new(redraw: bool) -> Self { Self { redraw, ..Default::default() } }
+
+    /// Print one refresh of `statuses`, where `now` is the current time in seconds since the UNIX
+    /// epoch
+    pub fn print(&mut self, statuses: &[FormationStatus], interval_secs: u64, now: u64) {
+        if self.redraw {
+            cli_print!("{CLEAR_SCREEN}");
+            cli_println!("Every {interval_secs}s (press Ctrl+C to stop)\n");
+        } else if self.printed {
+            cli_println!("");
+        }
+        self.printed = true;
+
+        for status in statuses {
+            status.status.print_sym();
+            cli_print!(" Formation {}: ", status.name);
+            status.status.print();
+            cli_println!("");
+
+            #[cfg(feature = "compute_api_v2")]
+            {
+                if !status.flights.is_empty() {
+                    self.print_v2_flights(status, now);
+                    continue;
+                }
+            }
+
+            let cfgs: Vec<_> = status
+                .configurations
+                .inner
+                .iter()
+                .filter(|cfg| !cfg.flights.is_empty())
+                .collect();
+            for (i, cfg) in cfgs.iter().enumerate() {
+                self.print_configuration(&status.name, cfg, i == cfgs.len() - 1, now);
+            }
+        }
+
+        self.seen = std::mem::take(&mut self.next);
+    }
+
+    fn print_configuration(
+        &mut self,
+        formation: &str,
+        cfg: &FormationConfigStatus,
+        last: bool,
+        now: u64,
+    ) {
+        // Chars we'll need: │ ├ ─ └
+        cli_print!("{}", if last { "└─" } else { "├─" });
+        cfg.status.print_sym();
+        cli_print!(" Configuration {}: ", cfg.uuid);
+        cfg.status.print();
+        cli_println!("");
+
+        let prefix = if last { "  " } else { "│ " };
+        let width = name_width(cfg.flights.inner.iter().map(|f| f.name.as_str()));
+        cli_println!(
+            "{prefix}│   {:width$}{:COLUMN$}{:COLUMN$}{:COLUMN$}{:COLUMN$}CHANGED",
+            "FLIGHT",
+            "RUNNING",
+            "STARTING",
+            "STOPPED",
+            "MIN / MAX"
+        );
+        for (i, flight) in cfg.flights.inner.iter().enumerate() {
+            cli_print!("{prefix}{}", if i == cfg.flights.inner.len() - 1 { "└─" } else { "├─" });
+            flight.get_status().print_sym();
+            cli_print!(" {:width$}", flight.name);
+
+            let state = FlightState::Containers {
+                running: flight.running,
+                starting: flight.starting,
+                stopped: flight.exited + flight.errored,
+            };
+            let key = (formation.to_owned(), Some(cfg.uuid), flight.name.clone());
+            let (previous, changed_at) = self.track(key, state, now);
+            let previous = match previous {
+                Some(FlightState::Containers { running, starting, stopped }) => {
+                    [Some(running), Some(starting), Some(stopped)]
+                }
+                _ => [None; 3],
+            };
+            let current = [flight.running, flight.starting, flight.exited + flight.errored];
+            for (n, prev) in current.into_iter().zip(previous) {
+                print_count(n, prev);
+            }
+            cli_print!("{:COLUMN$}", min_max(flight));
+            cli_println!("{}", changed(changed_at, now));
+        }
+        cli_println!("{}", if last { "" } else { "│" });
+    }
+
+    #[cfg(feature = "compute_api_v2")]
+    fn print_v2_flights(&mut self, status: &FormationStatus, now: u64) {
+        let width = name_width(status.flights.iter().map(|f| f.name.as_str()));
+        cli_println!("│   {:width$}{:COLUMN$}CHANGED", "FLIGHT", "HEALTH");
+        for (i, flight) in status.flights.iter().enumerate() {
+            cli_print!("{}", if i == status.flights.len() - 1 { "└─" } else { "├─" });
+            let op_status = match flight.health {
+                FlightHealthStatus::Healthy => OpStatus::Up,
+                FlightHealthStatus::Unhealthy => OpStatus::Down,
+                _ => OpStatus::Degraded,
+            };
+            op_status.print_sym();
+            cli_print!(" {:width$}", flight.name);
+
+            let health = flight.health.as_ref().to_owned();
+            let key = (status.name.clone(), None, flight.name.clone());
+            let (previous, changed_at) = self.track(key, FlightState::Health(health.clone()), now);
+            let cell = format!("{health:COLUMN$}");
+            if matches!(previous, Some(prev) if prev != FlightState::Health(health)) {
+                cli_print!(@Cyan, "{cell}");
+            } else {
+                op_status.print_msg(&cell);
+            }
+            cli_println!("{}", changed(changed_at, now));
+        }
+    }
+
+    /// Records the `state` of the Flight `key`, returning its state at the previous refresh (if it
+    /// was seen) and when it last changed
+    fn track(
+        &mut self,
+        key: FlightKey,
+        state: FlightState,
+        now: u64,
+    ) -> (Option<FlightState>, Option<u64>) {
+        let (previous, changed_at) = match self.seen.get(&key) {
+            Some(seen) if seen.state != state => (Some(seen.state.clone()), Some(now)),
+            Some(seen) => (Some(seen.state.clone()), seen.changed_at),
+            None => (None, None),
+        };
+        self.next.insert(key, Seen { state, changed_at });
+        (previous, changed_at)
+    }
+}
+
+fn name_width<'a>(names: impl Iterator<Item = &'a str>) -> usize {
+    names.map(str::len).max().unwrap_or_default().max(10)
+}
+
+/// Prints a count, highlighting it along with the difference if it changed since `previous`
+fn print_count(n: u64, previous: Option<u64>) {
+    match previous {
+        Some(prev) if prev != n => {
+            let cell = format!("{n} ({:+})", n as i64 - prev as i64);
+            cli_print!(@Cyan, "{cell:COLUMN$}");
+        }
+        _ => cli_print!("{n:<COLUMN$}"),
+    }
+}
+
+fn min_max(flight: &FlightStatus) -> String {
+    match flight.maximum {
+        Some(max) => format!("{} / {max}", flight.minimum),
+        None => format!("{} / AUTO", flight.minimum),
+    }
+}
+
+fn changed(changed_at: Option<u64>, now: u64) -> String {
+    match changed_at {
+        Some(at) => format!("{}s ago", now.saturating_sub(at)),
+        None => "-".into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(flight: &str) -> FlightKey { ("stubb".into(), Some(Uuid::nil()), flight.into()) }
+
+    fn containers(running: u64, starting: u64, stopped: u64) -> FlightState {
+        FlightState::Containers { running, starting, stopped }
+    }
+
+    fn refresh(watch: &mut StatusWatch) { watch.seen = std::mem::take(&mut watch.next); }
+
+    #[test]
+    fn tracks_changes_between_refreshes() {
+        let mut watch = StatusWatch::new(false);
+        assert_eq!(watch.track(key("flask"), containers(0, 2, 0), 100), (None, None));
+        refresh(&mut watch);
+
+        assert_eq!(
+            watch.track(key("flask"), containers(1, 1, 0), 105),
+            (Some(containers(0, 2, 0)), Some(105))
+        );
+        refresh(&mut watch);
+
+        // Unchanged Flights keep the time of their last change
+        assert_eq!(
+            watch.track(key("flask"), containers(1, 1, 0), 110),
+            (Some(containers(1, 1, 0)), Some(105))
+        );
+        assert_eq!(watch.track(key("pequod"), containers(1, 0, 0), 110), (None, None));
+        refresh(&mut watch);
+
+        // Flights which disappear are forgotten
+        refresh(&mut watch);
+        assert!(watch.seen.is_empty());
+    }
+
+    #[test]
+    fn changed_column() {
+        assert_eq!(changed(None, 100), "-");
+        assert_eq!(changed(Some(95), 100), "5s ago");
+    }
+}
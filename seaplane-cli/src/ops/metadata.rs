@@ -1,7 +1,8 @@
-use std::io::Write;
+use std::{collections::BTreeMap, io::Write};
 
 use seaplane::api::metadata::v1::KeyValue as KeyValueModel;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
 use tabwriter::TabWriter;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -16,7 +17,7 @@ use crate::{
 /// and implement a bunch of additional methods and traits that wouldn't make sense for the models
 ///
 /// We also need to keep track if the values are encoded or not
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct KeyValue {
     pub key: EncodedString,
     pub value: EncodedString,
@@ -62,7 +63,7 @@ impl KeyValue {
     }
 }
 
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct KeyValues {
     inner: Vec<KeyValue>,
@@ -92,6 +93,137 @@ impl KeyValues {
     pub fn keys(&self) -> impl Iterator<Item = EncodedString> + '_ {
         self.inner.iter().map(|kv| kv.key.clone())
     }
+
+    /// Removes the (un-encoded) directory prefix `dir` from any keys that start with it, so that
+    /// the keys of two different directories can be compared to one another
+    pub fn strip_dir(&mut self, dir: &[u8]) -> Result<()> {
+        let engine = ::base64::engine::fast_portable::FastPortable::from(
+            &::base64::alphabet::URL_SAFE,
+            ::base64::engine::fast_portable::NO_PAD,
+        );
+        let mut prefix = dir.to_vec();
+        if !prefix.ends_with(b"/") {
+            prefix.push(b'/');
+        }
+        for kv in self.iter_mut() {
+            let key = kv.key.decoded()?;
+            if let Some(rel) = key.strip_prefix(prefix.as_slice()) {
+                kv.key = EncodedString::new(base64::encode_engine(rel, &engine));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A key whose value differs between two sets of key-value pairs
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedKeyValue {
+    pub key: EncodedString,
+    pub old: EncodedString,
+    pub new: EncodedString,
+    /// A unified diff of the values decoded the same way as `--decode-safe`
+    pub diff: String,
+}
+
+/// The differences between two sets of key-value pairs, i.e. what would need to happen to `old`
+/// in order to arrive at `new`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct KeyValuesDiff {
+    pub added: Vec<KeyValue>,
+    pub removed: Vec<KeyValue>,
+    pub changed: Vec<ChangedKeyValue>,
+}
+
+impl KeyValuesDiff {
+    /// Compares two sets of key-value pairs by their decoded keys and values
+    pub fn new(old: &KeyValues, new: &KeyValues) -> Result<Self> {
+        let mut old_map = BTreeMap::new();
+        for kv in old.iter() {
+            old_map.insert(kv.key.decoded()?, kv);
+        }
+        let mut new_map = BTreeMap::new();
+        for kv in new.iter() {
+            new_map.insert(kv.key.decoded()?, kv);
+        }
+
+        let mut diff = KeyValuesDiff::default();
+        for (key, old_kv) in &old_map {
+            match new_map.get(key) {
+                None => diff.removed.push((*old_kv).clone()),
+                Some(new_kv) => {
+                    if old_kv.value.decoded()? == new_kv.value.decoded()? {
+                        continue;
+                    }
+                    // Values are compared as shown by --decode-safe, so anything that isn't
+                    // safe to display is escaped rather than left out of the diff
+                    let text_diff = TextDiff::from_lines(
+                        &old_kv.value.decoded_safe_lines()?,
+                        &new_kv.value.decoded_safe_lines()?,
+                    )
+                    .unified_diff()
+                    .to_string();
+                    diff.changed.push(ChangedKeyValue {
+                        key: old_kv.key.clone(),
+                        old: old_kv.value.clone(),
+                        new: new_kv.value.clone(),
+                        diff: text_diff,
+                    });
+                }
+            }
+        }
+        for (key, new_kv) in &new_map {
+            if !old_map.contains_key(key) {
+                diff.added.push((*new_kv).clone());
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Returns `true` if there are no differences at all
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Output for KeyValuesDiff {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        if self.is_empty() {
+            cli_println!("No differences");
+            return Ok(());
+        }
+
+        for kv in &self.added {
+            cli_println!(@Green, "+ {}", kv.key.decoded_safe()?);
+        }
+        for kv in &self.removed {
+            cli_println!(@Red, "- {}", kv.key.decoded_safe()?);
+        }
+        for changed in &self.changed {
+            cli_println!(@Yellow, "~ {}", changed.key.decoded_safe()?);
+            let (old, new) = (changed.old.decoded_safe_lines()?, changed.new.decoded_safe_lines()?);
+            let text_diff = TextDiff::from_lines(&old, &new);
+            for hunk in text_diff.unified_diff().iter_hunks() {
+                cli_println!(@Cyan, "    {}", hunk.header());
+                for change in hunk.iter_changes() {
+                    let line = change.to_string_lossy();
+                    let line = line.trim_end_matches(&['\r', '\n'][..]);
+                    match change.tag() {
+                        ChangeTag::Delete => cli_println!(@Red, "    -{line}"),
+                        ChangeTag::Insert => cli_println!(@Green, "    +{line}"),
+                        ChangeTag::Equal => cli_println!("     {line}"),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Output for KeyValues {
@@ -222,4 +354,54 @@ mod tests {
             json!([{"key": "a2V5MQ", "value": "dmFsdWUx"}, {"key": "a2V5Mg", "value": "dmFsdWUy"}, {"key": "a2V5Mw", "value": "dmFsdWUz"}]).to_string()
         );
     }
+
+    #[test]
+    fn diff_keyvalues() {
+        let old = build_kvs();
+        let mut new = KeyValues::default();
+        // key1 => value1 (unchanged)
+        new.insert("a2V5MQ", "dmFsdWUx");
+        // key2 => foo\nbar (changed)
+        new.insert("a2V5Mg", "Zm9vCmJhcg");
+        // key4 => value4 (added)
+        new.insert("a2V5NA", "dmFsdWU0");
+
+        let diff = KeyValuesDiff::new(&old, &new).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].key.to_string(), "a2V5NA");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].key.to_string(), "a2V5Mw");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].key.to_string(), "a2V5Mg");
+        assert_eq!(diff.changed[0].diff, "@@ -1 +1,2 @@\n-value2\n\\ No newline at end of file\n+foo\n+bar\n\\ No newline at end of file\n");
+        assert!(KeyValuesDiff::new(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_keyvalues_decode_safe() {
+        let mut old = KeyValues::default();
+        // key1 => Hey\x01There
+        old.insert("a2V5MQ", "SGV5AVRoZXJl");
+        let mut new = KeyValues::default();
+        // key1 => Hey\x02There
+        new.insert("a2V5MQ", "SGV5AlRoZXJl");
+
+        // Values which aren't safe to display are escaped the same way as --decode-safe
+        let diff = KeyValuesDiff::new(&old, &new).unwrap();
+        assert_eq!(
+            diff.changed[0].diff,
+            "@@ -1 +1 @@\n-Hey\\x01There\n\\ No newline at end of file\n+Hey\\x02There\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn strip_dir_keyvalues() {
+        let mut kvs = KeyValues::default();
+        // foo/bar
+        kvs.insert("Zm9vL2Jhcg", "");
+        // baz
+        kvs.insert("YmF6", "");
+        kvs.strip_dir(b"foo").unwrap();
+        assert_eq!(kvs.keys().map(|k| k.to_string()).collect::<Vec<_>>(), vec!["YmFy", "YmF6"]);
+    }
 }
@@ -0,0 +1,144 @@
+//! Placement presets are named combinations of the `--provider`, `--region`, `--exclude-provider`
+//! and `--exclude-region` values, which can be defined in the `[presets]` table of the
+//! configuration file. A few common presets are built in.
+
+use std::collections::HashSet;
+
+use seaplane::api::shared::v1::{Provider as ProviderModel, Region as RegionModel};
+
+use crate::{
+    cli::{Provider, Region},
+    config::RawPresetConfig,
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    printer::Color,
+};
+
+/// The presets that are available without defining them in a configuration file, as the name and
+/// the allowed regions. Presets of the same name in a configuration file take precedence.
+const BUILTIN_PRESETS: &[(&str, &[RegionModel])] = &[
+    ("eu-only", &[RegionModel::XE]),
+    ("gdpr", &[RegionModel::XE, RegionModel::XU]),
+    ("na-only", &[RegionModel::XN]),
+    ("uk-only", &[RegionModel::XU]),
+];
+
+/// The long help of the `--preset` argument, which has to be kept in sync with `BUILTIN_PRESETS`
+pub static LONG_PRESET: &str = "A named placement preset to expand into provider and region values
+
+Presets are defined in the [presets] table of the configuration file, and the built in presets
+eu-only, gdpr (EU and UK), na-only and uk-only are always available. Any --provider, --region,
+--exclude-provider or --exclude-region values are added to those of the preset.";
+
+/// A placement preset expanded into the same values the CLI arguments produce
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub providers_allowed: HashSet<ProviderModel>,
+    pub providers_denied: HashSet<ProviderModel>,
+    pub regions_allowed: HashSet<RegionModel>,
+    pub regions_denied: HashSet<RegionModel>,
+}
+
+impl Preset {
+    /// Looks up the preset `name` in the configuration, falling back to the built in presets
+    pub fn resolve(ctx: &Ctx, name: &str) -> Result<Self> {
+        if let Some(raw) = ctx.presets.get(name) {
+            return Self::from_config(name, raw);
+        }
+
+        if let Some((_, regions)) = BUILTIN_PRESETS.iter().find(|(n, _)| *n == name) {
+            return Ok(Self {
+                regions_allowed: regions.iter().cloned().collect(),
+                ..Self::default()
+            });
+        }
+
+        let mut names: Vec<&str> = ctx.presets.keys().map(String::as_str).collect();
+        names.extend(BUILTIN_PRESETS.iter().map(|(n, _)| *n));
+        names.sort_unstable();
+        names.dedup();
+
+        Err(CliErrorKind::InvalidCliValue(Some("preset"), name.into())
+            .into_err()
+            .context("(hint: available presets are ")
+            .color_context(Color::Green, names.join(", "))
+            .context(")\n"))
+    }
+
+    /// Expands a preset from the configuration file, the values are parsed the same way as the
+    /// CLI arguments so the same aliases (and `all`) can be used
+    pub fn from_config(name: &str, raw: &RawPresetConfig) -> Result<Self> {
+        fn providers(name: &str, values: &[String]) -> Result<HashSet<ProviderModel>> {
+            values
+                .iter()
+                .map(|v| {
+                    v.parse::<Provider>()
+                        .map(|p| p.into_model())
+                        .map_err(|_| invalid_value(name, "provider", v))
+                })
+                .filter_map(Result::transpose)
+                .collect()
+        }
+        fn regions(name: &str, values: &[String]) -> Result<HashSet<RegionModel>> {
+            values
+                .iter()
+                .map(|v| {
+                    v.parse::<Region>()
+                        .map(|r| r.into_model())
+                        .map_err(|_| invalid_value(name, "region", v))
+                })
+                .filter_map(Result::transpose)
+                .collect()
+        }
+
+        Ok(Self {
+            providers_allowed: providers(name, &raw.providers)?,
+            providers_denied: providers(name, &raw.exclude_providers)?,
+            regions_allowed: regions(name, &raw.regions)?,
+            regions_denied: regions(name, &raw.exclude_regions)?,
+        })
+    }
+}
+
+fn invalid_value(preset: &str, kind: &str, value: &str) -> CliError {
+    CliErrorKind::OneOff(format!(
+        "'{value}' is not a valid {kind} in the placement preset '{preset}' of the configuration file"
+    ))
+    .into_err()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builtin_preset() {
+        let preset = Preset::resolve(&Ctx::default(), "eu-only").unwrap();
+        assert_eq!(preset.regions_allowed, HashSet::from([RegionModel::XE]));
+        assert!(preset.providers_allowed.is_empty());
+    }
+
+    #[test]
+    fn config_preset_overrides_builtin() {
+        let mut ctx = Ctx::default();
+        ctx.presets.insert(
+            "eu-only".into(),
+            RawPresetConfig {
+                regions: vec!["europe".into()],
+                exclude_providers: vec!["GCP".into(), "all".into()],
+                ..Default::default()
+            },
+        );
+        let preset = Preset::resolve(&ctx, "eu-only").unwrap();
+        assert_eq!(preset.regions_allowed, HashSet::from([RegionModel::XE]));
+        assert_eq!(preset.providers_denied, HashSet::from([ProviderModel::GCP]));
+    }
+
+    #[test]
+    fn invalid_preset() {
+        assert!(Preset::resolve(&Ctx::default(), "nope").is_err());
+
+        let raw = RawPresetConfig { regions: vec!["atlantis".into()], ..Default::default() };
+        assert!(Preset::from_config("bad", &raw).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+
+use seaplane::{
+    api::{metadata::v1::Key, shared::v1::Directory},
+    queue::QueuedTask,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    printer::{printer, Output},
+};
+
+/// The ID of a task, which is its key without the queue directory
+pub fn task_id(directory: &Directory, key: &Key) -> String {
+    let key = key.decode();
+    let dir = directory.decode();
+    let id = key
+        .strip_prefix(dir.as_slice())
+        .map(|id| id.strip_prefix(b"/").unwrap_or(id))
+        .unwrap_or(&key);
+    String::from_utf8_lossy(id).into_owned()
+}
+
+/// A task of a queue, in a form that is easy to display
+#[derive(Debug, Clone, Serialize)]
+pub struct Task {
+    pub id: String,
+    pub value: String,
+    pub claimed_by: Option<String>,
+}
+
+/// The tasks of a queue
+#[derive(Debug, Default, Clone)]
+pub struct Tasks {
+    inner: Vec<Task>,
+}
+
+impl Tasks {
+    pub fn new(directory: &Directory, tasks: Vec<QueuedTask>) -> Self {
+        Self {
+            inner: tasks
+                .into_iter()
+                .map(|task| Task {
+                    id: task_id(directory, &task.key),
+                    value: String::from_utf8_lossy(&task.value.decode()).into_owned(),
+                    claimed_by: task.claimed_by,
+                })
+                .collect(),
+        }
+    }
+
+    fn impl_print_table(&self, headers: bool) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+
+        if headers {
+            writeln!(tw, "ID\tCLAIMED-BY\tVALUE")?;
+        }
+
+        for task in &self.inner {
+            writeln!(
+                tw,
+                "{}\t{}\t{}",
+                task.id,
+                task.claimed_by.as_deref().unwrap_or("-"),
+                task.value
+            )?;
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing tasks"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Output for Tasks {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(&self.inner)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        self.impl_print_table(!ctx.queue_ctx.get_or_init().no_header)
+    }
+}
@@ -1,3 +1,5 @@
+pub mod policy;
+
 use std::{collections::BTreeSet, fmt::Display, io::Write};
 
 use seaplane::api::restrict::v1::{RestrictedDirectory as RestrictedDirectoryModel, Restriction};
@@ -11,6 +13,13 @@ use crate::{
     printer::{printer, Output},
 };
 
+// Helper function for displaying region and provider HashSets
+fn join_set<S: Display>(set: BTreeSet<S>) -> String {
+    let mut vec = set.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    vec.sort();
+    vec.join(",")
+}
+
 /// We use our own RestrictedDirectory instead of the models because we need to
 /// *not* enforce base64 encoding, and implement a bunch of additional methods
 /// and traits that wouldn't make sense for the models
@@ -63,13 +72,6 @@ impl Restrictions {
     fn impl_print_table(&self, headers: bool, decode: bool) -> Result<()> {
         let mut tw = TabWriter::new(Vec::new());
 
-        // Helper function for displaying region and provider HashSets
-        fn join_set<S: Display>(set: BTreeSet<S>) -> String {
-            let mut vec = set.iter().map(|s| s.to_string()).collect::<Vec<String>>();
-            vec.sort();
-            vec.join(",")
-        }
-
         if headers {
             writeln!(tw, "API\tDIRECTORY\tSTATE\tREGIONS ALLOWED\tREGIONS DENIED\tPROVIDERS ALLOWED\tPROVIDERS DENIED")?;
         }
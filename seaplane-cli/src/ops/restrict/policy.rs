@@ -0,0 +1,517 @@
+//! Restriction policy files, which declare every restriction that should exist so they can be
+//! kept in version control and applied with `seaplane restrict apply`.
+//!
+//! A policy file is either TOML or YAML and lists restrictions under the `restrictions` key:
+//!
+//! ```toml
+//! [[restrictions]]
+//! api = "config"
+//! directory = "customers/eu"
+//! regions_allowed = ["XE", "XU"]
+//! providers_denied = ["GCP"]
+//! ```
+
+use std::{collections::BTreeSet, fs, io::Write, path::Path, str::FromStr};
+
+use seaplane::api::{
+    restrict::v1::{Api, RestrictedDirectory, Restriction, RestrictionDetails},
+    shared::v1::{Provider, Region},
+};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use strum::VariantNames;
+use tabwriter::TabWriter;
+
+use super::join_set;
+use crate::{
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    printer::{printer, Color, Output},
+};
+
+/// The syntax of a policy file
+#[derive(strum::EnumString, strum::Display, Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum PolicyFormat {
+    Toml,
+    Yaml,
+}
+
+impl PolicyFormat {
+    /// Picks the format from the extension of `path`, defaulting to TOML
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                PolicyFormat::Yaml
+            }
+            _ => PolicyFormat::Toml,
+        }
+    }
+}
+
+/// A single restriction in a policy file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRestriction {
+    pub api: String,
+    /// The directory, for directories that are valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+    /// The URL safe base64 encoded directory, for directories that are not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory_base64: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeSet::is_empty",
+        deserialize_with = "deserialize_known"
+    )]
+    pub regions_allowed: BTreeSet<Region>,
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeSet::is_empty",
+        deserialize_with = "deserialize_known"
+    )]
+    pub regions_denied: BTreeSet<Region>,
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeSet::is_empty",
+        deserialize_with = "deserialize_known"
+    )]
+    pub providers_allowed: BTreeSet<Provider>,
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeSet::is_empty",
+        deserialize_with = "deserialize_known"
+    )]
+    pub providers_denied: BTreeSet<Provider>,
+}
+
+// Policy files are written by users, so unlike in API responses unknown providers and regions
+// (most likely typos) are rejected instead of being kept as `Unknown` values
+fn deserialize_known<'de, D, T>(de: D) -> std::result::Result<BTreeSet<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Ord,
+{
+    Vec::<String>::deserialize(de)?
+        .iter()
+        .map(|v| {
+            v.parse()
+                .map_err(|_| de::Error::custom(format!("unknown provider or region '{v}'")))
+        })
+        .collect()
+}
+
+impl PolicyRestriction {
+    pub fn from_model(restriction: &Restriction) -> Self {
+        let (directory, directory_base64) = match String::from_utf8(restriction.directory.decode())
+        {
+            Ok(dir) => (Some(dir), None),
+            Err(_) => (None, Some(restriction.directory.encoded().to_owned())),
+        };
+        let details = &restriction.details;
+        Self {
+            api: restriction.api.to_string().to_lowercase(),
+            directory,
+            directory_base64,
+            regions_allowed: details.regions_allowed.clone(),
+            regions_denied: details.regions_denied.clone(),
+            providers_allowed: details.providers_allowed.clone(),
+            providers_denied: details.providers_denied.clone(),
+        }
+    }
+
+    pub fn api(&self) -> Result<Api> {
+        match self.api.parse() {
+            Ok(api) if !Api::is_unknown(&api) => Ok(api),
+            _ => Err(CliErrorKind::OneOff(format!(
+                "unknown API '{}' in policy file (expected one of: {})",
+                self.api,
+                Api::VARIANTS
+                    .iter()
+                    .filter(|v| **v != "Unknown")
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .to_lowercase()
+            ))
+            .into_err()),
+        }
+    }
+
+    pub fn directory(&self) -> Result<RestrictedDirectory> {
+        match (&self.directory, &self.directory_base64) {
+            (Some(dir), None) => Ok(RestrictedDirectory::from_unencoded(dir)),
+            (None, Some(dir)) => {
+                // Check that what the user wrote really is valid base64
+                let engine = ::base64::engine::fast_portable::FastPortable::from(
+                    &::base64::alphabet::URL_SAFE,
+                    ::base64::engine::fast_portable::NO_PAD,
+                );
+                let _ = base64::decode_engine(dir, &engine)?;
+                Ok(RestrictedDirectory::from_encoded(dir))
+            }
+            _ => Err(CliErrorKind::OneOff(format!(
+                "each restriction in the policy file needs exactly one of 'directory' or \
+                 'directory_base64' (found a restriction in the {} API with {})",
+                self.api,
+                if self.directory.is_some() { "both" } else { "neither" }
+            ))
+            .into_err()),
+        }
+    }
+
+    pub fn details(&self) -> Result<RestrictionDetails> {
+        let mut builder = RestrictionDetails::builder();
+        for item in &self.providers_allowed {
+            builder = builder.add_allowed_provider(item.clone());
+        }
+        for item in &self.providers_denied {
+            builder = builder.add_denied_provider(item.clone());
+        }
+        for item in &self.regions_allowed {
+            builder = builder.add_allowed_region(item.clone());
+        }
+        for item in &self.regions_denied {
+            builder = builder.add_denied_region(item.clone());
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Every restriction that should exist
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub restrictions: Vec<PolicyRestriction>,
+}
+
+impl Policy {
+    /// Builds a policy describing the given restrictions, ordered by API and directory
+    pub fn from_model(restrictions: &[Restriction]) -> Self {
+        let mut restrictions: Vec<_> = restrictions
+            .iter()
+            .map(PolicyRestriction::from_model)
+            .collect();
+        restrictions.sort_by(|a, b| {
+            (&a.api, &a.directory, &a.directory_base64).cmp(&(
+                &b.api,
+                &b.directory,
+                &b.directory_base64,
+            ))
+        });
+        Self { restrictions }
+    }
+
+    /// Reads a policy file, using the extension of `path` to pick the format
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(CliError::from)
+            .context("\n\tpath: ")
+            .with_color_context(|| (Color::Yellow, path.display().to_string()))?;
+        Self::from_str(&contents, PolicyFormat::from_path(path))
+            .context("\n\tpolicy file: ")
+            .with_color_context(|| (Color::Yellow, path.display().to_string()))
+    }
+
+    pub fn from_str(s: &str, format: PolicyFormat) -> Result<Self> {
+        Ok(match format {
+            PolicyFormat::Toml => toml::from_str(s)?,
+            PolicyFormat::Yaml => serde_yaml::from_str(s)?,
+        })
+    }
+
+    pub fn to_string(&self, format: PolicyFormat) -> Result<String> {
+        Ok(match format {
+            PolicyFormat::Toml => toml::to_string(self)?,
+            PolicyFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+
+    /// Checks every restriction in the policy and converts them into (API, directory, details)
+    /// triples
+    pub fn resolve(&self) -> Result<Vec<(Api, RestrictedDirectory, RestrictionDetails)>> {
+        let mut resolved: Vec<(Api, RestrictedDirectory, RestrictionDetails)> = Vec::new();
+        for restriction in &self.restrictions {
+            let api = restriction.api()?;
+            let directory = restriction.directory()?;
+            if resolved
+                .iter()
+                .any(|(a, d, _)| a == &api && d == &directory)
+            {
+                return Err(CliErrorKind::OneOff(format!(
+                    "the restriction on directory {} in the {} API is listed more than once",
+                    String::from_utf8_lossy(&directory.decode()),
+                    restriction.api
+                ))
+                .into_err());
+            }
+            let details = restriction
+                .details()
+                .context("\n\tdirectory: ")
+                .with_color_context(|| {
+                    (Color::Yellow, String::from_utf8_lossy(&directory.decode()).into_owned())
+                })?;
+            resolved.push((api, directory, details));
+        }
+        Ok(resolved)
+    }
+}
+
+/// What needs to happen to a restriction to make it match a policy
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PlanAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A single change to make to a restriction
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedChange {
+    pub action: PlanAction,
+    pub api: Api,
+    pub directory: RestrictedDirectory,
+    /// The details the restriction will have, or the current ones for deletions
+    pub details: RestrictionDetails,
+}
+
+/// The changes needed to make the existing restrictions match a policy
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Plan {
+    pub changes: Vec<PlannedChange>,
+    /// The number of existing restrictions missing from the policy that are left alone because
+    /// pruning is off
+    #[serde(skip)]
+    pub unmanaged: usize,
+}
+
+impl Plan {
+    /// Compares a `policy` against the `current` restrictions. Restrictions missing from the
+    /// policy are only deleted if `prune` is set.
+    pub fn new(policy: &Policy, current: Vec<Restriction>, prune: bool) -> Result<Self> {
+        let mut plan = Plan::default();
+        let desired = policy.resolve()?;
+
+        for (api, directory, details) in &desired {
+            match current
+                .iter()
+                .find(|r| &r.api == api && &r.directory == directory)
+            {
+                Some(existing) if &existing.details == details => (),
+                Some(_) => plan.changes.push(PlannedChange {
+                    action: PlanAction::Update,
+                    api: api.clone(),
+                    directory: directory.clone(),
+                    details: details.clone(),
+                }),
+                None => plan.changes.push(PlannedChange {
+                    action: PlanAction::Create,
+                    api: api.clone(),
+                    directory: directory.clone(),
+                    details: details.clone(),
+                }),
+            }
+        }
+
+        for restriction in current {
+            if desired
+                .iter()
+                .any(|(a, d, _)| a == &restriction.api && d == &restriction.directory)
+            {
+                continue;
+            }
+            if prune {
+                plan.changes.push(PlannedChange {
+                    action: PlanAction::Delete,
+                    api: restriction.api,
+                    directory: restriction.directory,
+                    details: restriction.details,
+                });
+            } else {
+                plan.unmanaged += 1;
+            }
+        }
+
+        Ok(plan)
+    }
+
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    /// Tells the user about restrictions that are not in the policy and were left alone
+    pub fn hint_unmanaged(&self) {
+        if self.unmanaged > 0 {
+            cli_eprint!(
+                "(hint: {} existing restriction{} not in the policy file, use '",
+                self.unmanaged,
+                if self.unmanaged == 1 { " is" } else { "s are" }
+            );
+            cli_eprint!(@Green, "--prune");
+            cli_eprintln!("' to delete them)");
+        }
+    }
+
+    fn impl_print_table(&self, headers: bool, decode: bool) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+
+        if headers {
+            writeln!(tw, "ACTION\tAPI\tDIRECTORY\tREGIONS ALLOWED\tREGIONS DENIED\tPROVIDERS ALLOWED\tPROVIDERS DENIED")?;
+        }
+
+        for change in &self.changes {
+            write!(tw, "{}\t{}\t", change.action, change.api)?;
+            if decode {
+                tw.write_all(&change.directory.decode())?;
+            } else {
+                write!(tw, "{}", change.directory)?;
+            }
+            writeln!(
+                tw,
+                "\t[{}]\t[{}]\t[{}]\t[{}]",
+                join_set(change.details.regions_allowed.clone()),
+                join_set(change.details.regions_denied.clone()),
+                join_set(change.details.providers_allowed.clone()),
+                join_set(change.details.providers_denied.clone())
+            )?;
+        }
+        tw.flush()?;
+
+        let mut ptr = printer();
+        let page = tw
+            .into_inner()
+            .map_err(|_| CliError::bail("IO flush error writing restriction plan"))?;
+        ptr.write_all(&page)?;
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Output for Plan {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        if self.is_empty() {
+            cli_println!("No changes, the restrictions match the policy file");
+            return Ok(());
+        }
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
+        self.impl_print_table(!restrict_ctx.no_header, restrict_ctx.decode)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use seaplane::api::restrict::v1::RestrictionState;
+
+    use super::*;
+
+    fn restriction(api: Api, dir: &str, region: Region) -> Restriction {
+        Restriction {
+            api,
+            directory: RestrictedDirectory::from_unencoded(dir),
+            details: RestrictionDetails::builder()
+                .add_allowed_region(region)
+                .build()
+                .unwrap(),
+            state: RestrictionState::Enforced,
+        }
+    }
+
+    #[test]
+    fn toml_and_yaml_round_trip() {
+        let policy = Policy::from_model(&[
+            restriction(Api::Locks, "b", Region::XN),
+            restriction(Api::Config, "a", Region::XE),
+        ]);
+        assert_eq!(policy.restrictions[0].api, "config");
+        for format in [PolicyFormat::Toml, PolicyFormat::Yaml] {
+            let s = policy.to_string(format).unwrap();
+            assert_eq!(Policy::from_str(&s, format).unwrap(), policy);
+        }
+    }
+
+    #[test]
+    fn parse_toml() {
+        let policy = Policy::from_str(
+            r#"
+[[restrictions]]
+api = "Config"
+directory = "foo"
+regions_allowed = ["xe"]
+providers_denied = ["gcp"]
+"#,
+            PolicyFormat::Toml,
+        )
+        .unwrap();
+        let resolved = policy.resolve().unwrap();
+        assert_eq!(resolved[0].0, Api::Config);
+        assert_eq!(resolved[0].1, RestrictedDirectory::from_unencoded("foo"));
+        assert!(resolved[0].2.regions_allowed.contains(&Region::XE));
+        assert!(resolved[0].2.providers_denied.contains(&Provider::GCP));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_values() {
+        for field in [
+            r#"regions_allowed = ["XZ"]"#,
+            r#"regions_denied = ["XZ"]"#,
+            r#"providers_allowed = ["Linode"]"#,
+            r#"providers_denied = ["Linode"]"#,
+        ] {
+            let policy =
+                format!("[[restrictions]]\napi = \"config\"\ndirectory = \"foo\"\n{field}\n");
+            assert!(Policy::from_str(&policy, PolicyFormat::Toml).is_err(), "{field}");
+        }
+        assert!(Policy::from_str(
+            "restrictions:\n- api: config\n  directory: foo\n  regions_allowed: [XZ]\n",
+            PolicyFormat::Yaml
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn resolve_rejects_duplicates() {
+        let mut policy = Policy::from_model(&[restriction(Api::Config, "a", Region::XE)]);
+        policy.restrictions.push(policy.restrictions[0].clone());
+        assert!(policy.resolve().is_err());
+    }
+
+    #[test]
+    fn plan_changes() {
+        let policy = Policy::from_model(&[
+            restriction(Api::Config, "same", Region::XE),
+            restriction(Api::Config, "changed", Region::XE),
+            restriction(Api::Config, "new", Region::XE),
+        ]);
+        let current = vec![
+            restriction(Api::Config, "same", Region::XE),
+            restriction(Api::Config, "changed", Region::XN),
+            restriction(Api::Locks, "extra", Region::XN),
+        ];
+
+        let plan = Plan::new(&policy, current.clone(), false).unwrap();
+        let actions: Vec<_> = plan
+            .changes
+            .iter()
+            .map(|c| (c.action, c.directory.decode()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![(PlanAction::Update, b"changed".to_vec()), (PlanAction::Create, b"new".to_vec())]
+        );
+        assert_eq!(plan.unmanaged, 1);
+
+        let plan = Plan::new(&policy, current, true).unwrap();
+        assert_eq!(plan.changes.len(), 3);
+        assert_eq!(plan.changes[2].action, PlanAction::Delete);
+        assert_eq!(plan.unmanaged, 0);
+    }
+}
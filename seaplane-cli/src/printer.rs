@@ -106,6 +106,20 @@ pub enum Color {
     White,
 }
 
+/// Whether stdout is a terminal which accepts ANSI escapes, in which case output may be redrawn in
+/// place. This is `false` when color is disabled (i.e. `--color=never`), and without color support
+/// there's no way to tell, so it's always `false`.
+pub fn stdout_can_redraw() -> bool {
+    #[cfg(all(feature = "color", not(feature = "api_tests")))]
+    {
+        atty::is(atty::Stream::Stdout) && printer().supports_color()
+    }
+    #[cfg(not(all(feature = "color", not(feature = "api_tests"))))]
+    {
+        false
+    }
+}
+
 pub trait Output {
     fn print_json(&self, _ctx: &Ctx) -> Result<()> {
         cli_eprint!("--format=");
@@ -175,6 +189,8 @@ mod _printer {
 
         fn set_stream(&mut self, stream: StandardStream) { self.0 = stream; }
 
+        pub fn supports_color(&self) -> bool { self.0.supports_color() }
+
         pub fn set_color(&mut self, color: Color) {
             let _ = self
                 .0
@@ -0,0 +1,141 @@
+use httpmock::prelude::*;
+use seaplane_cli::printer::printer;
+use serde_json::json;
+
+use super::{then, when, when_json, MOCK_SERVER};
+
+#[test]
+fn flags_list() {
+    let resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            // flags/beta = {"enabled":true,"kind":"boolean"}
+            {"key": "ZmxhZ3MvYmV0YQ", "value": "eyJlbmFibGVkIjp0cnVlLCJraW5kIjoiYm9vbGVhbiJ9"},
+            // flags/checkout = {"kind":"percentage","enabled":true,"percentage":25,"regions":["XE"],"description":"New checkout"}
+            {"key": "ZmxhZ3MvY2hlY2tvdXQ", "value": "eyJraW5kIjoicGVyY2VudGFnZSIsImVuYWJsZWQiOnRydWUsInBlcmNlbnRhZ2UiOjI1LCJyZWdpb25zIjpbIlhFIl0sImRlc2NyaXB0aW9uIjoiTmV3IGNoZWNrb3V0In0"}
+        ]
+    });
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:ZmxhZ3M/");
+        then(t, &resp);
+    });
+
+    let res = run!("flags list");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "NAME      KIND        ENABLED  ROLLOUT   REGIONS  DESCRIPTION
+beta      boolean     true     everyone  all      -
+checkout  percentage  true     25%       XE       New checkout"
+    );
+    printer().clear();
+
+    let res = run!("flags list --format json");
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        r#"{"beta":{"enabled":true,"kind":"boolean"},"checkout":{"enabled":true,"kind":"percentage","percentage":25,"regions":["XE"],"description":"New checkout"}}"#
+    );
+    printer().clear();
+
+    mock.delete();
+}
+
+#[test]
+fn flags_create() {
+    let not_found_json = json!({"status": 404_i32, "title": "Not Found", "detail": "not found"});
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:ZmxhZ3MvbmV3");
+        t.status(404).json_body_obj(&not_found_json);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        // {"enabled":true,"kind":"percentage","percentage":25,"regions":["XE"]}
+        when(w, PUT, "/v1/config/base64:ZmxhZ3MvbmV3")
+            .header("content-type", "application/octet-stream")
+            .body("eyJlbmFibGVkIjp0cnVlLCJraW5kIjoicGVyY2VudGFnZSIsInBlcmNlbnRhZ2UiOjI1LCJyZWdpb25zIjpbIlhFIl19");
+        then(t, &resp_json);
+    });
+
+    let res = run!("flags create new --percentage 25 --region eu");
+    assert!(res.is_ok());
+    get_mock.assert_hits(1);
+    put_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "Created flag 'new'");
+    printer().clear();
+
+    get_mock.delete();
+    put_mock.delete();
+}
+
+#[test]
+fn flags_create_existing() {
+    let resp =
+        json!({"key": "ZmxhZ3MvYmV0YQ", "value": "eyJlbmFibGVkIjp0cnVlLCJraW5kIjoiYm9vbGVhbiJ9"});
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:ZmxhZ3MvZXhpc3Rpbmc");
+        then(t, &resp);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        // {"enabled":true,"kind":"boolean"}
+        when(w, PUT, "/v1/config/base64:ZmxhZ3MvZXhpc3Rpbmc")
+            .header("content-type", "application/octet-stream")
+            .body("eyJlbmFibGVkIjp0cnVlLCJraW5kIjoiYm9vbGVhbiJ9");
+        then(t, &resp_json);
+    });
+
+    let res = run!("flags create existing");
+    assert!(res.is_err());
+    get_mock.assert_hits(1);
+    put_mock.assert_hits(0);
+
+    let res = run!("flags create existing --force");
+    assert!(res.is_ok());
+    get_mock.assert_hits(1);
+    put_mock.assert_hits(1);
+    printer().clear();
+
+    get_mock.delete();
+    put_mock.delete();
+}
+
+#[test]
+fn flags_toggle() {
+    // {"kind":"boolean","enabled":true}
+    let resp =
+        json!({"key": "ZmxhZ3MvdG9nZ2xl", "value": "eyJlbmFibGVkIjp0cnVlLCJraW5kIjoiYm9vbGVhbiJ9"});
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:ZmxhZ3MvdG9nZ2xl");
+        then(t, &resp);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        // {"enabled":false,"kind":"boolean"}
+        when(w, PUT, "/v1/config/base64:ZmxhZ3MvdG9nZ2xl")
+            .header("content-type", "application/octet-stream")
+            .body("eyJlbmFibGVkIjpmYWxzZSwia2luZCI6ImJvb2xlYW4ifQ");
+        then(t, &resp_json);
+    });
+
+    let res = run!("flags toggle toggle");
+    assert!(res.is_ok());
+    get_mock.assert_hits(1);
+    put_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "Flag 'toggle' is now off");
+    printer().clear();
+
+    let res = run!("flags toggle toggle --off");
+    assert!(res.is_ok());
+    put_mock.assert_hits(2);
+    printer().clear();
+
+    get_mock.delete();
+    put_mock.delete();
+}
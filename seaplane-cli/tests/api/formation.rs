@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use httpmock::prelude::*;
 use seaplane::api::{
     compute::v1::FormationConfiguration as FormationConfigurationModel,
@@ -5,14 +7,17 @@ use seaplane::api::{
 };
 use seaplane_cli::{
     context::Ctx,
-    ops::formation::{Formation, FormationConfiguration},
+    ops::{
+        formation::{DeployedConfiguration, Deployment, Formation, FormationConfiguration},
+        Id,
+    },
     printer::printer,
     test_main_exec_with_ctx,
 };
 use serde_json::json;
 use wildmatch::WildMatch;
 
-use super::{then, when, when_json, MOCK_SERVER};
+use super::{ctx_from_url, then, when, when_json, MOCK_SERVER};
 
 // The ARGV must use the name `stubb` as these are just tests, no need to spend brainpower trying to
 // figure out how to make it perfectly generic.
@@ -21,6 +26,8 @@ use super::{then, when, when_json, MOCK_SERVER};
 //
 
 const DEFAULT_CFG_UUID: &str = "46c5d58c-7b8b-4e8d-9e98-26bb31b9ab8f";
+const CFG_A: &str = "aa8522e7-06cc-4e35-8966-484ae26e02a9";
+const CFG_B: &str = "bb8522e7-06cc-4e35-8966-484ae26e02a9";
 
 fn build_ctx_with_default_formation(local_only: bool) -> Ctx {
     let fcm: FormationConfigurationModel =
@@ -70,6 +77,48 @@ fn default_cfg_json() -> serde_json::Value {
     })
 }
 
+/// A local Formation Plan `name` without any configurations
+fn build_ctx(name: &str) -> Ctx {
+    let mut ctx = ctx_from_url(MOCK_SERVER.base_url());
+    ctx.db.formations.formations.push(Formation::new(name));
+    ctx
+}
+
+fn cfg_model(flights: serde_json::Value) -> FormationConfigurationModel {
+    serde_json::from_str(&json!({ "flights": flights }).to_string()).unwrap()
+}
+
+/// The remote Formation Instance `name` has the configurations CFG_A and CFG_B
+fn mock_cfg_list(name: &'static str) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when_json(w, GET, format!("/v1/formations/{name}/configurations"));
+        then.status(200).json_body(json!([CFG_A, CFG_B]));
+    })
+}
+
+fn mock_active_cfgs(name: &'static str, body: serde_json::Value) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when_json(w, GET, format!("/v1/formations/{name}/activeConfiguration"));
+        then.status(200).json_body(body);
+    })
+}
+
+fn mock_set_weights(name: &'static str, body: serde_json::Value) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when_json(w, PUT, format!("/v1/formations/{name}/activeConfiguration"))
+            .query_param("force", "false")
+            .json_body_obj(&body);
+        then.status(200);
+    })
+}
+
+fn mock_containers(name: &'static str, body: serde_json::Value) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when_json(w, GET, format!("/v1/formations/{name}/containers"));
+        then.status(200).json_body(body);
+    })
+}
+
 // This could be a fn and not a macro...but as a macro we don't have to worry about return values
 // and other things. And since this is just a test the macro is easier.
 //
@@ -754,6 +803,117 @@ fn formation_launch_grounded_fetch() {
     );
 }
 
+/// Mocks launching the default Formation as a new Formation Instance whose containers are
+/// `containers`
+fn mock_launch_wait(containers: serde_json::Value) -> Vec<httpmock::Mock<'static>> {
+    vec![
+        MOCK_SERVER.mock(|w, then| {
+            when_json(w, POST, "/v1/formations/stubb/configurations");
+            then.status(404);
+        }),
+        MOCK_SERVER.mock(|w, then| {
+            when_json(w, POST, "/v1/formations/stubb").query_param("active", "true");
+            then.status(201).json_body(json!([DEFAULT_CFG_UUID]));
+        }),
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb");
+            then(t, &json!({"url":"https://stubb--bar.on.cplane.cloud/"}));
+        }),
+        MOCK_SERVER.mock(|w, then| {
+            when_json(w, GET, "/v1/formations/stubb/containers");
+            then.status(200).json_body(containers);
+        }),
+    ]
+}
+
+fn container_json(flight: &str, status: &str, exit_status: Option<i32>) -> serde_json::Value {
+    json!({
+        "container_id": "aa8522e7-06cc-4e35-8966-484ae26e02a9",
+        "status": status,
+        "flight_name": flight,
+        "configuration_id": DEFAULT_CFG_UUID,
+        "exit_status": exit_status,
+    })
+}
+
+#[test]
+fn formation_launch_wait() {
+    let mocks = mock_launch_wait(json!([
+        container_json("flask", "running", None),
+        container_json("pequod", "running", None),
+    ]));
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(printer()
+        .as_string()
+        .contains("Remote Formation Instance 'stubb' is running"));
+    assert_eq!(mocks[3].hits(), 1);
+
+    printer().clear();
+    for mut m in mocks {
+        m.delete();
+    }
+}
+
+#[test]
+fn formation_launch_wait_failed() {
+    let mocks = mock_launch_wait(json!([
+        container_json("flask", "running", None),
+        container_json("pequod", "stopped", Some(1)),
+    ]));
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_err());
+    assert!(printer()
+        .as_string()
+        .contains("of Flight 'pequod' exited with status 1"));
+    printer().clear();
+
+    // The summary follows --format
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait --format json"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_err());
+    let out = printer().as_string().to_string();
+    assert!(out.contains(r#""exit_status":1"#), "{out}");
+    assert!(!out.contains("CONFIGURATION"), "{out}");
+
+    printer().clear();
+    for mut m in mocks {
+        m.delete();
+    }
+}
+
+#[test]
+fn formation_launch_wait_timeout() {
+    let mocks = mock_launch_wait(json!([container_json("flask", "started", None)]));
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait --timeout 0s"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_err());
+    assert_eq!(mocks[3].hits(), 1);
+
+    printer().clear();
+    for mut m in mocks {
+        m.delete();
+    }
+
+    let parse = |argv: &str| seaplane_cli::test_cli(argv.split(' '));
+    assert!(parse("seaplane formation launch stubb --wait --grounded").is_err());
+    assert!(parse("seaplane formation launch stubb --timeout 5m").is_err());
+    assert!(parse("seaplane formation launch stubb --format json").is_err());
+}
+
 macro_rules! test_fn_fetch {
     ($test_fn:ident, $argv:expr) => {
         #[test]
@@ -944,3 +1104,927 @@ fn formation_status_all_no_fetch() {
 fn formation_status_one_no_fetch() {
     test_fn_status!("formation status stubb --no-fetch", ctx_with_remote_id());
 }
+
+#[test]
+fn formation_status_watch_args() {
+    let parse = |argv: &str| seaplane_cli::test_cli(argv.split(' '));
+    assert!(parse("seaplane formation status stubb --watch --interval 10s").is_ok());
+    assert!(parse("seaplane formation status stubb --interval 10s").is_err());
+    assert!(parse("seaplane formation status stubb --watch --interval 10x").is_err());
+    assert!(parse("seaplane formation status stubb --watch --interval 0s").is_err());
+    assert!(parse("seaplane formation status stubb --watch --interval 0").is_err());
+}
+
+/// Two active configurations, where CFG_B has no explicit traffic weight
+fn lb_active_json() -> serde_json::Value {
+    json!([
+        {"configuration_id": CFG_A, "traffic_weight": 1.0},
+        {"configuration_id": CFG_B}
+    ])
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_load_balance_show() {
+    let mut get = mock_active_cfgs("lb-show", lb_active_json());
+
+    let res = run!("formation lb lb-show");
+    assert!(res.is_ok(), "{res:?}");
+    get.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "CONFIGURATION                         WEIGHT  TRAFFIC
+{CFG_A}  1       50.0%
+{CFG_B}  1       50.0%
+"
+        )
+    );
+    printer().clear();
+
+    let res = run!("formation lb lb-show --format json");
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            r#"[{{"configuration_id":"{CFG_A}","traffic_weight":1.0}},{{"configuration_id":"{CFG_B}","traffic_weight":1.0}}]"#
+        )
+    );
+
+    printer().clear();
+    get.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_load_balance_dry_run() {
+    let mut get = mock_active_cfgs("lb-dry", lb_active_json());
+    let mut put = MOCK_SERVER.mock(|w, then| {
+        when_json(w, PUT, "/v1/formations/lb-dry/activeConfiguration");
+        then.status(200);
+    });
+
+    let res = run!("formation lb lb-dry --set bb=3 --dry-run");
+    assert!(res.is_ok(), "{res:?}");
+    get.assert();
+    put.assert_hits(0);
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "Current traffic split of Formation Instance 'lb-dry':
+CONFIGURATION                         WEIGHT  TRAFFIC
+{CFG_A}  1       50.0%
+{CFG_B}  1       50.0%
+
+New traffic split:
+CONFIGURATION                         WEIGHT  TRAFFIC
+{CFG_A}  25      25.0%
+{CFG_B}  75      75.0%
+
+(hint: run without '--dry-run' to apply it)
+"
+        )
+    );
+
+    printer().clear();
+    get.delete();
+    put.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_load_balance_set() {
+    let mut get = mock_active_cfgs("lb-set", lb_active_json());
+    let mut put = mock_set_weights(
+        "lb-set",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 80.0_f32},
+            {"configuration_id": CFG_B, "traffic_weight": 20.0_f32}
+        ]),
+    );
+
+    let res = run!("formation lb lb-set --set aa=4,bb=1");
+    assert!(res.is_ok(), "{res:?}");
+    get.assert();
+    put.assert();
+    printer().clear();
+
+    // Weights that would send no traffic anywhere, or that don't match a configuration
+    assert!(run!("formation lb lb-set --set aa=0,bb=0").is_err());
+    assert!(run!("formation lb lb-set --set cc=1").is_err());
+    put.assert_hits(1);
+
+    printer().clear();
+    get.delete();
+    put.delete();
+}
+
+fn rollout_active_json() -> serde_json::Value {
+    json!([{"configuration_id": CFG_A, "traffic_weight": 1.0}])
+}
+
+fn rollout_containers_json(status: &str) -> serde_json::Value {
+    json!([{
+        "container_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+        "status": status,
+        "flight_name": "web",
+        "configuration_id": CFG_B,
+    }])
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_rollout() {
+    let list = mock_cfg_list("rollout-ok");
+    let active = mock_active_cfgs("rollout-ok", rollout_active_json());
+    let half = mock_set_weights(
+        "rollout-ok",
+        json!([
+            {"configuration_id": CFG_B, "traffic_weight": 50.0_f32},
+            {"configuration_id": CFG_A, "traffic_weight": 50.0_f32}
+        ]),
+    );
+    let full = mock_set_weights(
+        "rollout-ok",
+        json!([{"configuration_id": CFG_B, "traffic_weight": 100.0_f32}]),
+    );
+    let containers = mock_containers("rollout-ok", rollout_containers_json("running"));
+
+    let res = run!("formation rollout rollout-ok --to bb --steps 50,100 --interval 0s");
+    assert!(res.is_ok(), "{res:?}");
+    list.assert();
+    active.assert();
+    half.assert();
+    containers.assert();
+    full.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "Sent 50% of traffic to configuration {CFG_B}
+Sent 100% of traffic to configuration {CFG_B}
+Successfully rolled out configuration {CFG_B} to remote Formation Instance 'rollout-ok'
+"
+        )
+    );
+
+    printer().clear();
+    for mut m in [list, active, half, full, containers] {
+        m.delete();
+    }
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_rollout_rolls_back() {
+    let list = mock_cfg_list("rollout-bad");
+    let active = mock_active_cfgs("rollout-bad", rollout_active_json());
+    let half = mock_set_weights(
+        "rollout-bad",
+        json!([
+            {"configuration_id": CFG_B, "traffic_weight": 50.0_f32},
+            {"configuration_id": CFG_A, "traffic_weight": 50.0_f32}
+        ]),
+    );
+    let rollback = mock_set_weights(
+        "rollout-bad",
+        json!([{"configuration_id": CFG_A, "traffic_weight": 1.0_f32}]),
+    );
+    let containers = mock_containers("rollout-bad", rollout_containers_json("stopped"));
+
+    let res = run!("formation rollout rollout-bad --to bb --steps 50,100 --interval 0s");
+    assert!(res.is_err());
+    half.assert();
+    containers.assert();
+    rollback.assert();
+
+    printer().clear();
+    for mut m in [list, active, half, rollback, containers] {
+        m.delete();
+    }
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_rollout_invalid_steps() {
+    // Steps must increase
+    assert!(run!("formation rollout stubb --to bb --steps 50,25").is_err());
+    // Rejected by the argument parser
+    let parse = |argv: &str| seaplane_cli::test_cli(argv.split(' '));
+    assert!(parse("seaplane formation rollout stubb --to bb --steps 0,100").is_err());
+    assert!(parse("seaplane formation rollout stubb --to bb --interval 5x").is_err());
+    printer().clear();
+}
+
+fn flask_cfg_model() -> FormationConfigurationModel {
+    cfg_model(json!([{"name":"flask","image":"registry.cplane.cloud/stubb/alpine:latest"}]))
+}
+
+/// A local Formation Plan `name` with configuration 1111... uploaded as CFG_A and active, and
+/// configuration 2222... which only exists locally
+fn build_ctx_with_cfgs(name: &str) -> Ctx {
+    let mut uploaded = FormationConfiguration::with_uuid(CFG_A.parse().unwrap(), flask_cfg_model());
+    uploaded.id = Id { inner: [0x11; 32] };
+    let mut local = FormationConfiguration::new(flask_cfg_model());
+    local.id = Id { inner: [0x22; 32] };
+
+    let mut ctx = build_ctx(name);
+    let f = &mut ctx.db.formations.formations[0];
+    f.local.insert(uploaded.id);
+    f.local.insert(local.id);
+    f.in_air.insert(uploaded.id);
+    ctx.db.formations.configurations.push(uploaded);
+    ctx.db.formations.configurations.push(local);
+    ctx
+}
+
+fn cfg_active_json() -> serde_json::Value {
+    json!([{"configuration_id": CFG_A, "traffic_weight": 10.0}])
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_configuration_list() {
+    let mut list = mock_cfg_list("cfg-list");
+    let mut active = mock_active_cfgs("cfg-list", cfg_active_json());
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg list cfg-list"),
+        build_ctx_with_cfgs("cfg-list"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    list.assert();
+    active.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "LOCAL ID  UUID                                  STATUS    TRAFFIC WEIGHT
+11111111  {CFG_A}  active    10
+-         {CFG_B}  inactive  -
+22222222  -                                     local     -
+"
+        )
+    );
+    printer().clear();
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg list cfg-list --format json"),
+        build_ctx_with_cfgs("cfg-list"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        json!([
+            {"local_id": "11111111", "uuid": CFG_A, "status": "active", "traffic_weight": 10.0},
+            {"local_id": null, "uuid": CFG_B, "status": "inactive", "traffic_weight": null},
+            {"local_id": "22222222", "uuid": null, "status": "local", "traffic_weight": null},
+        ])
+        .to_string()
+    );
+
+    printer().clear();
+    list.delete();
+    active.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_configuration_get() {
+    let mut list = mock_cfg_list("cfg-get");
+    let mut active = mock_active_cfgs("cfg-get", cfg_active_json());
+    let mut get = MOCK_SERVER.mock(|w, then| {
+        when_json(w, GET, format!("/v1/formations/cfg-get/configurations/{CFG_A}"));
+        then.status(200).json_body_obj(&flask_cfg_model());
+    });
+
+    // Matched by the local ID
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg get cfg-get 1111"),
+        build_ctx_with_cfgs("cfg-get"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    get.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "Configuration {CFG_A} (local ID 11111111) of Formation Instance 'cfg-get':
+FLIGHT  IMAGE                                      MIN  MAX
+flask   registry.cplane.cloud/stubb/alpine:latest  0    INF
+"
+        )
+    );
+
+    printer().clear();
+    list.delete();
+    active.delete();
+    get.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_configuration_add() {
+    let mut add = MOCK_SERVER.mock(|w, then| {
+        when_json(w, POST, "/v1/formations/cfg-add/configurations")
+            .query_param("active", "true")
+            .json_body_obj(&flask_cfg_model());
+        then.status(200).json_body(json!(CFG_B));
+    });
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg add cfg-add 2222 --active"),
+        build_ctx_with_cfgs("cfg-add"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    add.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully added configuration {CFG_B} (local ID 22222222) to remote Formation Instance 'cfg-add' as active"
+        )
+    );
+    printer().clear();
+
+    // Already uploaded configurations can't be added again
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg add cfg-add 1111"),
+        build_ctx_with_cfgs("cfg-add"),
+    );
+    assert!(res.is_err());
+    add.assert_hits(1);
+
+    printer().clear();
+    add.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_configuration_remove() {
+    let mut list = mock_cfg_list("cfg-rm");
+    let mut active = mock_active_cfgs("cfg-rm", cfg_active_json());
+    let mut remove = MOCK_SERVER.mock(|w, then| {
+        when(w, DELETE, format!("/v1/formations/cfg-rm/configurations/{CFG_B}"))
+            .query_param("force", "true");
+        then.status(200).json_body(json!(CFG_B));
+    });
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg remove cfg-rm bb --force"),
+        build_ctx_with_cfgs("cfg-rm"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    remove.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully removed configuration {CFG_B} from remote Formation Instance 'cfg-rm'"
+        )
+    );
+
+    printer().clear();
+    list.delete();
+    active.delete();
+    remove.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_configuration_activate_deactivate() {
+    let mut list = mock_cfg_list("cfg-act");
+    let mut active = mock_active_cfgs("cfg-act", cfg_active_json());
+    let mut set = mock_set_weights(
+        "cfg-act",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 10.0_f32},
+            {"configuration_id": CFG_B, "traffic_weight": 10.0_f32}
+        ]),
+    );
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg activate cfg-act bb"),
+        build_ctx_with_cfgs("cfg-act"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    set.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully activated configuration {CFG_B} of remote Formation Instance 'cfg-act'"
+        )
+    );
+    printer().clear();
+
+    // The only active configuration can't be deactivated
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg deactivate cfg-act aa"),
+        build_ctx_with_cfgs("cfg-act"),
+    );
+    assert!(res.is_err());
+    set.assert_hits(1);
+    printer().clear();
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation cfg deactivate cfg-act bb"),
+        build_ctx_with_cfgs("cfg-act"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(printer().as_string().trim(), format!("Configuration {CFG_B} is already inactive"));
+
+    printer().clear();
+    list.delete();
+    active.delete();
+    set.delete();
+}
+
+fn stats_containers_json() -> serde_json::Value {
+    json!([
+        {
+            "container_id": "aa8522e7-06cc-4e35-8966-484ae26e02a9",
+            "status": "running",
+            "flight_name": "web",
+            "configuration_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+            "cpu_usage": 10,
+            "ram_usage": 1024,
+            "host_region": "XN",
+            "host_provider": "AWS",
+            "host_country": "us"
+        },
+        {
+            "container_id": "bb8522e7-06cc-4e35-8966-484ae26e02a9",
+            "status": "stopped",
+            "flight_name": "web",
+            "configuration_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+            "exit_status": 1,
+            "start_time": "2022-01-01T00:00:00Z",
+            "stop_time": "2022-01-01T00:01:00Z",
+            "cpu_usage": 30,
+            "host_region": "XE",
+            "host_country": "de"
+        }
+    ])
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_container_stats() {
+    let mut mock = mock_containers("stats", stats_containers_json());
+
+    let res = run!("formation container-statistics stats");
+    assert!(res.is_ok());
+    let out = printer().as_string().to_string();
+    assert!(out.starts_with("CONTAINER"));
+    assert!(out.contains("aa8522e7-06cc-4e35-8966-484ae26e02a9"));
+    assert!(out.contains("cc8522e7"));
+    printer().clear();
+
+    let res = run!("formation stats stats --format json");
+    assert!(res.is_ok());
+    let out: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    assert_eq!(out[0]["uptime_secs"], json!(null));
+    assert_eq!(out[1]["uptime_secs"], json!(60));
+    assert_eq!(out[1]["exit_status"], json!(1));
+    printer().clear();
+
+    mock.assert_hits(2);
+    mock.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_container_stats_group_by() {
+    let mut mock = mock_containers("stats-grouped", stats_containers_json());
+
+    let res = run!("formation stats stats-grouped --group-by flight --format json");
+    assert!(res.is_ok());
+    let out: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    assert_eq!(out.as_array().unwrap().len(), 1);
+    assert_eq!(out[0]["group"], json!("web"));
+    assert_eq!(out[0]["containers"], json!(2));
+    assert_eq!(out[0]["stopped"], json!(1));
+    assert_eq!(
+        out[0]["metrics"]["cpu_secs"],
+        json!({"count": 2, "sum": 40, "p50": 10, "p90": 30, "p99": 30})
+    );
+    assert_eq!(out[0]["metrics"]["ram_bytes"]["count"], json!(1));
+    printer().clear();
+
+    let res = run!("formation stats stats-grouped --group-by provider");
+    assert!(res.is_ok());
+    let out = printer().as_string().to_string();
+    assert!(out.starts_with("PROVIDER"));
+    assert!(out.contains("AWS"));
+    assert!(out.contains("unknown"));
+    printer().clear();
+
+    mock.assert_hits(2);
+    mock.delete();
+}
+
+fn mock_stop(name: &'static str) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when(w, DELETE, format!("/v1/formations/{name}/activeConfiguration"));
+        then.status(200).body("success");
+    })
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_land_drain() {
+    let mut active = mock_active_cfgs(
+        "drain",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 1.0},
+            {"configuration_id": CFG_B, "traffic_weight": 3.0}
+        ]),
+    );
+    let mut drained = mock_set_weights(
+        "drain",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 0.0_f32},
+            {"configuration_id": CFG_B, "traffic_weight": 100.0_f32}
+        ]),
+    );
+    let mut deactivated = mock_set_weights(
+        "drain",
+        json!([{"configuration_id": CFG_B, "traffic_weight": 100.0_f32}]),
+    );
+    let mut stop = mock_stop("drain");
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation land drain --drain --over 0s --configuration aa"),
+        build_ctx("drain"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    drained.assert();
+    deactivated.assert();
+    assert_eq!(stop.hits(), 0);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!("Successfully Landed configuration {CFG_A} of remote Formation Instance 'drain'")
+    );
+
+    printer().clear();
+    active.delete();
+    drained.delete();
+    deactivated.delete();
+    stop.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_land_drain_no_takeover() {
+    let mut active = mock_active_cfgs(
+        "drain-all",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 1.0},
+            {"configuration_id": CFG_B, "traffic_weight": 1.0}
+        ]),
+    );
+    let mut stop = mock_stop("drain-all");
+
+    // Landing every active configuration leaves nothing to take over the traffic
+    let res = test_main_exec_with_ctx(
+        &argv!("formation land drain-all --drain --over 0s --configuration aa,bb"),
+        build_ctx("drain-all"),
+    );
+    assert!(res.is_err());
+    assert_eq!(stop.hits(), 0);
+
+    let parse = |argv: &str| seaplane_cli::test_cli(argv.split(' '));
+    assert!(parse("seaplane formation land drain-all --over 1m").is_err());
+    assert!(parse("seaplane formation land drain-all --configuration aa").is_err());
+    assert!(parse("seaplane formation land drain-all --drain").is_ok());
+    assert!(parse("seaplane formation land drain-all --drain --all").is_ok());
+    assert!(parse("seaplane formation land drain-all --drain --all --configuration aa").is_err());
+
+    printer().clear();
+    active.delete();
+    stop.delete();
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_land_drain_all_but_busiest() {
+    let mut active = mock_active_cfgs(
+        "drain-rest",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 1.0},
+            {"configuration_id": CFG_B, "traffic_weight": 3.0}
+        ]),
+    );
+    let mut drained = mock_set_weights(
+        "drain-rest",
+        json!([
+            {"configuration_id": CFG_A, "traffic_weight": 0.0_f32},
+            {"configuration_id": CFG_B, "traffic_weight": 100.0_f32}
+        ]),
+    );
+    let mut deactivated = mock_set_weights(
+        "drain-rest",
+        json!([{"configuration_id": CFG_B, "traffic_weight": 100.0_f32}]),
+    );
+
+    // Without --configuration the busiest configuration takes over the traffic of all others
+    let res = test_main_exec_with_ctx(
+        &argv!("formation land drain-rest --drain --over 0s"),
+        build_ctx("drain-rest"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    drained.assert();
+    deactivated.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully Landed configuration {CFG_A} of remote Formation Instance 'drain-rest'"
+        )
+    );
+
+    printer().clear();
+    active.delete();
+    drained.delete();
+    deactivated.delete();
+}
+
+fn scale_cfg_model(minimum: u64, maximum: Option<u64>) -> FormationConfigurationModel {
+    cfg_model(json!([
+        {"name":"web","image":"registry.cplane.cloud/stubb/web:latest","minimum":minimum,"maximum":maximum},
+        {"name":"db","image":"registry.cplane.cloud/stubb/db:latest","minimum":1,"maximum":1}
+    ]))
+}
+
+/// The remote Formation Instance `name` with CFG_A active, where Flight 'web' has a minimum of 1
+/// and a maximum of 5
+fn mock_scale_old(name: &'static str) -> (httpmock::Mock<'static>, httpmock::Mock<'static>) {
+    let active =
+        mock_active_cfgs(name, json!([{"configuration_id": CFG_A, "traffic_weight": 2.0}]));
+    let get = MOCK_SERVER.mock(|w, then| {
+        when_json(w, GET, format!("/v1/formations/{name}/configurations/{CFG_A}"));
+        then.status(200).json_body_obj(&scale_cfg_model(1, Some(5)));
+    });
+    (active, get)
+}
+
+fn mock_scale_add(
+    name: &'static str,
+    model: FormationConfigurationModel,
+) -> httpmock::Mock<'static> {
+    MOCK_SERVER.mock(|w, then| {
+        when_json(w, POST, format!("/v1/formations/{name}/configurations"))
+            .query_param("active", "false")
+            .json_body_obj(&model);
+        then.status(200).json_body(json!(CFG_B));
+    })
+}
+
+fn mock_scale_activate(name: &'static str) -> httpmock::Mock<'static> {
+    mock_set_weights(name, json!([{"configuration_id": CFG_B, "traffic_weight": 2.0_f32}]))
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_scale() {
+    let (active, get) = mock_scale_old("scale");
+    let add = mock_scale_add("scale", scale_cfg_model(3, Some(5)));
+    let activate = mock_scale_activate("scale");
+
+    let res = run!("formation scale scale web --min 3");
+    assert!(res.is_ok(), "{res:?}");
+    active.assert();
+    get.assert();
+    add.assert();
+    activate.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully scaled Flight 'web' of remote Formation Instance 'scale' with new configuration {CFG_B}"
+        )
+    );
+
+    printer().clear();
+    for mut m in [active, get, add, activate] {
+        m.delete();
+    }
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_scale_remove_old() {
+    let (active, get) = mock_scale_old("scale-rm");
+    let add = mock_scale_add("scale-rm", scale_cfg_model(2, None));
+    let activate = mock_scale_activate("scale-rm");
+    let containers = mock_containers(
+        "scale-rm",
+        json!([
+            {
+                "container_id": "cc8522e7-06cc-4e35-8966-484ae26e02a9",
+                "status": "running",
+                "flight_name": "web",
+                "configuration_id": CFG_B,
+            },
+            {
+                "container_id": "cd8522e7-06cc-4e35-8966-484ae26e02a9",
+                "status": "running",
+                "flight_name": "web",
+                "configuration_id": CFG_B,
+            },
+            {
+                "container_id": "ce8522e7-06cc-4e35-8966-484ae26e02a9",
+                "status": "running",
+                "flight_name": "db",
+                "configuration_id": CFG_B,
+            }
+        ]),
+    );
+    let remove = MOCK_SERVER.mock(|w, then| {
+        when(w, DELETE, format!("/v1/formations/scale-rm/configurations/{CFG_A}"))
+            .query_param("force", "false");
+        then.status(200).json_body(json!(CFG_A));
+    });
+
+    let res = run!("formation scale scale-rm web --min 2 --no-max --remove-old --timeout 0s");
+    assert!(res.is_ok(), "{res:?}");
+    add.assert();
+    activate.assert();
+    containers.assert();
+    remove.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "Successfully scaled Flight 'web' of remote Formation Instance 'scale-rm' with new configuration {CFG_B}
+Remote Formation Instance 'scale-rm' is running
+Removed old configuration {CFG_A}
+"
+        )
+    );
+
+    printer().clear();
+    for mut m in [active, get, add, activate, containers, remove] {
+        m.delete();
+    }
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_scale_invalid() {
+    let (mut active, mut get) = mock_scale_old("scale-bad");
+    let mut add = mock_scale_add("scale-bad", scale_cfg_model(6, Some(5)));
+
+    // The minimum can't be above the current maximum
+    let res = run!("formation scale scale-bad web --min 6");
+    assert!(res.is_err());
+
+    // The Flight must be in an active configuration
+    let res = run!("formation scale scale-bad api --min 1");
+    assert!(res.is_err());
+    assert_eq!(add.hits(), 0);
+
+    let parse = |argv: &str| seaplane_cli::test_cli(argv.split(' '));
+    assert!(parse("seaplane formation scale scale-bad web").is_err());
+    assert!(parse("seaplane formation scale scale-bad web --min 1 --max 2 --no-max").is_err());
+    assert!(parse("seaplane formation scale scale-bad web --min 1 --timeout 1m").is_err());
+
+    printer().clear();
+    active.delete();
+    get.delete();
+    add.delete();
+}
+
+fn deployment(command: &str, cfgs: &[&str], age_secs: u64) -> Deployment {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    Deployment {
+        timestamp: now - age_secs,
+        command: command.into(),
+        configurations: cfgs
+            .iter()
+            .map(|uuid| DeployedConfiguration {
+                uuid: uuid.parse().unwrap(),
+                traffic_weight: None,
+                images: vec![format!("registry.cplane.cloud/stubb/{}:latest", &uuid[..2])],
+            })
+            .collect(),
+    }
+}
+
+/// The Formation Instance `name` was launched with CFG_A, rolled out to CFG_B and then landed
+fn build_ctx_with_deployments(name: &str) -> Ctx {
+    let mut ctx = build_ctx(name);
+    ctx.db.formations.deployments.insert(
+        name.into(),
+        vec![
+            deployment("launch", &[CFG_A], 3 * 60 * 60 + 1000),
+            deployment(&format!("rollout --to {CFG_B}"), &[CFG_A, CFG_B], 2 * 60 * 60 + 1000),
+            deployment("land", &[], 150),
+        ],
+    );
+    ctx
+}
+
+fn mock_rollback(name: &'static str, cfgs: &[&str]) -> httpmock::Mock<'static> {
+    let body: Vec<_> = cfgs
+        .iter()
+        .map(|uuid| json!({"configuration_id": uuid, "traffic_weight": 1.0_f32}))
+        .collect();
+    mock_set_weights(name, json!(body))
+}
+
+#[test]
+fn formation_history() {
+    let res = test_main_exec_with_ctx(
+        &argv!("formation history hist"),
+        build_ctx_with_deployments("hist"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "#   AGE     COMMAND                                            CONFIGURATION                         WEIGHT  IMAGES
+1   3h ago  launch                                             {CFG_A}  -       registry.cplane.cloud/stubb/aa:latest
+2   2h ago  rollout --to {CFG_B}  {CFG_A}  -       registry.cplane.cloud/stubb/aa:latest
+                                                               {CFG_B}  -       registry.cplane.cloud/stubb/bb:latest
+3   2m ago  land                                               -                                     -       -
+"
+        )
+    );
+    printer().clear();
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation history other"),
+        build_ctx_with_deployments("hist"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        "No deployments of remote Formation Instance 'other' were recorded"
+    );
+    printer().clear();
+}
+
+#[test]
+fn formation_rollback() {
+    let mut activate = mock_rollback("rollback", &[CFG_A]);
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation rollback rollback --to 1"),
+        build_ctx_with_deployments("rollback"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    activate.assert();
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "Successfully rolled back remote Formation Instance 'rollback' to deployment 1 with Configuration UUIDs:
+{CFG_A}
+"
+        )
+    );
+
+    printer().clear();
+    activate.delete();
+}
+
+#[test]
+fn formation_rollback_previous() {
+    let mut activate = mock_rollback("rollback-prev", &[CFG_A, CFG_B]);
+
+    // Without --to the deployment before the latest one is re-activated
+    let res = test_main_exec_with_ctx(
+        &argv!("formation rollback rollback-prev"),
+        build_ctx_with_deployments("rollback-prev"),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    activate.assert();
+
+    printer().clear();
+    activate.delete();
+}
+
+#[test]
+fn formation_rollback_invalid() {
+    let mut activate = mock_rollback("rollback-bad", &[]);
+
+    // There is no deployment 4
+    let res = test_main_exec_with_ctx(
+        &argv!("formation rollback rollback-bad --to 4"),
+        build_ctx_with_deployments("rollback-bad"),
+    );
+    assert!(res.is_err());
+
+    // Nothing was active after landing
+    let res = test_main_exec_with_ctx(
+        &argv!("formation rollback rollback-bad --to 3"),
+        build_ctx_with_deployments("rollback-bad"),
+    );
+    assert!(res.is_err());
+
+    // Nothing was recorded to roll back to
+    let res = test_main_exec_with_ctx(
+        &argv!("formation rollback other"),
+        build_ctx_with_deployments("rollback-bad"),
+    );
+    assert!(res.is_err());
+    assert_eq!(activate.hits(), 0);
+
+    printer().clear();
+    activate.delete();
+}
@@ -0,0 +1,332 @@
+use httpmock::prelude::*;
+use seaplane::api::compute::v1::FormationConfiguration as FormationConfigurationModel;
+use seaplane_cli::{
+    context::Ctx,
+    ops::formation::{ComputeApiVersion, Formation, FormationConfiguration},
+    printer::printer,
+    test_main_exec_with_ctx,
+};
+use serde_json::json;
+use wildmatch::WildMatch;
+
+use super::{then, when, when_json, MOCK_SERVER};
+
+fn build_ctx_with_v2_formation() -> Ctx {
+    let cfg_json = json!({
+        "flights":[{
+            "name":"flask",
+            "image":"registry.cplane.cloud/stubb/alpine:latest",
+        },
+        {
+            "name":"pequod",
+            "image":"registry.cplane.cloud/stubb/alpine:latest",
+        }]
+    });
+    let fcm: FormationConfigurationModel = serde_json::from_str(&cfg_json.to_string()).unwrap();
+    let fc = FormationConfiguration::new(fcm);
+    let mut f = Formation::new("stubb");
+    f.api_version = ComputeApiVersion::V2;
+    f.gateway_flight = Some("flask".into());
+    f.local.insert(fc.id);
+    f.in_air.insert(fc.id);
+
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.db.formations.formations.push(f);
+    ctx.db.formations.configurations.push(fc);
+    ctx
+}
+
+#[test]
+fn formation_plan_launch_v2() {
+    let mut mock = MOCK_SERVER.mock(|w, then| {
+        when_json(w, POST, "/v2beta/formations/stubb").json_body(json!({
+            "name":"stubb",
+            "flights":[
+                {"name":"flask","image":"registry.cplane.cloud/stubb/alpine:latest"},
+                {"name":"pequod","image":"registry.cplane.cloud/stubb/alpine:latest"}
+            ],
+            "gateway-flight":"flask"
+        }));
+        then.status(201);
+    });
+
+    let res = run!(
+        "formation plan \
+            --name stubb \
+            --api-version v2 \
+            --include-flight-plan name=pequod,image=stubb/alpine:latest \
+            --include-flight-plan name=flask,image=stubb/alpine:latest \
+            --gateway-flight flask \
+            --launch"
+    );
+    assert!(res.is_ok(), "{res:?}");
+    mock.assert();
+
+    let correct_out = "Successfully created Flight Plan '*' with ID '????????'
+Successfully created Flight Plan '*' with ID '????????'
+Successfully created local Formation Plan 'stubb' with ID '????????'
+Successfully Launched remote Formation Instance 'stubb' with gateway Flight 'flask'
+(hint: it may take up to a minute for the Formation to become fully online)
+(hint: check the status of this Formation Instance with 'seaplane formation status stubb')";
+    let actual_out: String = printer().as_string().trim().to_string();
+    assert!(
+        WildMatch::new(correct_out).matches(&actual_out),
+        "\ncorrect:\n{correct_out}\n\nactual:\n{actual_out}"
+    );
+
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn formation_plan_v2_unsupported_args() {
+    let res = run!(
+        "formation plan \
+            --name stubb \
+            --api-version v2 \
+            --include-flight-plan name=flask,image=stubb/alpine:latest \
+            --public-endpoint /=flask:80 \
+            --region xe"
+    );
+    assert!(res.is_err());
+    printer().clear();
+}
+
+#[test]
+fn formation_plan_v2_requires_gateway() {
+    let res = run!(
+        "formation plan \
+            --name stubb \
+            --api-version v2 \
+            --include-flight-plan name=pequod,image=stubb/alpine:latest \
+            --include-flight-plan name=flask,image=stubb/alpine:latest"
+    );
+    assert!(res.is_err());
+
+    let res = run!(
+        "formation plan \
+            --name stubb \
+            --api-version v2 \
+            --include-flight-plan name=flask,image=stubb/alpine:latest \
+            --gateway-flight pequod"
+    );
+    assert!(res.is_err());
+    printer().clear();
+}
+
+#[test]
+fn formation_status_v2() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v2beta/formations/stubb/status");
+        then(
+            t,
+            &json!({
+                "name":"stubb",
+                "flights":[
+                    {"name":"flask","health":"healthy"},
+                    {"name":"pequod","health":"unhealthy"}
+                ]
+            }),
+        );
+    });
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation status stubb --no-fetch"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    mock.assert();
+
+    let correct_out = "◉ Formation stubb: DEGRADED
+│
+│   FLIGHT    HEALTH
+├─◉ flask     healthy
+└─◉ pequod    unhealthy";
+    assert_eq!(printer().as_string().trim(), correct_out);
+    printer().clear();
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation status stubb --no-fetch --format json"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        r#"[{"name":"stubb","status":"Degraded","configurations":[],"flights":[{"name":"flask","health":"healthy"},{"name":"pequod","health":"unhealthy"}]}]"#
+    );
+
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn formation_launch_wait_v2() {
+    let mut create = MOCK_SERVER.mock(|w, then| {
+        when_json(w, POST, "/v2beta/formations/stubb");
+        then.status(201);
+    });
+    let status = |pequod: &str| {
+        let body = json!({
+            "name":"stubb",
+            "flights":[
+                {"name":"flask","health":"healthy"},
+                {"name":"pequod","health":pequod}
+            ]
+        });
+        MOCK_SERVER.mock(move |w, t| {
+            when(w, GET, "/v2beta/formations/stubb/status");
+            then(t, &body);
+        })
+    };
+
+    // Health values this version doesn't know about are still pending
+    let mut starting = status("starting");
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait --timeout 0s"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_err());
+    starting.assert();
+    assert!(printer().as_string().contains("└─◉ pequod    starting"));
+    printer().clear();
+    starting.delete();
+
+    let mut healthy = status("healthy");
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    healthy.assert();
+    assert!(printer()
+        .as_string()
+        .contains("Remote Formation Instance 'stubb' is running"));
+
+    printer().clear();
+    healthy.delete();
+    create.delete();
+}
+
+#[test]
+fn formation_launch_wait_v2_unhealthy() {
+    let mut create = MOCK_SERVER.mock(|w, then| {
+        when_json(w, POST, "/v2beta/formations/stubb");
+        then.status(201);
+    });
+    let mut unhealthy = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v2beta/formations/stubb/status");
+        then(
+            t,
+            &json!({
+                "name":"stubb",
+                "flights":[
+                    {"name":"flask","health":"healthy"},
+                    {"name":"pequod","health":"unhealthy"}
+                ]
+            }),
+        );
+    });
+
+    // An unhealthy Flight fails the launch without waiting for the timeout
+    let res = test_main_exec_with_ctx(
+        &argv!("formation launch stubb --wait --timeout 1h"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_err());
+    unhealthy.assert_hits(1);
+    assert!(printer().as_string().contains("└─◉ pequod    unhealthy"));
+
+    printer().clear();
+    unhealthy.delete();
+    create.delete();
+}
+
+#[test]
+fn formation_delete_v2() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v2beta/formations/stubb");
+        t.status(200).body("stubb");
+    });
+
+    let res = test_main_exec_with_ctx(
+        &argv!("formation delete stubb --no-local"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    mock.assert();
+    assert_eq!(
+        printer().as_string().trim(),
+        "Deleted remote Formation Instance 'stubb'\n\nSuccessfully removed 1 item"
+    );
+
+    printer().clear();
+    mock.delete();
+}
+
+fn build_ctx_with_v1_formation() -> Ctx {
+    let cfg_json = json!({
+        "flights":[{
+            "name":"flask",
+            "image":"registry.cplane.cloud/stubb/alpine:latest",
+            "minimum":2
+        },
+        {
+            "name":"pequod",
+            "image":"registry.cplane.cloud/stubb/alpine:latest",
+        }],
+        "public_endpoints":{"http:/":"flask:80"},
+        "regions_allowed":["XE"]
+    });
+    let fcm: FormationConfigurationModel = serde_json::from_str(&cfg_json.to_string()).unwrap();
+    let fc = FormationConfiguration::new(fcm);
+    let mut f = Formation::new("stubb");
+    f.local.insert(fc.id);
+
+    let mut ctx = Ctx::default();
+    ctx.db.formations.formations.push(f);
+    ctx.db.formations.configurations.push(fc);
+    ctx
+}
+
+#[test]
+fn formation_migrate_dry_run() {
+    let res = test_main_exec_with_ctx(
+        &argv!("formation migrate stubb --to v2 --dry-run"),
+        build_ctx_with_v1_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        "The following fields of Formation Plan 'stubb' can't be expressed in the v2 Compute API and would be dropped:
+    flights[flask].minimum = 2
+    public_endpoints = http:/=flask:80
+    regions_allowed = XE
+Formation Plan 'stubb' can be migrated to the v2 Compute API with gateway Flight 'flask'
+(hint: run without '--dry-run' to migrate it)"
+    );
+    printer().clear();
+}
+
+#[test]
+fn formation_migrate() {
+    let res = test_main_exec_with_ctx(
+        &argv!("formation migrate stubb --to v2"),
+        build_ctx_with_v1_formation(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(printer()
+        .as_string()
+        .trim()
+        .ends_with("Successfully migrated local Formation Plan 'stubb' to the v2 Compute API"));
+    printer().clear();
+
+    // Formations with remote instances must be deleted first
+    let res = test_main_exec_with_ctx(
+        &argv!("formation migrate stubb --to v1"),
+        build_ctx_with_v2_formation(),
+    );
+    assert!(res.is_err());
+    printer().clear();
+}
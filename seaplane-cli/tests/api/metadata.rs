@@ -4,6 +4,15 @@ use serde_json::json;
 
 use super::{then, when, when_json, MOCK_SERVER};
 
+/// Setting or deleting a key first checks whether it holds a chunked value
+fn mock_not_found(key: &str) -> httpmock::Mock<'static> {
+    let not_found_json = json!({"status": 404_i32, "title": "Not Found", "detail": "not found"});
+    MOCK_SERVER.mock(|w, t| {
+        when(w, GET, format!("/v1/config/base64:{key}"));
+        t.status(404).json_body_obj(&not_found_json);
+    })
+}
+
 fn multi_kv_resp() -> serde_json::Value {
     json!({
         "next_key": None::<String>,
@@ -54,6 +63,7 @@ fn metadata_put() {
             .body("YmFy");
         then(t, &resp_json);
     });
+    let mut not_found_mock = mock_not_found("Zm9v");
 
     let res = run!("metadata set foo bar");
     assert!(res.is_ok());
@@ -67,6 +77,7 @@ fn metadata_put() {
     assert_eq!(printer().as_string().trim(), "Success");
     printer().clear();
     mock.delete();
+    not_found_mock.delete();
 }
 
 #[test]
@@ -185,10 +196,421 @@ fn metadata_delete() {
         when_json(w, DELETE, "/v1/config/base64:Zm9v");
         then(t, &resp_json);
     });
+    let mut not_found_mock = mock_not_found("Zm9v");
 
     let res = run!("metadata delete foo");
     assert!(res.is_ok());
     mock.assert_hits(1);
     assert_eq!(printer().as_string().trim(), "Removed Zm9v\n\nSuccessfully removed 1 item");
     printer().clear();
+    not_found_mock.delete();
+}
+
+fn chunked_value_json(key: &str, chunks: u64) -> serde_json::Value {
+    let manifest = json!({
+        "format": "seaplane-chunked-value/v1",
+        "id": "abc",
+        "size": 5,
+        "chunks": chunks,
+        "sha256": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    });
+    json!({
+        "key": key,
+        "value": seaplane::api::metadata::v1::Value::from_unencoded(manifest.to_string()).to_string()
+    })
+}
+
+#[test]
+fn metadata_delete_chunked() {
+    let resp_json = json!({"status": 200u32, "title": "Ok"});
+    let resp = chunked_value_json("bGFyZ2U", 2);
+    let mut manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:bGFyZ2U");
+        then(t, &resp);
+    });
+    let mut delete_mocks: Vec<_> =
+        ["bGFyZ2U", "bGFyZ2UvLmNodW5rcy9hYmMtMDAwMDAwMDA", "bGFyZ2UvLmNodW5rcy9hYmMtMDAwMDAwMDE"]
+            .into_iter()
+            .map(|key| {
+                MOCK_SERVER.mock(|w, t| {
+                    when(w, DELETE, format!("/v1/config/base64:{key}"));
+                    then(t, &resp_json);
+                })
+            })
+            .collect();
+
+    // The manifest is removed along with all of its chunks
+    let res = run!("metadata delete large");
+    assert!(res.is_ok(), "{res:?}");
+    for m in &delete_mocks {
+        m.assert_hits(1);
+    }
+    assert_eq!(printer().as_string().trim(), "Removed bGFyZ2U\n\nSuccessfully removed 1 item");
+    printer().clear();
+
+    // Only the chunks which don't belong to the current value are removed
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:bGFyZ2UvLmNodW5rcw/");
+        then(
+            t,
+            &json!({"next_key": None::<String>, "kvs": [
+                {"key": "bGFyZ2UvLmNodW5rcy9hYmMtMDAwMDAwMDA", "value": "aGVs"},
+                {"key": "bGFyZ2UvLmNodW5rcy9vbGQtMDAwMDAwMDA", "value": "b2xk"}
+            ]}),
+        );
+    });
+    delete_mocks.push(MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:bGFyZ2UvLmNodW5rcy9vbGQtMDAwMDAwMDA");
+        then(t, &resp_json);
+    }));
+
+    let res = run!("metadata delete large --chunks");
+    assert!(res.is_ok(), "{res:?}");
+    list_mock.assert();
+    for m in &delete_mocks {
+        m.assert_hits(1);
+    }
+    assert_eq!(
+        printer().as_string().trim(),
+        "Removed 1 orphaned chunk of bGFyZ2U\n\nSuccessfully removed 1 orphaned chunk"
+    );
+    printer().clear();
+
+    manifest_mock.delete();
+    list_mock.delete();
+    for mut m in delete_mocks {
+        m.delete();
+    }
+}
+
+#[test]
+fn metadata_set_replaces_chunked() {
+    let resp_json = json!({"status": 200u32, "title": "Ok"});
+    let resp = chunked_value_json("aHVnZQ", 1);
+    let mut manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:aHVnZQ");
+        then(t, &resp);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:aHVnZQ").body("eA");
+        then(t, &resp_json);
+    });
+    let mut chunk_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:aHVnZS8uY2h1bmtzL2FiYy0wMDAwMDAwMA");
+        then(t, &resp_json);
+    });
+
+    // The chunks of the previous value are no longer needed
+    let res = run!("metadata set huge x");
+    assert!(res.is_ok(), "{res:?}");
+    manifest_mock.assert();
+    put_mock.assert();
+    chunk_mock.assert();
+    assert_eq!(printer().as_string().trim(), "Success");
+    printer().clear();
+
+    manifest_mock.delete();
+    put_mock.delete();
+    chunk_mock.delete();
+}
+
+#[test]
+fn metadata_diff() {
+    let staging_resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            {"key": "ZGI", "value": "aG9zdD1hCnBvcnQ9MQo"},
+            {"key": "b2xk", "value": "eA"}
+        ]
+    });
+    let production_resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            {"key": "ZGI", "value": "aG9zdD1iCnBvcnQ9MQo"},
+            {"key": "bmV3", "value": "eQ"}
+        ]
+    });
+
+    let mut staging_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:c3RhZ2luZw/");
+        then(t, &staging_resp);
+    });
+    let mut production_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:cHJvZHVjdGlvbg/");
+        then(t, &production_resp);
+    });
+
+    let res = run!("metadata diff staging production");
+    assert!(res.is_ok());
+    staging_mock.assert_hits(1);
+    production_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "+ new\n- old\n~ db\n    @@ -1,2 +1,2 @@\n    -host=a\n    +host=b\n     port=1"
+    );
+    printer().clear();
+
+    let res = run!("metadata diff --format json staging production");
+    assert!(res.is_ok());
+    staging_mock.assert_hits(2);
+    production_mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        json!({
+            "added": [{"key": "bmV3", "value": "eQ"}],
+            "removed": [{"key": "b2xk", "value": "eA"}],
+            "changed": [{
+                "key": "ZGI",
+                "old": "aG9zdD1hCnBvcnQ9MQo",
+                "new": "aG9zdD1iCnBvcnQ9MQo",
+                "diff": "@@ -1,2 +1,2 @@\n-host=a\n+host=b\n port=1\n"
+            }]
+        })
+        .to_string()
+    );
+    printer().clear();
+
+    let res = run!("metadata diff staging staging");
+    assert!(res.is_ok());
+    assert_eq!(printer().as_string().trim(), "No differences");
+    printer().clear();
+
+    // With --exit-code only differences fail
+    let res = run!("metadata diff --exit-code staging production");
+    assert!(res.is_err());
+    printer().clear();
+    let res = run!("metadata diff --exit-code staging staging");
+    assert!(res.is_ok());
+    printer().clear();
+
+    staging_mock.delete();
+    production_mock.delete();
+}
+
+#[cfg(not(windows))]
+#[test]
+fn metadata_edit() {
+    let resp = json!({"key":"Zm9v", "value": "YmFy"});
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:Zm9v");
+        then(t, &resp);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:Zm9v")
+            .header("content-type", "application/octet-stream")
+            .body("YmF6");
+        then(t, &resp_json);
+    });
+
+    // An "editor" that doesn't change anything should not write the value back
+    std::env::set_var("VISUAL", "true");
+    let res = run!("metadata edit foo");
+    assert!(res.is_ok());
+    get_mock.assert_hits(1);
+    put_mock.assert_hits(0);
+    assert_eq!(printer().as_string().trim(), "No changes made to foo");
+    printer().clear();
+
+    // The value is re-read before writing it back
+    std::env::set_var("VISUAL", "sed -i s/bar/baz/");
+    let res = run!("metadata edit foo");
+    assert!(res.is_ok());
+    get_mock.assert_hits(3);
+    put_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "Success");
+    printer().clear();
+
+    // A failing editor is an error
+    std::env::set_var("VISUAL", "false");
+    let res = run!("metadata edit foo");
+    assert!(res.is_err());
+    put_mock.assert_hits(1);
+    printer().clear();
+
+    std::env::remove_var("VISUAL");
+    get_mock.delete();
+    put_mock.delete();
+}
+
+#[test]
+fn metadata_get_output_file() {
+    let path = "metadata-get-output-file.out";
+    let resp = json!({"key":"Zm9v", "value": "YmFy"});
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:Zm9v");
+        then(t, &resp);
+    });
+
+    // Regular values are decoded and written as is
+    let res = run!("metadata get foo --output-file metadata-get-output-file.out");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(std::fs::read(path).unwrap(), b"bar");
+    assert_eq!(printer().as_string().trim(), format!("Wrote 3 bytes to {path}"));
+    printer().clear();
+    mock.delete();
+
+    // Chunked values are reassembled from their chunks
+    let manifest = json!({
+        "format": "seaplane-chunked-value/v1",
+        "id": "abc",
+        "size": 5,
+        "chunks": 2,
+        "sha256": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    });
+    let resp = json!({
+        "key": "Ymln",
+        "value": seaplane::api::metadata::v1::Value::from_unencoded(manifest.to_string()).to_string()
+    });
+    let mut manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Ymln");
+        then(t, &resp);
+    });
+    let resp = json!({"key": "YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAw", "value": "aGVs"});
+    let mut chunk0_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAw");
+        then(t, &resp);
+    });
+    let resp = json!({"key": "YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx", "value": "bG8"});
+    let mut chunk1_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx");
+        then(t, &resp);
+    });
+
+    let res = run!("metadata get big --output-file metadata-get-output-file.out");
+    assert!(res.is_ok());
+    // Once to find out the value is chunked, and again by the SDK when reassembling it
+    manifest_mock.assert_hits(2);
+    chunk0_mock.assert();
+    chunk1_mock.assert();
+    assert_eq!(std::fs::read(path).unwrap(), b"hello");
+    assert_eq!(printer().as_string().trim(), format!("Wrote 5 bytes to {path}"));
+    printer().clear();
+
+    std::fs::remove_file(path).unwrap();
+    manifest_mock.delete();
+    chunk0_mock.delete();
+    chunk1_mock.delete();
+}
+
+#[test]
+fn metadata_set_chunked() {
+    let path = "metadata-set-chunked.in";
+    // Just over a single chunk
+    std::fs::write(path, vec![b'a'; 256 * 1024 + 1]).unwrap();
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut not_found_mock = mock_not_found("cHV0");
+    let mut chunks_mock = MOCK_SERVER.mock(|w, t| {
+        w.method(PUT)
+            .path_contains("/v1/config/base64:cHV0Ly5jaHVua3Mv")
+            .header("authorization", "Bearer abc.123.def");
+        then(t, &resp_json);
+    });
+    let mut manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:cHV0").header("content-type", "application/octet-stream");
+        then(t, &resp_json);
+    });
+
+    let res = run!("metadata set put @metadata-set-chunked.in");
+    assert!(res.is_ok());
+    not_found_mock.assert();
+    chunks_mock.assert_hits(2);
+    manifest_mock.assert();
+    assert_eq!(printer().as_string().trim(), "Success");
+    printer().clear();
+
+    std::fs::remove_file(path).unwrap();
+    not_found_mock.delete();
+    chunks_mock.delete();
+    manifest_mock.delete();
+}
+
+#[test]
+fn metadata_encrypted() {
+    use seaplane::api::metadata::v1::{is_sealed, Keyring, Value};
+
+    let path = "metadata-encrypted.json";
+    let key_file = json!({
+        "active": "k1",
+        "keys": [{"id": "k1", "algorithm": "aes-256-gcm", "key": "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc="}]
+    });
+    std::fs::write(path, key_file.to_string()).unwrap();
+    let keyring: Keyring = serde_json::from_value(key_file).unwrap();
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    // Only the encrypted value is sent
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:ZW5j")
+            .header("content-type", "application/octet-stream")
+            .matches(|req| {
+                let body = String::from_utf8(req.body.clone().unwrap_or_default()).unwrap();
+                is_sealed(&Value::from_encoded(body).decode())
+            });
+        then(t, &resp_json);
+    });
+
+    let mut not_found_mock = mock_not_found("ZW5j");
+
+    let res = run!("metadata set enc s3cr3t --encrypt --key-file metadata-encrypted.json");
+    assert!(res.is_ok());
+    put_mock.assert();
+    assert_eq!(printer().as_string().trim(), "Success");
+    printer().clear();
+    not_found_mock.delete();
+
+    let sealed = Value::from_unencoded(keyring.seal(b"s3cr3t").unwrap()).to_string();
+    let resp = json!({"key": "ZW5j", "value": sealed});
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:ZW5j");
+        then(t, &resp);
+    });
+
+    // Values are transparently decrypted with a key file
+    let res = run!("metadata get enc --decode --key-file metadata-encrypted.json");
+    assert!(res.is_ok());
+    get_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "s3cr3t");
+    printer().clear();
+
+    // ...and left alone without one
+    let res = run!("metadata get enc");
+    assert!(res.is_ok());
+    get_mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), sealed);
+    printer().clear();
+
+    std::fs::remove_file(path).unwrap();
+    put_mock.delete();
+    get_mock.delete();
+}
+
+#[test]
+fn metadata_watch() {
+    let resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            {"key": "d2F0Y2hlZC9h", "value": "YmFy"},
+            {"key": "d2F0Y2hlZC9i", "value": "YnV6"}
+        ]
+    });
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:d2F0Y2hlZA/");
+        then(t, &resp);
+    });
+
+    let res = run!("metadata watch watched/ --initial --count 2");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "{\"event\":\"added\",\"key\":\"d2F0Y2hlZC9h\",\"value\":\"YmFy\"}
+{\"event\":\"added\",\"key\":\"d2F0Y2hlZC9i\",\"value\":\"YnV6\"}"
+    );
+    printer().clear();
+
+    mock.delete();
 }
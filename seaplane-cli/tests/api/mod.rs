@@ -26,9 +26,13 @@ macro_rules! run {
 }
 
 mod account;
+mod flags;
 mod formation;
+#[cfg(feature = "compute_api_v2")]
+mod formation_v2;
 mod locks;
 mod metadata;
+mod queue;
 mod restrict;
 
 fn ctx_from_url(url: String) -> Ctx {
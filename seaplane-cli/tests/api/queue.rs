@@ -0,0 +1,175 @@
+use httpmock::prelude::*;
+use seaplane_cli::printer::printer;
+use serde_json::json;
+
+use super::{then, when, MOCK_SERVER};
+
+#[test]
+fn queue_push() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        // "inbox/" followed by the task ID
+        w.method(PUT)
+            .path_contains("/v1/config/base64:aW5ib3gv")
+            .header("authorization", "Bearer abc.123.def")
+            .body("aGVsbG8");
+        t.status(200);
+    });
+
+    let res = run!("queue push inbox hello");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    // {nanos:020}-{uuid}
+    assert_eq!(printer().as_string().trim().len(), 53);
+    printer().clear();
+
+    mock.delete();
+}
+
+#[test]
+fn queue_pop() {
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:am9icw/");
+        then(
+            t,
+            &json!({"next_key": None::<String>, "kvs": [{"key": "am9icy9h", "value": "aGVsbG8"}]}),
+        );
+    });
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:am9icy9h")
+            .query_param("ttl", "30")
+            .query_param("client-id", "seaplane-queue");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 1}));
+    });
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:am9icy9h");
+        then(t, &json!({"key": "am9icy9h", "value": "aGVsbG8"}));
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:am9icy9h");
+        then(t, &json!({"status": 200_i32, "title": "Ok"}));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/locks/base64:am9icy9h").query_param("id", "D4lbVpdBE_U");
+        then(t, &json!({"status": 200_i32, "title": "Ok"}));
+    });
+
+    let res = run!("queue pop jobs");
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    acquire_mock.assert_hits(1);
+    get_mock.assert_hits(1);
+    delete_mock.assert_hits(1);
+    release_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "hello");
+    printer().clear();
+
+    list_mock.delete();
+    acquire_mock.delete();
+    get_mock.delete();
+    delete_mock.delete();
+    release_mock.delete();
+}
+
+#[test]
+fn queue_pop_keep() {
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:dGFza3M/");
+        then(
+            t,
+            &json!({"next_key": None::<String>, "kvs": [{"key": "dGFza3MvYQ", "value": "aGVsbG8"}]}),
+        );
+    });
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:dGFza3MvYQ")
+            .query_param("ttl", "300")
+            .query_param("client-id", "seaplane-queue");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 1}));
+    });
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:dGFza3MvYQ");
+        then(t, &json!({"key": "dGFza3MvYQ", "value": "aGVsbG8"}));
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:dGFza3MvYQ");
+        then(t, &json!({"status": 200_i32, "title": "Ok"}));
+    });
+
+    let res = run!("queue pop tasks --keep --ttl 300 --format json");
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    acquire_mock.assert_hits(1);
+    get_mock.assert_hits(1);
+    // The task stays in the queue
+    delete_mock.assert_hits(0);
+    assert_eq!(
+        printer().as_string().trim(),
+        r#"{"id":"a","value":"hello","lock_id":"D4lbVpdBE_U"}"#
+    );
+    printer().clear();
+
+    list_mock.delete();
+    acquire_mock.delete();
+    get_mock.delete();
+    delete_mock.delete();
+}
+
+#[test]
+fn queue_pop_empty() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:aWRsZQ/");
+        then(t, &json!({"next_key": None::<String>, "kvs": []}));
+    });
+
+    let res = run!("queue pop idle");
+    assert!(res.is_err());
+    mock.assert_hits(1);
+    printer().clear();
+
+    mock.delete();
+}
+
+#[test]
+fn queue_list() {
+    let mut kvs_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmFja2xvZw/");
+        then(
+            t,
+            &json!({"next_key": None::<String>, "kvs": [
+                {"key": "YmFja2xvZy9h", "value": "b25l"},
+                {"key": "YmFja2xvZy9i", "value": "dHdv"}
+            ]}),
+        );
+    });
+    let mut locks_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/locks/base64:YmFja2xvZw/");
+        then(
+            t,
+            &json!({"next": None::<String>, "locks": [
+                {"name": "YmFja2xvZy9i", "id": "D4lbVpdBE_U", "info": {"ttl": 30, "client-id": "worker-2", "ip": ""}}
+            ]}),
+        );
+    });
+
+    let res = run!("queue ls backlog");
+    assert!(res.is_ok());
+    kvs_mock.assert_hits(1);
+    locks_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "ID  CLAIMED-BY  VALUE
+a   -           one
+b   worker-2    two"
+    );
+    printer().clear();
+
+    let res = run!("queue ls backlog --format json");
+    assert!(res.is_ok());
+    assert_eq!(
+        printer().as_string().trim(),
+        r#"[{"id":"a","value":"one","claimed_by":null},{"id":"b","value":"two","claimed_by":"worker-2"}]"#
+    );
+    printer().clear();
+
+    kvs_mock.delete();
+    locks_mock.delete();
+}
@@ -1,5 +1,5 @@
 use httpmock::prelude::*;
-use seaplane_cli::printer::printer;
+use seaplane_cli::{error::TIMEOUT_EXIT_STATUS, printer::printer};
 use serde_json::json;
 
 use super::{then, when_json, MOCK_SERVER};
@@ -136,6 +136,53 @@ Config  foo/baz    Enforced  [XN]             []              []
     mock.delete();
 }
 
+#[test]
+fn restrict_list_unknown_values() {
+    let api_resp = json!({ "restrictions": [
+        {
+            "api": "Queues",
+            "directory": "Zm9vL2Jhcg",
+            "details": {
+                "regions_allowed": ["XE","XZ"],
+                "regions_denied": [],
+                "providers_allowed": ["Linode"],
+                "providers_denied": []
+            },
+            "state": "Revoking"
+        },
+    ]});
+
+    static TABLE: &str = "\
+API     DIRECTORY   STATE     REGIONS ALLOWED  REGIONS DENIED  PROVIDERS ALLOWED  PROVIDERS DENIED
+Queues  Zm9vL2Jhcg  Revoking  [XE,XZ]          []              [Linode]           []";
+
+    // Values the server sends which this version doesn't know about are kept as-is
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/");
+        then(t, &api_resp);
+    });
+
+    let res = run!("restrict list");
+    assert!(res.is_ok(), "{res:?}");
+    mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), TABLE);
+    printer().clear();
+
+    let res = run!("restrict list --format json");
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), api_resp["restrictions"].to_string());
+    printer().clear();
+
+    // But the user can't ask for them
+    let res = run!("restrict list queues");
+    assert!(res.is_err());
+    mock.assert_hits(2);
+    printer().clear();
+
+    mock.delete();
+}
+
 #[test]
 fn restrict_set() {
     let req_json = json!({
@@ -192,6 +239,112 @@ fn restrict_set() {
     mock.delete();
 }
 
+#[test]
+fn restrict_set_preset() {
+    let req_json = json!({
+        "regions_allowed": ["XE", "XU"],
+        "regions_denied": [],
+        "providers_allowed": [],
+        "providers_denied": ["GCP"]
+    });
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:cHJlc2V0/")
+            .header("content-type", "application/json")
+            .json_body_obj(&req_json);
+        then(t, &resp_json);
+    });
+
+    let res =
+        run!("restrict set config preset --preset eu-only --region uk --exclude-provider gcp");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    printer().clear();
+
+    let res = run!("restrict set config preset --preset nope");
+    assert!(res.is_err());
+    mock.assert_hits(1);
+
+    mock.delete();
+}
+
+#[test]
+fn restrict_set_wait() {
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+    let restriction = json!({
+        "api": "Config",
+        "directory": "d2FpdA",
+        "details": {
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        },
+        "state": "Enforced"
+    });
+
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, &resp_json);
+    });
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, &restriction);
+    });
+
+    let res = run!("restrict set config wait --region xe --wait");
+    assert!(res.is_ok());
+    put_mock.assert_hits(1);
+    get_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "Set a restriction on directory d2FpdA in config API
+The restriction on directory d2FpdA is enforced"
+    );
+    printer().clear();
+
+    let res = run!("restrict set config wait --region xe --wait --format json");
+    assert!(res.is_ok());
+    get_mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        json!({"set_restriction": {"api": "config", "directory": "d2FpdA", "state": "Enforced"}})
+            .to_string()
+    );
+    printer().clear();
+
+    put_mock.delete();
+    get_mock.delete();
+}
+
+#[test]
+fn restrict_get_wait_timeout() {
+    let restriction = json!({
+        "api": "Config",
+        "directory": "cGVuZGluZw",
+        "details": {
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        },
+        "state": "Pending"
+    });
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:cGVuZGluZw/");
+        then(t, &restriction);
+    });
+
+    let res = run!("restrict get config pending --wait --timeout 1");
+    assert_eq!(res.unwrap_err().exit_status(), TIMEOUT_EXIT_STATUS);
+    assert!(mock.hits() >= 1);
+    assert!(printer().as_string().is_empty());
+
+    mock.delete();
+}
+
 #[test]
 fn restrict_delete() {
     let resp_json = json!({"status": 200u32, "title": "Ok"});
@@ -239,3 +392,265 @@ fn restrict_delete() {
 
     mock.delete();
 }
+
+#[test]
+fn restrict_plan_apply() {
+    let path = "restrict-policy.toml";
+    std::fs::write(
+        path,
+        r#"
+[[restrictions]]
+api = "config"
+directory = "foo/bar"
+regions_allowed = ["XE", "XN"]
+
+[[restrictions]]
+api = "config"
+directory = "foo/baz"
+regions_allowed = ["XE"]
+
+[[restrictions]]
+api = "locks"
+directory = "foo/qux"
+providers_denied = ["GCP"]
+"#,
+    )
+    .unwrap();
+
+    // foo/bar already matches, foo/baz has different regions, foo/qux is missing and foo/old is
+    // not in the policy
+    let resp = json!({ "restrictions": [
+        {
+            "api": "Config",
+            "directory": "Zm9vL2Jhcg",
+            "details": {"regions_allowed": ["XE","XN"], "regions_denied": [], "providers_allowed": [], "providers_denied": []},
+            "state": "Enforced"
+        },
+        {
+            "api": "Config",
+            "directory": "Zm9vL2Jheg",
+            "details": {"regions_allowed": ["XN"], "regions_denied": [], "providers_allowed": [], "providers_denied": []},
+            "state": "Enforced"
+        },
+        {
+            "api": "Config",
+            "directory": "Zm9vL29sZA",
+            "details": {"regions_allowed": ["XU"], "regions_denied": [], "providers_allowed": [], "providers_denied": []},
+            "state": "Enforced"
+        },
+    ]});
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/");
+        then(t, &resp);
+    });
+
+    let res = run!("restrict plan -f restrict-policy.toml --decode");
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "\
+ACTION  API     DIRECTORY  REGIONS ALLOWED  REGIONS DENIED  PROVIDERS ALLOWED  PROVIDERS DENIED
+update  Config  foo/baz    [XE]             []              []                 []
+create  Locks   foo/qux    []               []              []                 [GCP]"
+    );
+    printer().clear();
+
+    let res = run!("restrict plan -f restrict-policy.toml --prune --format json");
+    assert!(res.is_ok());
+    list_mock.assert_hits(2);
+    let plan: serde_json::Value = serde_json::from_str(printer().as_string().trim()).unwrap();
+    let actions: Vec<_> = plan
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["action"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(actions, vec!["update", "create", "delete"]);
+    printer().clear();
+
+    let mut update_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:Zm9vL2Jheg/").json_body_obj(&json!({
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        }));
+        then(t, &resp_json);
+    });
+    let mut create_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/locks/base64:Zm9vL3F1eA/").json_body_obj(&json!({
+            "regions_allowed": [],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": ["GCP"]
+        }));
+        then(t, &resp_json);
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/restrict/config/base64:Zm9vL29sZA/");
+        then(t, &resp_json);
+    });
+
+    let res = run!("restrict apply -f restrict-policy.toml --prune --decode");
+    assert!(res.is_ok());
+    list_mock.assert_hits(3);
+    update_mock.assert_hits(1);
+    create_mock.assert_hits(1);
+    delete_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "\
+Updated a restriction on directory foo/baz in config API
+Created a restriction on directory foo/qux in locks API
+Deleted a restriction on directory foo/old in config API"
+    );
+    printer().clear();
+
+    std::fs::remove_file(path).unwrap();
+    list_mock.delete();
+    update_mock.delete();
+    create_mock.delete();
+    delete_mock.delete();
+}
+
+#[test]
+fn restrict_export() {
+    let resp = json!({ "restrictions": [
+        {
+            "api": "Locks",
+            "directory": "Zm9vL3F1eA",
+            "details": {"regions_allowed": [], "regions_denied": [], "providers_allowed": [], "providers_denied": ["GCP"]},
+            "state": "Enforced"
+        },
+        {
+            "api": "Config",
+            "directory": "Zm9vL2Jhcg",
+            "details": {"regions_allowed": ["XE","XN"], "regions_denied": [], "providers_allowed": [], "providers_denied": []},
+            "state": "Enforced"
+        },
+    ]});
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/");
+        then(t, &resp);
+    });
+
+    let res = run!("restrict export");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        r#"[[restrictions]]
+api = "config"
+directory = "foo/bar"
+regions_allowed = ["XE", "XN"]
+
+[[restrictions]]
+api = "locks"
+directory = "foo/qux"
+providers_denied = ["GCP"]"#
+    );
+    printer().clear();
+
+    let res = run!("restrict export --policy-format yaml");
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        "\
+restrictions:
+- api: config
+  directory: foo/bar
+  regions_allowed:
+  - XE
+  - XN
+- api: locks
+  directory: foo/qux
+  providers_denied:
+  - GCP"
+    );
+    printer().clear();
+
+    mock.delete();
+}
+
+#[test]
+fn restrict_check() {
+    let resp = json!({
+        "api": "Config",
+        "directory": "Y2hr",
+        "details": {
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": ["GCP"]
+        },
+        "state": "Enforced"
+    });
+    let not_found_json = json!({"status": 404_i32, "title": "Not Found", "detail": "not found"});
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:Y2hr/");
+        then(t, &resp);
+    });
+
+    let res = run!("restrict check config chk --provider aws --region europe --decode");
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "Placement with AWS in XE is allowed by the restriction on directory chk in config API"
+    );
+    printer().clear();
+
+    let res = run!("restrict check config chk --provider gcp --region xn --decode");
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        "\
+Placement with GCP in XN is not allowed by the restriction on directory chk in config API:
+  - provider GCP is denied
+  - region XN is not one of the allowed regions"
+    );
+    printer().clear();
+
+    let res = run!("restrict check config chk --provider gcp --region xe --format json");
+    assert!(res.is_ok());
+    mock.assert_hits(3);
+    assert_eq!(
+        printer().as_string().trim(),
+        json!({
+            "api": "config",
+            "directory": "Y2hr",
+            "provider": "GCP",
+            "region": "XE",
+            "restricted": true,
+            "permitted": false,
+            "reasons": ["provider GCP is denied"]
+        })
+        .to_string()
+    );
+    printer().clear();
+
+    let mut not_found_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:bm9uZQ/");
+        t.status(404).json_body_obj(&not_found_json);
+    });
+
+    let res = run!("restrict check config none --provider gcp --region xe --decode");
+    assert!(res.is_ok());
+    not_found_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "There is no restriction on directory none in config API, placement with GCP in XE is \
+         allowed"
+    );
+    printer().clear();
+
+    mock.delete();
+    not_found_mock.delete();
+}
@@ -399,6 +399,12 @@ fn seaplane_formation_common() {
     assert!(cli!("formation plan -I foo --region xa xc xf").is_err());
     // alias
     assert!(cli!("formation plan -I foo --regions=XA,Asia,XC").is_ok());
+    // the region names listed in the REGION SPEC, but not countries
+    assert!(
+        cli!("formation plan -I foo --region=north-america,South_America,united-kingdom,Peoples-Republic-Of-China")
+            .is_ok()
+    );
+    assert!(cli!("formation plan -I foo --region=DE").is_err());
 
     // exclude region
     // valid
@@ -502,6 +508,11 @@ fn seaplane_formation_plan() {
     // should be OK but not override
     assert!(cli!("formation plan -I foo --launch --no-active").is_ok());
 
+    // presets
+    assert!(cli!("formation plan -I foo --preset gdpr").is_ok());
+    assert!(cli!("formation plan -I foo --preset eu-only --exclude-provider gcp").is_ok());
+    assert!(cli!("formation plan -I foo --preset not-a-preset").is_err());
+
     // add is an alias
     assert!(cli!("formation add -I foo").is_ok());
     assert!(cli!("formation create -I foo").is_ok());
@@ -620,6 +631,12 @@ fn seaplane_md_get() {
 
     // can't have both --only-keys and --only-values
     assert!(cli!("metadata get foo --only-keys --only-values").is_err());
+
+    // --output-file requires a path
+    assert!(cli!("metadata get foo --output-file").is_err());
+    assert!(cli!("metadata get foo --output-file out.bin").is_ok());
+    assert!(cli!("metadata get foo -o out.bin").is_ok());
+    assert!(cli!("metadata get foo --key-file keys.json").is_ok());
 }
 
 #[test]
@@ -638,6 +655,13 @@ fn seaplane_md_set() {
 
     // aliases
     assert!(cli!("metadata put foo bar").is_ok());
+
+    // --encrypt requires a key file
+    assert!(cli!("metadata set foo bar --encrypt").is_err());
+    assert!(cli!("metadata set foo bar --encrypt --key-file keys.json").is_ok());
+    assert!(cli!("metadata set foo bar -e --key-file keys.json").is_ok());
+    // and a key file is only used with --encrypt
+    assert!(cli!("metadata set foo bar --key-file keys.json").is_err());
 }
 
 #[test]
@@ -661,6 +685,110 @@ fn seaplane_md_list() {
     assert!(cli!("metadata list --only-keys --only-values").is_err());
 }
 
+#[test]
+fn seaplane_md_edit() {
+    // requires a key
+    assert!(cli!("metadata edit").is_err());
+    assert!(cli!("metadata edit foo").is_ok());
+    assert!(cli!("metadata edit foo --force").is_ok());
+    // multiples not supported
+    assert!(cli!("metadata edit foo bar").is_err());
+}
+
+#[test]
+fn seaplane_md_watch() {
+    // requires a key or dir
+    assert!(cli!("metadata watch").is_err());
+    assert!(cli!("metadata watch foo").is_ok());
+    assert!(cli!("metadata watch foo/").is_ok());
+    assert!(cli!("metadata watch foo --interval 10 --count 3 --initial").is_ok());
+    assert!(cli!("metadata watch foo -i 10 -n 3 -x true").is_ok());
+    // interval must be at least one second
+    assert!(cli!("metadata watch foo --interval 0").is_err());
+    // multiples not supported
+    assert!(cli!("metadata watch foo bar").is_err());
+}
+
+#[test]
+fn seaplane_md_diff() {
+    // requires a dir and something to compare against
+    assert!(cli!("metadata diff").is_err());
+    assert!(cli!("metadata diff foo").is_err());
+    // can provide two dirs
+    assert!(cli!("metadata diff foo bar").is_ok());
+    assert!(cli!("metadata diff foo bar --exit-code").is_ok());
+    // or a snapshot
+    assert!(cli!("metadata diff foo --against snapshot.json").is_ok());
+    assert!(cli!("metadata diff foo -a snapshot.json").is_ok());
+    // but not both
+    assert!(cli!("metadata diff foo bar --against snapshot.json").is_err());
+    // multiples not supported
+    assert!(cli!("metadata diff foo bar baz").is_err());
+}
+
+#[test]
+fn seaplane_flags() {
+    // requires a subcmd
+    assert!(cli!("flags").is_err());
+    // provide subcmd
+    assert!(cli!("flags list").is_ok());
+    assert!(cli!("flags list --dir my-flags").is_ok());
+}
+
+#[test]
+fn seaplane_flags_create() {
+    // requires a name
+    assert!(cli!("flags create").is_err());
+    assert!(cli!("flags create beta").is_ok());
+    assert!(cli!("flags create beta --percentage 25 --region eu,xn --disabled").is_ok());
+    assert!(cli!("flags create beta --allow alice,bob --allow carol").is_ok());
+    // percentage and allow-list are different kinds of flags
+    assert!(cli!("flags create beta --percentage 25 --allow alice").is_err());
+    // percentage must be 0 to 100
+    assert!(cli!("flags create beta --percentage 101").is_err());
+    // names are a single key segment
+    assert!(cli!("flags create beta/gamma").is_err());
+}
+
+#[test]
+fn seaplane_flags_toggle() {
+    // requires a name
+    assert!(cli!("flags toggle").is_err());
+    assert!(cli!("flags toggle beta").is_ok());
+    assert!(cli!("flags toggle beta --on").is_ok());
+    assert!(cli!("flags toggle beta --off").is_ok());
+    assert!(cli!("flags toggle beta --on --off").is_err());
+}
+
+#[test]
+fn seaplane_queue() {
+    // requires a subcmd
+    assert!(cli!("queue").is_err());
+    // provide subcmd
+    assert!(cli!("queue list jobs").is_ok());
+    assert!(cli!("queue ls jobs").is_ok());
+    // requires a queue
+    assert!(cli!("queue list").is_err());
+}
+
+#[test]
+fn seaplane_queue_push() {
+    // requires a queue and value
+    assert!(cli!("queue push").is_err());
+    assert!(cli!("queue push jobs").is_err());
+    assert!(cli!("queue push jobs resize").is_ok());
+}
+
+#[test]
+fn seaplane_queue_pop() {
+    // requires a queue
+    assert!(cli!("queue pop").is_err());
+    assert!(cli!("queue pop jobs").is_ok());
+    assert!(cli!("queue pop jobs --keep --ttl 120").is_ok());
+    // ttl must be positive
+    assert!(cli!("queue pop jobs --ttl 0").is_err());
+}
+
 #[test]
 fn seaplane_locks() {
     // requires a subcmd
@@ -770,6 +898,12 @@ fn seaplane_restrict_get() {
 
     // three is a crowd
     assert!(cli!("restrict get foo bar baz").is_err());
+
+    // --timeout only makes sense when waiting
+    assert!(cli!("restrict get config foo --wait").is_ok());
+    assert!(cli!("restrict get config foo --wait --timeout 10").is_ok());
+    assert!(cli!("restrict get config foo --timeout 10").is_err());
+    assert!(cli!("restrict get config foo --wait --timeout 0").is_err());
 }
 
 #[test]
@@ -824,10 +958,55 @@ fn seaplane_restrict_set() {
     assert!(cli!("restrict set config foo --exclude-region xn").is_ok());
     assert!(cli!("restrict set config foo --region xe --exclude-region xn").is_ok());
     assert!(cli!("restrict set config foo --region EuRoPe --exclude-region Namerica").is_ok());
+    assert!(cli!("restrict set config foo --region united_kingdom --exclude-region North-America")
+        .is_ok());
 
     // lists everywhere
     assert!(cli!("restrict set config foo --provider aws,digitalocean --exclude-provider azure,gcp --region xe,xs --exclude-region xn,xc").is_ok());
 
     // default is all providers and regions allowed
     assert!(cli!("restrict set config foo").is_ok());
+
+    // presets
+    assert!(cli!("restrict set config foo --preset eu-only").is_ok());
+    assert!(cli!("restrict set config foo --preset eu-only --exclude-provider gcp").is_ok());
+    assert!(cli!("restrict set config foo --preset").is_err());
+    assert!(cli!("restrict set config foo --preset not-a-preset").is_err());
+
+    // --timeout only makes sense when waiting
+    assert!(cli!("restrict set config foo --region xe --wait").is_ok());
+    assert!(cli!("restrict set config foo --region xe --wait --timeout 10").is_ok());
+    assert!(cli!("restrict set config foo --region xe --timeout 10").is_err());
+}
+
+#[test]
+fn seaplane_restrict_plan() {
+    // requires a policy file
+    assert!(cli!("restrict plan").is_err());
+    assert!(cli!("restrict plan -f policy.toml").is_ok());
+    assert!(cli!("restrict plan --file policy.yaml --prune --decode").is_ok());
+    assert!(cli!("restrict apply").is_err());
+    assert!(cli!("restrict apply -f policy.toml --prune --format json").is_ok());
+}
+
+#[test]
+fn seaplane_restrict_check() {
+    // requires API, directory, provider and region
+    assert!(cli!("restrict check").is_err());
+    assert!(cli!("restrict check config foo").is_err());
+    assert!(cli!("restrict check config foo --provider aws").is_err());
+    assert!(cli!("restrict check config foo --region xe").is_err());
+    assert!(cli!("restrict check config foo --provider aws --region xe").is_ok());
+    assert!(cli!("restrict check config foo --provider AWS --region europe").is_ok());
+    // a placement is a single provider and region
+    assert!(cli!("restrict check config foo --provider all --region xe").is_err());
+    assert!(cli!("restrict check config foo --provider aws --region all").is_err());
+}
+
+#[test]
+fn seaplane_restrict_export() {
+    assert!(cli!("restrict export").is_ok());
+    assert!(cli!("restrict export config -f policy.yaml").is_ok());
+    assert!(cli!("restrict export --policy-format yaml").is_ok());
+    assert!(cli!("restrict export --policy-format json").is_err());
 }
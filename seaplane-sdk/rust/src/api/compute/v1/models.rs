@@ -1,22 +1,23 @@
 mod containers;
 mod endpoint;
 mod formation;
-use serde::Serialize;
-use strum::{Display, EnumString, EnumVariantNames};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
 
 pub use crate::api::compute::v1::models::{containers::*, endpoint::*, formation::*};
 
 /// The processor architecture a [`Flight`] wants to run on
-#[derive(
-    Debug, Serialize, Hash, Eq, PartialEq, Copy, Clone, Display, EnumString, EnumVariantNames,
-)]
+#[derive(Debug, Hash, Eq, PartialEq, Clone, EnumString, EnumVariantNames, IntoStaticStr)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum Architecture {
     AMD64,
     ARM64,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
-impl_deser_from_str!(Architecture);
+impl_str_enum_with_unknown!(Architecture);
 
 #[cfg(test)]
 mod test_arch {
@@ -31,4 +32,12 @@ mod test_arch {
         let arch: Architecture = serde_json::from_str("\"AMD64\"").unwrap();
         assert_eq!(arch, Architecture::AMD64);
     }
+
+    #[test]
+    fn architecture_unknown_round_trips() {
+        let arch: Architecture = serde_json::from_str("\"RISCV64\"").unwrap();
+        assert_eq!(arch, Architecture::Unknown("RISCV64".into()));
+        assert_eq!(serde_json::to_string(&arch).unwrap(), "\"RISCV64\"");
+        assert!("RISCV64".parse::<Architecture>().is_err());
+    }
 }
@@ -1,17 +1,24 @@
 use chrono::{offset::Utc, DateTime};
 use serde::{Deserialize, Serialize};
+use strum::{EnumString, IntoStaticStr};
 use uuid::Uuid;
 
 use crate::api::shared::v1::{Provider, Region};
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Eq, PartialEq, EnumString, IntoStaticStr)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+#[non_exhaustive]
 pub enum ContainerStatus {
     Started,
     Stopped,
     Running,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
+impl_str_enum_with_unknown!(ContainerStatus);
+
 /// Information about a particular Container Host
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ContainerHostInfo {
@@ -35,6 +42,14 @@ pub struct ContainerHostInfo {
     pub host_provider: Option<Provider>,
 }
 
+impl ContainerHostInfo {
+    /// The region of the country the container host is operating in, which is useful when the
+    /// host doesn't report its region
+    pub fn host_country_region(&self) -> Option<Region> {
+        self.host_country.as_deref().and_then(Region::from_country)
+    }
+}
+
 /// The response from `GET /formations/NAME/containers`
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(transparent)]
@@ -368,11 +368,7 @@ impl FormationConfiguration {
     /// ([`FormationConfigurationBuilder::add_connection`]).
     #[cfg(feature = "unstable")]
     #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
-    pub fn formation_endpoints(
-        &self,
-        _key: EndpointKey,
-        _value: EndpointValue,
-    ) -> impl Iterator<Item = (&EndpointKey, &EndpointValue)> {
+    pub fn formation_endpoints(&self) -> impl Iterator<Item = (&EndpointKey, &EndpointValue)> {
         self.formation_endpoints.iter()
     }
 
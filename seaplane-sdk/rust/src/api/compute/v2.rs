@@ -1,7 +1,11 @@
 //! The `/formations` endpoint APIs which allows working with [`Formation`]s,
 //! [`Flight`]s, and the underlying containers
 
+#[cfg(feature = "compute_api_v1")]
+mod convert;
 mod models;
+#[cfg(feature = "compute_api_v1")]
+pub use convert::*;
 pub use models::*;
 
 use crate::{
@@ -0,0 +1,309 @@
+//! Conversions between the v1 [`FormationConfiguration`] and the v2 [`Formation`] models.
+//!
+//! The v2 model only knows about the name and image of each Flight plus a gateway Flight, so most
+//! of a v1 configuration has no equivalent. Rather than silently dropping those values the
+//! conversions return a [`ConversionError::Lossy`] listing every field that was left out, along
+//! with the converted model.
+
+use std::fmt;
+
+use crate::{
+    api::compute::{
+        v1::{
+            EndpointKey, EndpointValue, Flight as FlightV1,
+            FormationConfiguration as FormationConfigurationV1,
+        },
+        v2::{Flight, Formation},
+    },
+    error::SeaplaneError,
+};
+
+/// A field which was set in the source model but has no equivalent in the target model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedField {
+    /// The path of the field in the source model, i.e. `flights[web].minimum`
+    pub field: String,
+    /// The value the field had
+    pub value: String,
+}
+
+impl DroppedField {
+    fn new<S: Into<String>>(field: S, value: impl fmt::Display) -> Self {
+        Self { field: field.into(), value: value.to_string() }
+    }
+
+    fn list<S, I, T>(field: S, values: I) -> Option<Self>
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        let mut values: Vec<String> = values.into_iter().map(|v| v.to_string()).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+        Some(Self::new(field, values.join(", ")))
+    }
+
+    fn endpoints<'a, S, I>(field: S, endpoints: I) -> Option<Self>
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (&'a EndpointKey, &'a EndpointValue)>,
+    {
+        Self::list(field, endpoints.into_iter().map(|(k, v)| format!("{k}={v}")))
+    }
+}
+
+impl fmt::Display for DroppedField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.field, self.value)
+    }
+}
+
+/// The error returned when converting between the v1 and v2 Compute API models
+#[derive(Debug)]
+pub enum ConversionError<T> {
+    /// The model was converted, but the `dropped` fields have no equivalent in the target model
+    /// and were left out of `converted`
+    Lossy { converted: T, dropped: Vec<DroppedField> },
+    /// The model can't be converted at all
+    Invalid(SeaplaneError),
+}
+
+impl<T> ConversionError<T> {
+    /// Returns the fields which have no equivalent in the target model
+    pub fn dropped(&self) -> &[DroppedField] {
+        match self {
+            ConversionError::Lossy { dropped, .. } => dropped,
+            ConversionError::Invalid(_) => &[],
+        }
+    }
+
+    /// Returns the converted model without the dropped fields, or the reason the model couldn't be
+    /// converted at all
+    pub fn into_lossy(self) -> Result<T, SeaplaneError> {
+        match self {
+            ConversionError::Lossy { converted, .. } => Ok(converted),
+            ConversionError::Invalid(e) => Err(e),
+        }
+    }
+}
+
+impl<T> fmt::Display for ConversionError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Lossy { dropped, .. } => {
+                write!(f, "the following fields have no equivalent in the target model: ")?;
+                let fields: Vec<&str> = dropped.iter().map(|d| d.field.as_str()).collect();
+                f.write_str(&fields.join(", "))
+            }
+            ConversionError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for ConversionError<T> {}
+
+impl<T> From<SeaplaneError> for ConversionError<T> {
+    fn from(e: SeaplaneError) -> Self { ConversionError::Invalid(e) }
+}
+
+/// The v1 configuration has no name, so the converted [`Formation`] has an empty name which can be
+/// set with [`Formation::set_name`].
+///
+/// The gateway Flight is the Flight all public endpoints point to. If the public endpoints point
+/// to more than one Flight the one serving the `http:/` route is used, and if there are no public
+/// endpoints the configuration must only have a single Flight.
+impl TryFrom<FormationConfigurationV1> for Formation {
+    type Error = ConversionError<Formation>;
+
+    fn try_from(cfg: FormationConfigurationV1) -> Result<Self, Self::Error> {
+        let mut dropped = Vec::new();
+
+        let mut builder = Formation::builder();
+        for flight in cfg.flights() {
+            let name = flight.name();
+            if flight.minimum() != 0 {
+                dropped
+                    .push(DroppedField::new(format!("flights[{name}].minimum"), flight.minimum()));
+            }
+            if let Some(max) = flight.maximum() {
+                dropped.push(DroppedField::new(format!("flights[{name}].maximum"), max));
+            }
+            dropped.extend(DroppedField::list(
+                format!("flights[{name}].architecture"),
+                flight.architecture(),
+            ));
+            if flight.api_permission() {
+                dropped.push(DroppedField::new(format!("flights[{name}].api_permission"), true));
+            }
+            builder = builder.add_flight(
+                Flight::builder()
+                    .name(name)
+                    .image_reference(flight.image().clone())
+                    .build()?,
+            );
+        }
+
+        let mut gateways: Vec<&str> = cfg
+            .public_endpoints()
+            .map(|(_, v)| v.flight_name.as_str())
+            .collect();
+        gateways.sort_unstable();
+        gateways.dedup();
+        let gateway = match gateways.as_slice() {
+            [gateway] => Some(*gateway),
+            _ => cfg.public_endpoints().find_map(|(k, v)| {
+                if k == &(EndpointKey::Http { path: "/".into() }) {
+                    Some(v.flight_name.as_str())
+                } else {
+                    None
+                }
+            }),
+        };
+        match gateway {
+            Some(gateway) => builder = builder.gateway_flight(gateway),
+            None if !gateways.is_empty() => return Err(SeaplaneError::NoGatewayFlight.into()),
+            None => (),
+        }
+
+        dropped.extend(DroppedField::list("affinity", cfg.affinities()));
+        dropped.extend(DroppedField::list("connections", cfg.connections()));
+        dropped.extend(DroppedField::endpoints("public_endpoints", cfg.public_endpoints()));
+        dropped.extend(DroppedField::endpoints("formation_endpoints", cfg.formation_endpoints()));
+        dropped.extend(DroppedField::endpoints("flight_endpoints", cfg.flight_endpoints()));
+        dropped.extend(DroppedField::list("providers_allowed", cfg.allowed_providers()));
+        dropped.extend(DroppedField::list("providers_denied", cfg.denied_providers()));
+        dropped.extend(DroppedField::list("regions_allowed", cfg.allowed_regions()));
+        dropped.extend(DroppedField::list("regions_denied", cfg.denied_region()));
+
+        let converted = builder.build()?;
+        if dropped.is_empty() {
+            Ok(converted)
+        } else {
+            Err(ConversionError::Lossy { converted, dropped })
+        }
+    }
+}
+
+/// The v1 model routes public traffic using public endpoints, which require a port the v2 model
+/// doesn't have. A Formation with a single Flight converts without loss, because converting it
+/// back makes that Flight the gateway again, otherwise the gateway Flight is reported as dropped.
+/// The Formation name isn't part of a v1 configuration and is not reported.
+impl TryFrom<Formation> for FormationConfigurationV1 {
+    type Error = ConversionError<FormationConfigurationV1>;
+
+    fn try_from(formation: Formation) -> Result<Self, Self::Error> {
+        let mut builder = FormationConfigurationV1::builder();
+        for flight in formation.flights() {
+            builder = builder.add_flight(
+                FlightV1::builder()
+                    .name(flight.name())
+                    .image_reference(flight.image().clone())
+                    .build()?,
+            );
+        }
+
+        let converted = builder.build()?;
+        if formation.flights().len() == 1 {
+            Ok(converted)
+        } else {
+            Err(ConversionError::Lossy {
+                converted,
+                dropped: vec![DroppedField::new("gateway_flight", formation.gateway_flight())],
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn cfg(json: serde_json::Value) -> FormationConfigurationV1 {
+        serde_json::from_str(&json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn v1_to_v2_lossless() {
+        let cfg = cfg(json!({
+            "flights": [{"name": "web", "image": "foo.com/bar:latest"}],
+            "public_endpoints": {}
+        }));
+        let formation = Formation::try_from(cfg).unwrap();
+        assert_eq!(formation.gateway_flight(), "web");
+        assert_eq!(formation.flights().len(), 1);
+    }
+
+    #[test]
+    fn v1_to_v2_lossy() {
+        let cfg = cfg(json!({
+            "flights": [
+                {"name": "web", "image": "foo.com/bar:latest", "minimum": 2, "maximum": 5},
+                {"name": "db", "image": "foo.com/db:latest", "api_permission": true}
+            ],
+            "public_endpoints": {"http:/": "web:80", "http:/api": "db:8080"},
+            "regions_allowed": ["XE", "XU"]
+        }));
+        let err = Formation::try_from(cfg).unwrap_err();
+        let dropped: Vec<String> = err.dropped().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            dropped,
+            [
+                "flights[web].minimum = 2",
+                "flights[web].maximum = 5",
+                "flights[db].api_permission = true",
+                "public_endpoints = http:/=web:80, http:/api=db:8080",
+                "regions_allowed = XE, XU",
+            ]
+        );
+        let formation = err.into_lossy().unwrap();
+        assert_eq!(formation.gateway_flight(), "web");
+    }
+
+    #[test]
+    fn v1_to_v2_no_gateway() {
+        let cfg = cfg(json!({
+            "flights": [
+                {"name": "web", "image": "foo.com/bar:latest"},
+                {"name": "db", "image": "foo.com/db:latest"}
+            ]
+        }));
+        assert!(matches!(
+            Formation::try_from(cfg),
+            Err(ConversionError::Invalid(SeaplaneError::NoGatewayFlight))
+        ));
+    }
+
+    #[test]
+    fn v2_to_v1_lossless() {
+        let formation = Formation::builder()
+            .name("example")
+            .add_flight(Flight::new("web", "foo.com/bar:latest"))
+            .build()
+            .unwrap();
+        let cfg = FormationConfigurationV1::try_from(formation).unwrap();
+        assert_eq!(cfg.flights()[0].name(), "web");
+        assert_eq!(cfg.flights()[0].image_str(), "foo.com/bar:latest");
+        // The only Flight is the gateway again
+        assert_eq!(Formation::try_from(cfg).unwrap().gateway_flight(), "web");
+    }
+
+    #[test]
+    fn v2_to_v1_lossy() {
+        let formation = Formation::builder()
+            .name("example")
+            .add_flight(Flight::new("web", "foo.com/bar:latest"))
+            .add_flight(Flight::new("db", "foo.com/db:latest"))
+            .gateway_flight("web")
+            .build()
+            .unwrap();
+        let err = FormationConfigurationV1::try_from(formation).unwrap_err();
+        assert_eq!(err.dropped(), [DroppedField::new("gateway_flight", "web")]);
+        let cfg = err.into_lossy().unwrap();
+        assert_eq!(cfg.flights().len(), 2);
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumString};
+use strum::{EnumString, IntoStaticStr};
 
 #[cfg(doc)]
 use crate::api::compute::v2::FormationsRequest;
@@ -9,15 +9,18 @@ use crate::{
 };
 
 /// Whether a Flight is Health or Unhealthy as determined by the runtime
-#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, Display, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, IntoStaticStr)]
 #[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+#[non_exhaustive]
 pub enum FlightHealthStatus {
     Healthy,
     Unhealthy,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
-impl_deser_from_str!(FlightHealthStatus);
+impl_str_enum_with_unknown!(FlightHealthStatus);
 
 #[cfg(test)]
 mod flight_health_status_tests {
@@ -38,6 +41,15 @@ mod flight_health_status_tests {
     fn ser() {
         assert_eq!(FlightHealthStatus::Healthy.to_string(), "healthy".to_string());
         assert_eq!(FlightHealthStatus::Unhealthy.to_string(), "unhealthy".to_string());
+        assert_eq!(serde_json::to_string(&FlightHealthStatus::Healthy).unwrap(), "\"healthy\"");
+    }
+
+    #[test]
+    fn unknown_round_trips() {
+        let status: FlightHealthStatus = serde_json::from_str("\"degraded\"").unwrap();
+        assert_eq!(status, FlightHealthStatus::Unknown("degraded".into()));
+        assert_eq!(serde_json::to_string(&status).unwrap(), "\"degraded\"");
+        assert!("degraded".parse::<FlightHealthStatus>().is_err());
     }
 }
 
@@ -137,6 +149,13 @@ pub struct FormationBuilder {
 }
 
 impl FormationBuilder {
+    /// The human readable name of the Formation, which must be unique within the tenant
+    #[must_use]
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// Add a [`Flight`] to the makeup of this Formation Configuration.
     ///
     /// **NOTE:** This method can be called multiple times. All values will be utilized.
@@ -146,6 +165,9 @@ impl FormationBuilder {
         self
     }
 
+    /// The name of the [`Flight`] which receives the public traffic of this Formation, this may be
+    /// omitted when the Formation only has a single [`Flight`]
+    #[must_use]
     pub fn gateway_flight(mut self, flight: impl Into<String>) -> Self {
         // @TODO validate flight name
         self.gateway_flight = Some(flight.into());
@@ -220,8 +242,19 @@ impl Formation {
     /// Set the [`Flight`]s that makeup this Formation Configuration.
     pub fn set_flights(&mut self, flights: Vec<Flight>) { self.flights = flights; }
 
-    /// Set the [`Flight`]s that makeup this Formation Configuration.
+    /// Returns the [`Flight`]s that makeup this Formation Configuration.
     pub fn flights(&self) -> &[Flight] { &self.flights }
+
+    /// Returns the human readable name of this Formation
+    #[inline]
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Set the human readable name of this Formation
+    pub fn set_name<S: Into<String>>(&mut self, name: S) { self.name = name.into(); }
+
+    /// Returns the name of the [`Flight`] which receives the public traffic of this Formation
+    #[inline]
+    pub fn gateway_flight(&self) -> &str { &self.gateway_flight }
 }
 
 #[cfg(test)]
@@ -261,6 +294,18 @@ mod formation_tests {
 
         assert_eq!(json.to_string(), serde_json::to_string(&model).unwrap());
     }
+
+    #[test]
+    fn builder() {
+        let model = Formation::builder()
+            .name("example-formation")
+            .add_flight(Flight::new("example-flight", "foo.com/bar:latest"))
+            .build()
+            .unwrap();
+
+        assert_eq!(model.name(), "example-formation");
+        assert_eq!(model.gateway_flight(), "example-flight");
+    }
 }
 
 /// A builder to construct [`Flight`]s
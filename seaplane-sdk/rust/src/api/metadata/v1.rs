@@ -1,10 +1,19 @@
 //! The `/config` endpoint APIs which allows working with [`KeyValue`]s
+mod chunked;
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+mod encryption;
 mod models;
+mod watch;
+pub use chunked::*;
+#[cfg(feature = "encryption")]
+pub use encryption::*;
 pub use models::*;
 use reqwest::{
     header::{self, CONTENT_TYPE},
     Url,
 };
+pub use watch::*;
 
 use crate::{
     api::{
@@ -112,22 +121,87 @@ impl MetadataRequest {
             None | Some(RequestTarget::Key(_)) => {
                 Err(SeaplaneError::IncorrectMetadataRequestTarget)
             }
-            Some(RequestTarget::Range(context)) => {
-                let mut url = self.request.endpoint_url.clone();
+            Some(RequestTarget::Range(context)) => Ok(self.range_url_for(context)),
+        }
+    }
 
-                if let Some(encoded_dir) = context.directory() {
-                    url = add_base64_path_segment(url, encoded_dir.encoded());
-                    // A directory is distinguished from a key by the trailing slash
-                    url.set_path(&format!("{}/", url.path()));
-                }
+    // Internal method creating the URL for a range that may differ from the request target
+    fn range_url_for(&self, context: &RangeQueryContext<Key>) -> Url {
+        let mut url = self.request.endpoint_url.clone();
 
-                if let Some(from) = context.from() {
-                    url.set_query(Some(&format!("from=base64:{}", from.encoded())));
-                }
+        if let Some(encoded_dir) = context.directory() {
+            url = add_base64_path_segment(url, encoded_dir.encoded());
+            // A directory is distinguished from a key by the trailing slash
+            url.set_path(&format!("{}/", url.path()));
+        }
 
-                Ok(url)
-            }
+        if let Some(from) = context.from() {
+            url.set_query(Some(&format!("from=base64:{}", from.encoded())));
         }
+
+        url
+    }
+
+    // Internal method creating the URL for a key that may differ from the request target
+    fn key_url_for(&self, key: &Key) -> Url {
+        add_base64_path_segment(self.request.endpoint_url.clone(), key.encoded())
+    }
+
+    // Internal method to GET a single value at a given URL
+    fn get_value_at(&self, url: Url) -> Result<Value> {
+        let resp = self
+            .request
+            .client
+            .get(url)
+            .bearer_auth(&self.request.token)
+            .send()?;
+        map_api_error(resp)?
+            .json::<KeyValue>()
+            .map(|kv| kv.value)
+            .map_err(Into::into)
+    }
+
+    // Internal method to PUT a single value at a given URL
+    fn put_value_at(&self, url: Url, value: Value) -> Result<()> {
+        let resp = self
+            .request
+            .client
+            .put(url)
+            .bearer_auth(&self.request.token)
+            .header(CONTENT_TYPE, header::HeaderValue::from_static("application/octet-stream"))
+            .body(value.to_string())
+            .send()?;
+        map_api_error(resp)?
+            .text()
+            .map(|_| ()) // TODO: for now we drop the "success" message to control it ourselves
+            .map_err(Into::into)
+    }
+
+    // Internal method to DELETE a single value at a given URL
+    fn delete_value_at(&self, url: Url) -> Result<()> {
+        let resp = self
+            .request
+            .client
+            .delete(url)
+            .bearer_auth(&self.request.token)
+            .send()?;
+        map_api_error(resp)?
+            .text()
+            .map(|_| ()) // TODO: for now we drop the "success" message to control it ourselves
+            .map_err(Into::into)
+    }
+
+    // Internal method to GET a single page of a range at a given URL
+    fn get_page_at(&self, url: Url) -> Result<KeyValueRange> {
+        let resp = self
+            .request
+            .client
+            .get(url)
+            .bearer_auth(&self.request.token)
+            .send()?;
+        map_api_error(resp)?
+            .json::<KeyValueRange>()
+            .map_err(Into::into)
     }
 
     /// Returns the key value pair associated with the set key.
@@ -148,19 +222,7 @@ impl MetadataRequest {
     /// let resp = req.get_value().unwrap();
     /// dbg!(resp);
     /// ```
-    pub fn get_value(&self) -> Result<Value> {
-        let url = self.single_key_url()?;
-        let resp = self
-            .request
-            .client
-            .get(url)
-            .bearer_auth(&self.request.token)
-            .send()?;
-        map_api_error(resp)?
-            .json::<KeyValue>()
-            .map(|kv| kv.value)
-            .map_err(Into::into)
-    }
+    pub fn get_value(&self) -> Result<Value> { self.get_value_at(self.single_key_url()?) }
 
     /// Adds an unencoded value to the store at the given key performing the encoding before
     /// sending the request.
@@ -202,19 +264,7 @@ impl MetadataRequest {
     /// dbg!(resp);
     /// ```
     pub fn put_value(&self, value: Value) -> Result<()> {
-        let url = self.single_key_url()?;
-        let resp = self
-            .request
-            .client
-            .put(url)
-            .bearer_auth(&self.request.token)
-            .header(CONTENT_TYPE, header::HeaderValue::from_static("application/octet-stream"))
-            .body(value.to_string())
-            .send()?;
-        map_api_error(resp)?
-            .text()
-            .map(|_| ()) // TODO: for now we drop the "success" message to control it ourselves
-            .map_err(Into::into)
+        self.put_value_at(self.single_key_url()?, value)
     }
 
     /// Deletes the key value pair at from a given base64 encoded key.
@@ -234,19 +284,7 @@ impl MetadataRequest {
     /// let resp = req.delete_value().unwrap();
     /// dbg!(resp);
     /// ```
-    pub fn delete_value(&self) -> Result<()> {
-        let url = self.single_key_url()?;
-        let resp = self
-            .request
-            .client
-            .delete(url)
-            .bearer_auth(&self.request.token)
-            .send()?;
-        map_api_error(resp)?
-            .text()
-            .map(|_| ()) // TODO: for now we drop the "success" message to control it ourselves
-            .map_err(Into::into)
-    }
+    pub fn delete_value(&self) -> Result<()> { self.delete_value_at(self.single_key_url()?) }
 
     /// Returns a single page of key value pairs for the given directory, beginning with the `from`
     /// key.
@@ -294,19 +332,7 @@ impl MetadataRequest {
             None | Some(RequestTarget::Key(_)) => {
                 Err(SeaplaneError::IncorrectMetadataRequestTarget)
             }
-            Some(RequestTarget::Range(_)) => {
-                let url = self.range_url()?;
-
-                let resp = self
-                    .request
-                    .client
-                    .get(url)
-                    .bearer_auth(&self.request.token)
-                    .send()?;
-                map_api_error(resp)?
-                    .json::<KeyValueRange>()
-                    .map_err(Into::into)
-            }
+            Some(RequestTarget::Range(_)) => self.get_page_at(self.range_url()?),
         }
     }
 
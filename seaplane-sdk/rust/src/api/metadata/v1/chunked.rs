@@ -0,0 +1,333 @@
+//! Storing values that are too large for a single key by splitting them into chunks.
+//!
+//! A chunked value is made up of a *manifest* stored at the requested key, and any number of
+//! *chunks* stored under the `.chunks/` sub-directory of that key. The manifest records the total
+//! size, the number of chunks and the SHA-256 digest of the full value so that it can be verified
+//! when it is reassembled.
+//!
+//! Each upload uses a unique ID in the chunk key names, and the manifest is only written once all
+//! chunks have been stored. This means readers never see a partially written value, and chunks of
+//! a failed or replaced upload can be found and removed later with
+//! [`MetadataRequest::cleanup_chunks`].
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::{
+        metadata::v1::{Key, MetadataRequest, RequestTarget, Value},
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// The default size in bytes of each chunk (before base64 encoding)
+pub const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+
+/// The value of [`ChunkManifest::format`] used to tell a manifest apart from a regular value
+pub const CHUNK_MANIFEST_FORMAT: &str = "seaplane-chunked-value/v1";
+
+// The sub-directory of a key under which its chunks are stored
+const CHUNKS_DIR: &str = "/.chunks/";
+
+/// The manifest of a chunked value which is stored in place of the value itself
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ChunkManifest {
+    /// Always [`CHUNK_MANIFEST_FORMAT`]
+    pub format: String,
+    /// The unique ID of the upload these chunks belong to
+    pub id: String,
+    /// The total size of the value in bytes
+    pub size: u64,
+    /// The number of chunks the value was split into
+    pub chunks: u64,
+    /// The hex encoded SHA-256 digest of the full value
+    pub sha256: String,
+}
+
+impl ChunkManifest {
+    /// Attempts to interpret a value as a chunk manifest, returning `None` if it is just a regular
+    /// value
+    pub fn from_value(value: &Value) -> Option<Self> {
+        serde_json::from_slice::<Self>(&value.decode())
+            .ok()
+            .filter(|m| m.format == CHUNK_MANIFEST_FORMAT)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String { bytes.iter().map(|b| format!("{b:02x}")).collect() }
+
+// Reads from `reader` until `buf` is full or EOF is reached, returning the number of bytes read
+fn fill_buf<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(read)
+}
+
+impl MetadataRequest {
+    // Internal method returning the un-encoded target key
+    fn target_key(&self) -> Result<Vec<u8>> {
+        match &self.request.target {
+            Some(RequestTarget::Key(k)) => Ok(k.decode()),
+            _ => Err(SeaplaneError::IncorrectMetadataRequestTarget),
+        }
+    }
+
+    // Internal method returning the key of a single chunk of the target key
+    fn chunk_key(&self, id: &str, index: u64) -> Result<Key> {
+        let mut key = self.target_key()?;
+        key.extend_from_slice(CHUNKS_DIR.as_bytes());
+        key.extend_from_slice(format!("{id}-{index:08}").as_bytes());
+        Ok(Key::from_unencoded(key))
+    }
+
+    /// Returns the manifest stored at the target key, or `None` if the key does not exist or holds
+    /// a regular (non-chunked) value.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn get_chunk_manifest(&self) -> Result<Option<ChunkManifest>> {
+        match self.get_value() {
+            Ok(value) => Ok(ChunkManifest::from_value(&value)),
+            Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Streams a value from `reader` into the store at the target key, splitting it into chunks
+    /// of (at most) `chunk_size` bytes. Any chunks of a previous chunked value at this key are
+    /// removed once the new value has been stored.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use seaplane::api::metadata::v1::{MetadataRequestBuilder, DEFAULT_CHUNK_SIZE};
+    ///
+    /// let req = MetadataRequestBuilder::new()
+    ///     .token("abc123_token")
+    ///     .encoded_key("bW9ieQo")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let f = File::open("model.json").unwrap();
+    /// let manifest = req.put_value_chunked(f, DEFAULT_CHUNK_SIZE).unwrap();
+    /// dbg!(manifest);
+    /// ```
+    pub fn put_value_chunked<R: Read>(
+        &self,
+        mut reader: R,
+        chunk_size: usize,
+    ) -> Result<ChunkManifest> {
+        let previous = self.get_chunk_manifest()?;
+        let id = uuid::Uuid::new_v4().simple().to_string();
+
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0; chunk_size.max(1)];
+        let mut size = 0;
+        let mut chunks = 0;
+        loop {
+            let read = fill_buf(&mut reader, &mut buf)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+            let url = self.key_url_for(&self.chunk_key(&id, chunks)?);
+            if let Err(e) = self.put_value_at(url, Value::from_unencoded(&buf[..read])) {
+                // Best effort attempt to not leave orphans behind, anything left over can be
+                // removed by `cleanup_chunks`
+                for index in 0..chunks {
+                    let _ = self.delete_value_at(self.key_url_for(&self.chunk_key(&id, index)?));
+                }
+                return Err(e);
+            }
+            size += read as u64;
+            chunks += 1;
+        }
+
+        let manifest = ChunkManifest {
+            format: CHUNK_MANIFEST_FORMAT.into(),
+            id,
+            size,
+            chunks,
+            sha256: hex(&hasher.finalize()),
+        };
+        self.put_value(Value::from_unencoded(serde_json::to_vec(&manifest)?))?;
+
+        // The new value is already in place, so failing to remove the old chunks is not an error
+        // of this request. They can still be removed by `cleanup_chunks`.
+        if let Some(previous) = previous {
+            let _ = self.delete_chunks(&previous);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Reassembles a chunked value from the store at the target key into `writer`, verifying the
+    /// size and SHA-256 digest against the manifest.
+    ///
+    /// **NOTE:** The data is written as it is retrieved, so if verification fails
+    /// ([`SeaplaneError::ChunkedValueCorrupted`]) `writer` will already have received the invalid
+    /// data. If the key holds a regular value [`SeaplaneError::NotChunkedValue`] is returned.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use seaplane::api::metadata::v1::MetadataRequestBuilder;
+    ///
+    /// let req = MetadataRequestBuilder::new()
+    ///     .token("abc123_token")
+    ///     .encoded_key("bW9ieQo")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let f = File::create("model.json").unwrap();
+    /// let manifest = req.get_value_chunked(f).unwrap();
+    /// dbg!(manifest);
+    /// ```
+    pub fn get_value_chunked<W: Write>(&self, mut writer: W) -> Result<ChunkManifest> {
+        let manifest =
+            ChunkManifest::from_value(&self.get_value()?).ok_or(SeaplaneError::NotChunkedValue)?;
+
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        for index in 0..manifest.chunks {
+            let chunk = self
+                .get_value_at(self.key_url_for(&self.chunk_key(&manifest.id, index)?))?
+                .decode();
+            hasher.update(&chunk);
+            writer.write_all(&chunk)?;
+            size += chunk.len() as u64;
+        }
+        writer.flush()?;
+
+        if size != manifest.size {
+            return Err(SeaplaneError::ChunkedValueCorrupted(format!(
+                "expected {} bytes but got {size}",
+                manifest.size
+            )));
+        }
+        let sha256 = hex(&hasher.finalize());
+        if sha256 != manifest.sha256 {
+            return Err(SeaplaneError::ChunkedValueCorrupted(format!(
+                "expected SHA-256 digest {} but got {sha256}",
+                manifest.sha256
+            )));
+        }
+
+        Ok(manifest)
+    }
+
+    /// Deletes a chunked value at the target key, including all of its chunks.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn delete_value_chunked(&self) -> Result<()> {
+        let manifest =
+            ChunkManifest::from_value(&self.get_value()?).ok_or(SeaplaneError::NotChunkedValue)?;
+        // Remove the manifest first so the value never appears to exist with missing chunks
+        self.delete_value()?;
+        self.delete_chunks(&manifest)
+    }
+
+    /// Deletes the chunks described by `manifest` from under the target key, without touching the
+    /// value stored at the key itself. This is useful after a chunked value has been replaced by
+    /// a regular one.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn delete_chunks(&self, manifest: &ChunkManifest) -> Result<()> {
+        for index in 0..manifest.chunks {
+            self.delete_value_at(self.key_url_for(&self.chunk_key(&manifest.id, index)?))?;
+        }
+        Ok(())
+    }
+
+    /// Removes any chunks under the target key which do not belong to the chunked value currently
+    /// stored there (for example left behind by an interrupted upload), returning the number of
+    /// chunks removed.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn cleanup_chunks(&self) -> Result<usize> {
+        let current = self.get_chunk_manifest()?.map(|m| format!("{}-", m.id));
+
+        let mut dir = self.target_key()?;
+        dir.extend_from_slice(CHUNKS_DIR.trim_end_matches('/').as_bytes());
+        let mut context = RangeQueryContext::new();
+        context.set_directory(Directory::from_unencoded(dir));
+
+        let mut removed = 0;
+        loop {
+            let page = self.get_page_at(self.range_url_for(&context))?;
+            for kv in page.kvs {
+                let key = kv.key.decode();
+                // Keys may or may not include the directory, so we only look at the final segment
+                let name = key.rsplit(|b| *b == b'/').next().unwrap_or_default();
+                let is_current = current
+                    .as_ref()
+                    .map(|prefix| name.starts_with(prefix.as_bytes()))
+                    .unwrap_or(false);
+                if !is_current {
+                    self.delete_value_at(self.key_url_for(&kv.key))?;
+                    removed += 1;
+                }
+            }
+            match page.next_key {
+                Some(next_key) => context.set_from(next_key),
+                None => break,
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod chunked_test {
+    use super::*;
+
+    #[test]
+    fn manifest_from_value() {
+        let manifest = ChunkManifest {
+            format: CHUNK_MANIFEST_FORMAT.into(),
+            id: "abc".into(),
+            size: 3,
+            chunks: 1,
+            sha256: hex(&Sha256::digest(b"foo")),
+        };
+        let value = Value::from_unencoded(serde_json::to_vec(&manifest).unwrap());
+        assert_eq!(ChunkManifest::from_value(&value), Some(manifest));
+        assert_eq!(ChunkManifest::from_value(&Value::from_unencoded("foo")), None);
+        assert_eq!(
+            ChunkManifest::from_value(&Value::from_unencoded(
+                r#"{"format":"other","id":"abc","size":3,"chunks":1,"sha256":""}"#
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn fill_buf_reads_full_chunks() {
+        let mut reader = io::Cursor::new(b"hello world".to_vec()).chain(io::Cursor::new(b"!"));
+        let mut buf = [0; 8];
+        assert_eq!(fill_buf(&mut reader, &mut buf).unwrap(), 8);
+        assert_eq!(&buf, b"hello wo");
+        assert_eq!(fill_buf(&mut reader, &mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], b"rld!");
+        assert_eq!(fill_buf(&mut reader, &mut buf).unwrap(), 0);
+    }
+}
@@ -0,0 +1,541 @@
+//! Client-side envelope encryption of metadata values
+//!
+//! Values are sealed with a [`DataKey`] before they are sent to the Metadata API, and opened again
+//! after they have been retrieved, so the service only ever stores ciphertext. Each sealed value
+//! starts with a small header recording the algorithm and the ID of the key that was used. This
+//! allows a [`Keyring`] to hold several keys at once: new values are always sealed with the
+//! *active* key, while values sealed with older keys can still be opened until they have been
+//! re-sealed (see [`EncryptedMetadataRequest::rotate_value`]).
+//!
+//! The header is also used as additional authenticated data, so it can not be altered without the
+//! value failing to open.
+//!
+//! # Sealed value layout
+//!
+//! | Bytes            | Content                                   |
+//! |------------------|-------------------------------------------|
+//! | 4                | The magic bytes `SPEV`                    |
+//! | 1                | Format version (currently `1`)            |
+//! | 1                | [`EncryptionAlgorithm`] identifier        |
+//! | 1                | Length of the key ID                      |
+//! | *len*            | The UTF-8 key ID                          |
+//! | 12 or 24         | Nonce (size depends on the algorithm)     |
+//! | *rest*           | Ciphertext followed by the 16 byte tag    |
+
+use std::fmt;
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm,
+};
+use base64::{alphabet, decode_engine, encode_engine, engine::fast_portable};
+use chacha20poly1305::XChaCha20Poly1305;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    api::metadata::v1::{MetadataRequest, Value},
+    error::{Result, SeaplaneError},
+};
+
+const MAGIC: &[u8] = b"SPEV";
+const VERSION: u8 = 1;
+
+/// The size in bytes of all data keys
+pub const DATA_KEY_SIZE: usize = 32;
+
+/// The authenticated encryption algorithm used to seal a value
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::EnumString,
+    strum::Display,
+)]
+pub enum EncryptionAlgorithm {
+    /// AES-256 in Galois/Counter Mode with a 96-bit random nonce
+    #[serde(rename = "aes-256-gcm")]
+    #[strum(to_string = "aes-256-gcm")]
+    Aes256Gcm,
+    /// XChaCha20-Poly1305 with a 192-bit random nonce
+    #[serde(rename = "xchacha20-poly1305")]
+    #[strum(to_string = "xchacha20-poly1305")]
+    XChaCha20Poly1305,
+}
+
+impl EncryptionAlgorithm {
+    fn id(self) -> u8 {
+        match self {
+            Self::Aes256Gcm => 1,
+            Self::XChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Aes256Gcm),
+            2 => Some(Self::XChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    fn nonce_size(self) -> usize {
+        match self {
+            Self::Aes256Gcm => 12,
+            Self::XChaCha20Poly1305 => 24,
+        }
+    }
+}
+
+/// A 256-bit symmetric key used to seal and open values, identified by an ID which is recorded in
+/// every value it seals.
+///
+/// When (de)serialized the key material is standard base64 encoded (URL-safe base64 is also
+/// accepted), i.e. the output of `openssl rand -base64 32`.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DataKeyRepr")]
+pub struct DataKey {
+    id: String,
+    algorithm: EncryptionAlgorithm,
+    #[serde(serialize_with = "serialize_key")]
+    key: [u8; DATA_KEY_SIZE],
+}
+
+#[derive(Deserialize)]
+struct DataKeyRepr {
+    id: String,
+    algorithm: EncryptionAlgorithm,
+    #[serde(deserialize_with = "deserialize_key")]
+    key: [u8; DATA_KEY_SIZE],
+}
+
+impl TryFrom<DataKeyRepr> for DataKey {
+    type Error = SeaplaneError;
+
+    fn try_from(repr: DataKeyRepr) -> Result<Self> { Self::new(repr.id, repr.algorithm, repr.key) }
+}
+
+// Never print the key material
+impl fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataKey")
+            .field("id", &self.id)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+fn serialize_key<S: Serializer>(
+    key: &[u8; DATA_KEY_SIZE],
+    ser: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let engine = fast_portable::FastPortable::from(&alphabet::STANDARD, fast_portable::PAD);
+    ser.serialize_str(&encode_engine(key, &engine))
+}
+
+fn deserialize_key<'de, D: Deserializer<'de>>(
+    de: D,
+) -> std::result::Result<[u8; DATA_KEY_SIZE], D::Error> {
+    let s = String::deserialize(de)?;
+    let s = s.trim();
+    let standard = fast_portable::FastPortable::from(&alphabet::STANDARD, fast_portable::PAD);
+    let url_safe = fast_portable::FastPortable::from(&alphabet::URL_SAFE, fast_portable::NO_PAD);
+    let bytes = decode_engine(s, &standard)
+        .or_else(|_| decode_engine(s.trim_end_matches('='), &url_safe))
+        .map_err(|_| de::Error::custom("data key is not valid base64"))?;
+    bytes
+        .try_into()
+        .map_err(|_| de::Error::custom(format!("data key must be exactly {DATA_KEY_SIZE} bytes")))
+}
+
+impl DataKey {
+    /// Creates a data key from existing key material, which must be exactly [`DATA_KEY_SIZE`]
+    /// bytes long.
+    ///
+    /// The ID must be between 1 and 255 bytes long because it is stored in the header of every
+    /// sealed value.
+    pub fn new<S: Into<String>>(
+        id: S,
+        algorithm: EncryptionAlgorithm,
+        key: impl AsRef<[u8]>,
+    ) -> Result<Self> {
+        let id = id.into();
+        if id.is_empty() || id.len() > u8::MAX as usize {
+            return Err(SeaplaneError::InvalidDataKey(
+                "the key ID must be between 1 and 255 bytes long".into(),
+            ));
+        }
+        let key = key.as_ref().try_into().map_err(|_| {
+            SeaplaneError::InvalidDataKey(format!("the key must be exactly {DATA_KEY_SIZE} bytes"))
+        })?;
+        Ok(Self { id, algorithm, key })
+    }
+
+    /// Creates a data key with new random key material
+    pub fn generate<S: Into<String>>(id: S, algorithm: EncryptionAlgorithm) -> Result<Self> {
+        let key = match algorithm {
+            EncryptionAlgorithm::Aes256Gcm => Aes256Gcm::generate_key(&mut OsRng).to_vec(),
+            EncryptionAlgorithm::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::generate_key(&mut OsRng).to_vec()
+            }
+        };
+        Self::new(id, algorithm, key)
+    }
+
+    /// The ID of this key
+    pub fn id(&self) -> &str { &self.id }
+
+    /// The algorithm this key is used with
+    pub fn algorithm(&self) -> EncryptionAlgorithm { self.algorithm }
+
+    /// Seals `plaintext`, returning the header, nonce and ciphertext
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut sealed =
+            EnvelopeHeader { algorithm: self.algorithm, key_id: self.id.clone() }.to_bytes()?;
+        let aad = sealed.clone();
+        let payload = Payload { msg: plaintext, aad: &aad };
+        match self.algorithm {
+            EncryptionAlgorithm::Aes256Gcm => {
+                let cipher = Aes256Gcm::new(&self.key.into());
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                sealed.extend_from_slice(&nonce);
+                sealed.extend(
+                    cipher
+                        .encrypt(&nonce, payload)
+                        .map_err(|_| SeaplaneError::EncryptionFailed)?,
+                );
+            }
+            EncryptionAlgorithm::XChaCha20Poly1305 => {
+                let cipher = XChaCha20Poly1305::new(&self.key.into());
+                let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+                sealed.extend_from_slice(&nonce);
+                sealed.extend(
+                    cipher
+                        .encrypt(&nonce, payload)
+                        .map_err(|_| SeaplaneError::EncryptionFailed)?,
+                );
+            }
+        }
+        Ok(sealed)
+    }
+
+    /// Opens a value previously sealed with this key
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let (header, len) = EnvelopeHeader::parse(sealed)?;
+        if header.key_id != self.id || header.algorithm != self.algorithm {
+            return Err(SeaplaneError::UnknownDataKey(header.key_id));
+        }
+        let (aad, rest) = sealed.split_at(len);
+        if rest.len() < self.algorithm.nonce_size() {
+            return Err(SeaplaneError::DecryptionFailed);
+        }
+        let (nonce, msg) = rest.split_at(self.algorithm.nonce_size());
+        let payload = Payload { msg, aad };
+        match self.algorithm {
+            EncryptionAlgorithm::Aes256Gcm => {
+                Aes256Gcm::new(&self.key.into()).decrypt(nonce.into(), payload)
+            }
+            EncryptionAlgorithm::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(&self.key.into()).decrypt(nonce.into(), payload)
+            }
+        }
+        .map_err(|_| SeaplaneError::DecryptionFailed)
+    }
+}
+
+/// The header at the start of every sealed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    /// The algorithm the value was sealed with
+    pub algorithm: EncryptionAlgorithm,
+    /// The ID of the [`DataKey`] the value was sealed with
+    pub key_id: String,
+}
+
+impl EnvelopeHeader {
+    /// Reads the header of a sealed value, returning it along with its length in bytes
+    pub fn parse(sealed: &[u8]) -> Result<(Self, usize)> {
+        let rest = sealed
+            .strip_prefix(MAGIC)
+            .ok_or(SeaplaneError::NotEncryptedValue)?;
+        match rest {
+            [VERSION, alg, id_len, rest @ ..] if rest.len() >= *id_len as usize => {
+                let algorithm =
+                    EncryptionAlgorithm::from_id(*alg).ok_or(SeaplaneError::NotEncryptedValue)?;
+                let key_id = std::str::from_utf8(&rest[..*id_len as usize])
+                    .map_err(|_| SeaplaneError::NotEncryptedValue)?
+                    .to_owned();
+                Ok((Self { algorithm, key_id }, MAGIC.len() + 3 + *id_len as usize))
+            }
+            _ => Err(SeaplaneError::NotEncryptedValue),
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        let id_len = u8::try_from(self.key_id.len()).map_err(|_| {
+            SeaplaneError::InvalidDataKey("the key ID must be between 1 and 255 bytes long".into())
+        })?;
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[VERSION, self.algorithm.id(), id_len]);
+        bytes.extend_from_slice(self.key_id.as_bytes());
+        Ok(bytes)
+    }
+}
+
+/// Returns `true` if `bytes` looks like a sealed value, i.e. starts with a valid header
+pub fn is_sealed(bytes: &[u8]) -> bool { EnvelopeHeader::parse(bytes).is_ok() }
+
+/// A set of [`DataKey`]s, one of which is *active* and used to seal new values.
+///
+/// A keyring can be deserialized from JSON such as:
+///
+/// ```json
+/// {
+///   "active": "2023-02",
+///   "keys": [
+///     { "id": "2023-02", "algorithm": "xchacha20-poly1305", "key": "<32 bytes base64>" },
+///     { "id": "2022-08", "algorithm": "aes-256-gcm", "key": "<32 bytes base64>" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "KeyringRepr")]
+pub struct Keyring {
+    active: String,
+    keys: Vec<DataKey>,
+}
+
+#[derive(Deserialize)]
+struct KeyringRepr {
+    active: String,
+    keys: Vec<DataKey>,
+}
+
+impl TryFrom<KeyringRepr> for Keyring {
+    type Error = SeaplaneError;
+
+    fn try_from(repr: KeyringRepr) -> Result<Self> {
+        let mut keyring = Keyring { active: String::new(), keys: Vec::new() };
+        for key in repr.keys {
+            keyring.add(key)?;
+        }
+        keyring.set_active(repr.active)?;
+        Ok(keyring)
+    }
+}
+
+impl Keyring {
+    /// Creates a keyring with a single key, which is the active key
+    pub fn new(active: DataKey) -> Self { Self { active: active.id.clone(), keys: vec![active] } }
+
+    /// Adds a key which can be used to open values. The active key does not change.
+    pub fn add(&mut self, key: DataKey) -> Result<()> {
+        if self.get(&key.id).is_some() {
+            return Err(SeaplaneError::InvalidDataKey(format!(
+                "a key with the ID '{}' already exists",
+                key.id
+            )));
+        }
+        self.keys.push(key);
+        Ok(())
+    }
+
+    /// Sets the key used to seal new values
+    pub fn set_active<S: Into<String>>(&mut self, id: S) -> Result<()> {
+        let id = id.into();
+        if self.get(&id).is_none() {
+            return Err(SeaplaneError::UnknownDataKey(id));
+        }
+        self.active = id;
+        Ok(())
+    }
+
+    /// The key used to seal new values
+    pub fn active(&self) -> &DataKey {
+        self.get(&self.active)
+            .expect("the active key is always part of the keyring")
+    }
+
+    /// Returns the key with the given ID
+    pub fn get(&self, id: &str) -> Option<&DataKey> { self.keys.iter().find(|k| k.id == id) }
+
+    /// Seals `plaintext` with the active key
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> { self.active().seal(plaintext) }
+
+    /// Opens a sealed value with whichever key it was sealed with
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let (header, _) = EnvelopeHeader::parse(sealed)?;
+        self.get(&header.key_id)
+            .ok_or(SeaplaneError::UnknownDataKey(header.key_id))?
+            .open(sealed)
+    }
+}
+
+/// Wraps a [`MetadataRequest`] so that values are sealed before they are stored, and opened after
+/// they are retrieved.
+///
+/// # Examples
+///
+/// ```no_run
+/// use seaplane::api::metadata::v1::{
+///     DataKey, EncryptedMetadataRequest, EncryptionAlgorithm, Keyring, MetadataRequestBuilder,
+/// };
+///
+/// let key = DataKey::generate("2023-02", EncryptionAlgorithm::XChaCha20Poly1305).unwrap();
+/// let req = MetadataRequestBuilder::new()
+///     .token("abc123_token")
+///     .encoded_key("bW9ieQo")
+///     .build()
+///     .unwrap();
+/// let req = EncryptedMetadataRequest::new(req, Keyring::new(key));
+///
+/// req.put_value("s3cr3t").unwrap();
+/// assert_eq!(req.get_value().unwrap(), b"s3cr3t");
+/// ```
+#[derive(Debug)]
+pub struct EncryptedMetadataRequest {
+    request: MetadataRequest,
+    keyring: Keyring,
+}
+
+impl EncryptedMetadataRequest {
+    /// Wraps `request`, using `keyring` to seal and open values
+    pub fn new(request: MetadataRequest, keyring: Keyring) -> Self { Self { request, keyring } }
+
+    /// The wrapped request
+    pub fn inner(&self) -> &MetadataRequest { &self.request }
+
+    /// Returns the wrapped request
+    pub fn into_inner(self) -> MetadataRequest { self.request }
+
+    /// The keyring used to seal and open values
+    pub fn keyring(&self) -> &Keyring { &self.keyring }
+
+    /// Returns the decrypted value of the target key.
+    ///
+    /// If the value was not sealed [`SeaplaneError::NotEncryptedValue`] is returned, and if it was
+    /// sealed with a key that is not part of the keyring [`SeaplaneError::UnknownDataKey`].
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn get_value(&self) -> Result<Vec<u8>> {
+        self.keyring.open(&self.request.get_value()?.decode())
+    }
+
+    /// Seals `plaintext` with the active key and stores it at the target key.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn put_value(&self, plaintext: impl AsRef<[u8]>) -> Result<()> {
+        self.request
+            .put_value(Value::from_unencoded(self.keyring.seal(plaintext.as_ref())?))
+    }
+
+    /// Deletes the value at the target key.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn delete_value(&self) -> Result<()> { self.request.delete_value() }
+
+    /// Re-seals the value at the target key with the active key if it was sealed with a different
+    /// one, returning `true` if the value was changed.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Key`.
+    pub fn rotate_value(&self) -> Result<bool> {
+        let sealed = self.request.get_value()?.decode();
+        let (header, _) = EnvelopeHeader::parse(&sealed)?;
+        if header.key_id == self.keyring.active().id {
+            return Ok(false);
+        }
+        self.put_value(self.keyring.open(&sealed)?)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod encryption_test {
+    use super::*;
+
+    fn keyring() -> Keyring {
+        let mut keyring = Keyring::new(
+            DataKey::new("new", EncryptionAlgorithm::XChaCha20Poly1305, [1; 32]).unwrap(),
+        );
+        keyring
+            .add(DataKey::new("old", EncryptionAlgorithm::Aes256Gcm, [2; 32]).unwrap())
+            .unwrap();
+        keyring
+    }
+
+    #[test]
+    fn seal_open_roundtrip() {
+        let mut keyring = keyring();
+        for id in ["new", "old"] {
+            keyring.set_active(id).unwrap();
+            let sealed = keyring.seal(b"hello").unwrap();
+            assert!(is_sealed(&sealed));
+            assert_eq!(EnvelopeHeader::parse(&sealed).unwrap().0.key_id, id);
+            assert_ne!(&sealed[sealed.len() - 5..], b"hello");
+            assert_eq!(keyring.open(&sealed).unwrap(), b"hello");
+        }
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let keyring = keyring();
+        let sealed = keyring.seal(b"hello").unwrap();
+
+        // Flipping a bit in the ciphertext
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(keyring.open(&tampered), Err(SeaplaneError::DecryptionFailed));
+
+        // Pointing the header at a different key
+        let mut tampered = sealed;
+        let id_start = MAGIC.len() + 3;
+        tampered[id_start..id_start + 3].copy_from_slice(b"old");
+        tampered[MAGIC.len() + 1] = EncryptionAlgorithm::Aes256Gcm.id();
+        assert_eq!(keyring.open(&tampered), Err(SeaplaneError::DecryptionFailed));
+
+        // Unknown keys and plain values
+        let other = DataKey::new("other", EncryptionAlgorithm::Aes256Gcm, [3; 32]).unwrap();
+        assert_eq!(
+            keyring.open(&other.seal(b"hello").unwrap()),
+            Err(SeaplaneError::UnknownDataKey(String::new()))
+        );
+        assert!(!is_sealed(b"hello"));
+        assert_eq!(keyring.open(b"hello"), Err(SeaplaneError::NotEncryptedValue));
+    }
+
+    #[test]
+    fn keyring_from_json() {
+        let keyring: Keyring = serde_json::from_str(
+            r#"{
+                "active": "new",
+                "keys": [
+                    {"id": "new", "algorithm": "xchacha20-poly1305", "key": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="},
+                    {"id": "old", "algorithm": "aes-256-gcm", "key": "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(keyring, self::keyring());
+
+        // The active key must exist
+        assert!(serde_json::from_str::<Keyring>(r#"{"active": "nope", "keys": []}"#).is_err());
+        // Keys must be 32 bytes
+        assert!(serde_json::from_str::<Keyring>(
+            r#"{"active": "a", "keys": [{"id": "a", "algorithm": "aes-256-gcm", "key": "AQI="}]}"#
+        )
+        .is_err());
+        // Key IDs must be between 1 and 255 bytes
+        let keyring_with_id = |id: &str| {
+            serde_json::from_str::<Keyring>(&format!(
+                r#"{{"active": "{id}", "keys": [{{"id": "{id}", "algorithm": "aes-256-gcm", "key": "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI"}}]}}"#
+            ))
+        };
+        assert!(keyring_with_id(&"a".repeat(255)).is_ok());
+        assert!(keyring_with_id(&"a".repeat(256)).is_err());
+        assert!(keyring_with_id("").is_err());
+    }
+}
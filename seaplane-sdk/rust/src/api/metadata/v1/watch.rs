@@ -0,0 +1,220 @@
+//! Watching a key or directory for changes by polling it
+//!
+//! The Metadata API has no change notifications, so a [`Watcher`] takes repeated snapshots of its
+//! target and compares the SHA-256 digest of each value with the previous snapshot to produce
+//! [`WatchEvent`]s.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::{
+        metadata::v1::{Key, KeyValue, MetadataRequestBuilder, Value},
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// What a [`Watcher`] is watching
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchTarget {
+    /// A single key
+    Key(Key),
+    /// All keys under a directory
+    Directory(Directory),
+}
+
+/// A change to a watched key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum WatchEvent {
+    /// The key did not exist in the previous snapshot
+    Added { key: Key, value: Value },
+    /// The value of the key is different from the previous snapshot
+    Modified { key: Key, value: Value },
+    /// The key no longer exists
+    Deleted { key: Key },
+}
+
+impl WatchEvent {
+    /// The key this event is about
+    pub fn key(&self) -> &Key {
+        match self {
+            WatchEvent::Added { key, .. }
+            | WatchEvent::Modified { key, .. }
+            | WatchEvent::Deleted { key } => key,
+        }
+    }
+
+    /// The new value of the key, if it still exists
+    pub fn value(&self) -> Option<&Value> {
+        match self {
+            WatchEvent::Added { value, .. } | WatchEvent::Modified { value, .. } => Some(value),
+            WatchEvent::Deleted { .. } => None,
+        }
+    }
+}
+
+/// Produces [`WatchEvent`]s by comparing snapshots of a key or directory.
+///
+/// The first snapshot only records the current state (unless [`Watcher::emit_initial`] is used),
+/// every following snapshot produces events for whatever changed since the one before it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use seaplane::api::{
+///     metadata::v1::{MetadataRequestBuilder, WatchTarget, Watcher},
+///     shared::v1::Directory,
+/// };
+///
+/// let mut watcher = Watcher::new(WatchTarget::Directory(Directory::from_unencoded("my-app")));
+/// loop {
+///     let builder = MetadataRequestBuilder::new().token("abc123_token");
+///     for event in watcher.poll(builder).unwrap() {
+///         dbg!(event);
+///     }
+///     thread::sleep(Duration::from_secs(5));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    target: WatchTarget,
+    // The encoded key and the SHA-256 digest of its value from the previous snapshot
+    hashes: BTreeMap<String, [u8; 32]>,
+    initialized: bool,
+    emit_initial: bool,
+}
+
+impl Watcher {
+    /// Creates a watcher for `target`
+    pub fn new(target: WatchTarget) -> Self {
+        Self { target, hashes: BTreeMap::new(), initialized: false, emit_initial: false }
+    }
+
+    /// Emit an [`WatchEvent::Added`] event for every key in the first snapshot (default: `false`)
+    #[must_use]
+    pub fn emit_initial(mut self, yes: bool) -> Self {
+        self.emit_initial = yes;
+        self
+    }
+
+    /// What this watcher is watching
+    pub fn target(&self) -> &WatchTarget { &self.target }
+
+    /// Takes a snapshot of the target and returns what changed since the last one.
+    ///
+    /// `builder` supplies the access token and any other request options, the target of the
+    /// request is set by the watcher.
+    pub fn poll(&mut self, builder: MetadataRequestBuilder) -> Result<Vec<WatchEvent>> {
+        let snapshot = match &self.target {
+            WatchTarget::Key(key) => {
+                match builder.encoded_key(key.encoded()).build()?.get_value() {
+                    Ok(value) => vec![KeyValue { key: key.clone(), value }],
+                    Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::NotFound => {
+                        Vec::new()
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            WatchTarget::Directory(dir) => {
+                let mut context = RangeQueryContext::new();
+                context.set_directory(dir.clone());
+                builder.range(context).build()?.get_all_pages()?
+            }
+        };
+        Ok(self.apply(snapshot))
+    }
+
+    /// Records a snapshot of the target taken elsewhere (i.e. all key-value pairs that currently
+    /// exist) and returns what changed since the last one.
+    pub fn apply(&mut self, snapshot: Vec<KeyValue>) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+        let mut hashes = BTreeMap::new();
+        for KeyValue { key, value } in snapshot {
+            let hash: [u8; 32] = Sha256::digest(value.encoded().as_bytes()).into();
+            let encoded = key.encoded().to_owned();
+            match self.hashes.remove(&encoded) {
+                None if self.initialized || self.emit_initial => {
+                    events.push(WatchEvent::Added { key, value })
+                }
+                Some(old) if old != hash => events.push(WatchEvent::Modified { key, value }),
+                _ => (),
+            }
+            hashes.insert(encoded, hash);
+        }
+        // Anything left over no longer exists
+        for key in std::mem::replace(&mut self.hashes, hashes).into_keys() {
+            events.push(WatchEvent::Deleted { key: Key::from_encoded(key) });
+        }
+        self.initialized = true;
+        events
+    }
+}
+
+#[cfg(test)]
+mod watch_test {
+    use super::*;
+
+    fn kv(key: &str, value: &str) -> KeyValue {
+        KeyValue { key: Key::from_unencoded(key), value: Value::from_unencoded(value) }
+    }
+
+    #[test]
+    fn apply_snapshots() {
+        let mut watcher = Watcher::new(WatchTarget::Directory(Directory::from_unencoded("dir")));
+        assert_eq!(watcher.apply(vec![kv("dir/a", "1"), kv("dir/b", "2")]), vec![]);
+        assert_eq!(
+            watcher.apply(vec![kv("dir/a", "1"), kv("dir/b", "3"), kv("dir/c", "4")]),
+            vec![
+                WatchEvent::Modified {
+                    key: Key::from_unencoded("dir/b"),
+                    value: Value::from_unencoded("3")
+                },
+                WatchEvent::Added {
+                    key: Key::from_unencoded("dir/c"),
+                    value: Value::from_unencoded("4")
+                },
+            ]
+        );
+        assert_eq!(
+            watcher.apply(vec![kv("dir/c", "4")]),
+            vec![
+                WatchEvent::Deleted { key: Key::from_unencoded("dir/a") },
+                WatchEvent::Deleted { key: Key::from_unencoded("dir/b") },
+            ]
+        );
+        assert_eq!(watcher.apply(vec![kv("dir/c", "4")]), vec![]);
+    }
+
+    #[test]
+    fn apply_emit_initial() {
+        let mut watcher =
+            Watcher::new(WatchTarget::Key(Key::from_unencoded("a"))).emit_initial(true);
+        assert_eq!(
+            watcher.apply(vec![kv("a", "1")]),
+            vec![WatchEvent::Added {
+                key: Key::from_unencoded("a"),
+                value: Value::from_unencoded("1")
+            }]
+        );
+        assert_eq!(watcher.apply(vec![kv("a", "1")]), vec![]);
+    }
+
+    #[test]
+    fn event_json() {
+        let event =
+            WatchEvent::Added { key: Key::from_unencoded("a"), value: Value::from_unencoded("1") };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"added","key":"YQ","value":"MQ"}"#
+        );
+        let event = WatchEvent::Deleted { key: Key::from_unencoded("a") };
+        assert_eq!(serde_json::to_string(&event).unwrap(), r#"{"event":"deleted","key":"YQ"}"#);
+    }
+}
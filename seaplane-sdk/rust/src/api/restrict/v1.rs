@@ -1,6 +1,10 @@
 //! The `/restrict` endpoint APIs which allows working with [`Restriction`]s
 pub mod models;
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
 
 pub use models::*;
 use reqwest::{
@@ -467,4 +471,63 @@ impl RestrictRequest {
             .map(|_| ()) // TODO: for now we drop the "success" message to control it ourselves
             .map_err(Into::into)
     }
+
+    /// Polls the restriction every `poll_interval` until its state is
+    /// [`RestrictionState::Enforced`], returning the enforced restriction. Returns
+    /// [`SeaplaneError::Timeout`] if it is still pending after `timeout`.
+    ///
+    /// **NOTE:** This endpoint requires the `RequestTarget` be a `Single`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use seaplane::api::restrict::v1::{RestrictRequest, RestrictRequestBuilder};
+    ///
+    /// let req = RestrictRequestBuilder::new()
+    ///     .token("abc123_token")
+    ///     .single_restriction("config", "bW9ieQo")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let resp = req
+    ///     .wait_until_enforced(Duration::from_secs(60), Duration::from_secs(2))
+    ///     .unwrap();
+    /// dbg!(resp);
+    /// ```
+    pub fn wait_until_enforced(
+        &self,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<Restriction> {
+        self.wait_until_enforced_with(timeout, poll_interval, |_| ())
+    }
+
+    /// The same as [`RestrictRequest::wait_until_enforced`], but calls `on_poll` with the
+    /// restriction every time it is retrieved, i.e. to report progress.
+    pub fn wait_until_enforced_with<F>(
+        &self,
+        timeout: Duration,
+        poll_interval: Duration,
+        mut on_poll: F,
+    ) -> Result<Restriction>
+    where
+        F: FnMut(&Restriction),
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let restriction = self.get_restriction()?;
+            on_poll(&restriction);
+            if restriction.state == RestrictionState::Enforced {
+                return Ok(restriction);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(SeaplaneError::Timeout(timeout));
+            }
+            thread::sleep(poll_interval.min(deadline - now));
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use std::{collections::BTreeSet, fmt};
 
 use serde::{Deserialize, Serialize};
-use strum::{AsRefStr, EnumString, EnumVariantNames};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
 
 use crate::{
     api::shared::v1::{Provider, RangeQueryContext, Region},
@@ -40,26 +40,18 @@ pub struct Restriction {
     pub state: RestrictionState,
 }
 
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    PartialEq,
-    Eq,
-    Serialize,
-    AsRefStr,
-    strum::Display,
-    EnumString,
-    EnumVariantNames,
-)]
+#[derive(Clone, Debug, PartialEq, Eq, EnumString, EnumVariantNames, IntoStaticStr)]
 #[strum(ascii_case_insensitive)]
 #[non_exhaustive]
 pub enum Api {
     Config,
     Locks,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
-impl_deser_from_str!(Api);
+impl_str_enum_with_unknown!(Api);
 
 #[cfg(test)]
 mod test_api {
@@ -74,6 +66,20 @@ mod test_api {
         let api: Api = serde_json::from_str("\"CONFIG\"").unwrap();
         assert_eq!(api, Api::Config);
     }
+
+    #[test]
+    fn api_unknown_round_trips() {
+        let api: Api = serde_json::from_str("\"Queues\"").unwrap();
+        assert_eq!(api, Api::Unknown("Queues".into()));
+        assert_eq!(api.as_ref(), "Queues");
+        assert_eq!(serde_json::to_string(&api).unwrap(), "\"Queues\"");
+
+        let state: RestrictionState = serde_json::from_str("\"Revoking\"").unwrap();
+        assert_eq!(state, RestrictionState::Unknown("Revoking".into()));
+        assert_eq!(serde_json::to_string(&state).unwrap(), "\"Revoking\"");
+        assert!("Queues".parse::<Api>().is_err());
+        assert!("Revoking".parse::<RestrictionState>().is_err());
+    }
 }
 /// A key pointing to a directory, encoded in url-safe base64.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -91,16 +97,18 @@ impl fmt::Display for RestrictedDirectory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.inner) }
 }
 
-#[derive(
-    Clone, Copy, Debug, PartialEq, Eq, Serialize, strum::Display, EnumString, EnumVariantNames,
-)]
+#[derive(Clone, Debug, PartialEq, Eq, EnumString, EnumVariantNames, IntoStaticStr)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum RestrictionState {
     Pending,
     Enforced,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
-impl_deser_from_str!(RestrictionState);
+impl_str_enum_with_unknown!(RestrictionState);
 
 /// A builder for creating a [`RestrictionDetails`] struct
 #[derive(Debug, Default)]
@@ -171,7 +179,26 @@ impl RestrictionDetailsBuilder {
     }
 
     /// Performs validation checks, and builds the instance of [`RestrictionDetails`]
+    ///
+    /// Unknown providers and regions (i.e. [`Region::Unknown`]) are rejected, as they can only
+    /// come from values this version of the SDK doesn't know how to check.
     pub fn build(self) -> Result<RestrictionDetails, SeaplaneError> {
+        if let Some(unknown) = self
+            .providers_allowed
+            .iter()
+            .chain(&self.providers_denied)
+            .find(|p| p.is_unknown())
+        {
+            return Err(SeaplaneError::UnknownValue(unknown.to_string()));
+        }
+        if let Some(unknown) = self
+            .regions_allowed
+            .iter()
+            .chain(&self.regions_denied)
+            .find(|r| r.is_unknown())
+        {
+            return Err(SeaplaneError::UnknownValue(unknown.to_string()));
+        }
         if (self
             .providers_allowed
             .intersection(&self.providers_denied)
@@ -209,6 +236,112 @@ pub struct RestrictionDetails {
 impl RestrictionDetails {
     /// Create a [`RestrictionDetailsBuilder`] to build a new `RestrcitionDetails`
     pub fn builder() -> RestrictionDetailsBuilder { RestrictionDetailsBuilder::default() }
+
+    /// Returns `true` if data may be placed with `provider` in `region`.
+    ///
+    /// Providers and regions are evaluated separately and both must be permitted (see
+    /// [`RestrictionDetails::permits_provider`] and [`RestrictionDetails::permits_region`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use seaplane::api::{restrict::v1::RestrictionDetails, shared::v1::{Provider, Region}};
+    /// let details = RestrictionDetails::builder()
+    ///     .add_allowed_region(Region::XE)
+    ///     .add_denied_provider(Provider::GCP)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(details.permits(&Provider::AWS, &Region::XE));
+    /// assert!(!details.permits(&Provider::GCP, &Region::XE));
+    /// assert!(!details.permits(&Provider::AWS, &Region::XN));
+    /// ```
+    pub fn permits(&self, provider: &Provider, region: &Region) -> bool {
+        self.permits_provider(provider) && self.permits_region(region)
+    }
+
+    /// Returns `true` if data may be placed with `provider`.
+    ///
+    /// A denied provider is never permitted. Otherwise, when no providers are explicitly allowed
+    /// every provider is permitted, and when some are only those are.
+    pub fn permits_provider(&self, provider: &Provider) -> bool {
+        !self.providers_denied.contains(provider)
+            && (self.providers_allowed.is_empty() || self.providers_allowed.contains(provider))
+    }
+
+    /// Returns `true` if data may be placed in `region`.
+    ///
+    /// A denied region is never permitted. Otherwise, when no regions are explicitly allowed every
+    /// region is permitted, and when some are only those are.
+    pub fn permits_region(&self, region: &Region) -> bool {
+        !self.regions_denied.contains(region)
+            && (self.regions_allowed.is_empty() || self.regions_allowed.contains(region))
+    }
+}
+
+#[cfg(test)]
+mod test_restriction_details {
+    use super::*;
+
+    #[test]
+    fn permits_everything_by_default() {
+        let details = RestrictionDetails::builder().build().unwrap();
+        assert!(details.permits(&Provider::AWS, &Region::XA));
+        assert!(details.permits(&Provider::Equinix, &Region::XQ));
+    }
+
+    #[test]
+    fn allowed_limits_placement() {
+        let details = RestrictionDetails::builder()
+            .add_allowed_provider(Provider::AWS)
+            .add_allowed_provider(Provider::Azure)
+            .add_allowed_region(Region::XE)
+            .build()
+            .unwrap();
+        assert!(details.permits(&Provider::Azure, &Region::XE));
+        assert!(!details.permits(&Provider::GCP, &Region::XE));
+        assert!(!details.permits(&Provider::AWS, &Region::XN));
+    }
+
+    #[test]
+    fn denied_limits_placement() {
+        let details = RestrictionDetails::builder()
+            .add_denied_provider(Provider::GCP)
+            .add_denied_region(Region::XC)
+            .build()
+            .unwrap();
+        assert!(details.permits(&Provider::AWS, &Region::XE));
+        assert!(!details.permits(&Provider::GCP, &Region::XE));
+        assert!(!details.permits(&Provider::AWS, &Region::XC));
+    }
+
+    #[test]
+    fn denied_wins_over_allowed() {
+        // The builder rejects this, but the API could still return it
+        let details = RestrictionDetails {
+            regions_allowed: [Region::XE].into(),
+            regions_denied: [Region::XE].into(),
+            providers_allowed: BTreeSet::new(),
+            providers_denied: BTreeSet::new(),
+        };
+        assert!(!details.permits_region(&Region::XE));
+    }
+
+    #[test]
+    fn builder_rejects_unknown_values() {
+        assert_eq!(
+            RestrictionDetails::builder()
+                .add_allowed_region(Region::Unknown("XZ".into()))
+                .build(),
+            Err(SeaplaneError::UnknownValue(String::new()))
+        );
+        assert_eq!(
+            RestrictionDetails::builder()
+                .add_denied_provider(Provider::Unknown("Linode".into()))
+                .build(),
+            Err(SeaplaneError::UnknownValue(String::new()))
+        );
+    }
 }
 
 /// The response given from a range query
@@ -2,3 +2,4 @@ mod models;
 pub use models::*;
 mod range_query;
 pub use range_query::*;
+mod regions;
@@ -1,23 +1,11 @@
 //! This file contains models that are used across multiple APIs, like `Region`
 //! and `Provider`
 
-use serde::Serialize;
-use strum::{EnumString, EnumVariantNames};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
 
 /// A backing cloud provider used to restrict data placement
 #[derive(
-    Clone,
-    Copy,
-    Debug,
-    Hash,
-    Serialize,
-    Eq,
-    PartialEq,
-    PartialOrd,
-    Ord,
-    strum::Display,
-    EnumString,
-    EnumVariantNames,
+    Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, EnumString, EnumVariantNames, IntoStaticStr,
 )]
 #[allow(clippy::upper_case_acronyms)]
 #[strum(ascii_case_insensitive)]
@@ -28,9 +16,12 @@ pub enum Provider {
     DigitalOcean,
     Equinix,
     GCP,
+    /// A value this version of the SDK doesn't know about
+    #[strum(disabled)]
+    Unknown(String),
 }
 
-impl_deser_from_str!(Provider);
+impl_str_enum_with_unknown!(Provider);
 
 #[cfg(test)]
 mod test_provider {
@@ -45,14 +36,26 @@ mod test_provider {
         let provider: Provider = serde_json::from_str("\"AWS\"").unwrap();
         assert_eq!(provider, Provider::AWS);
     }
+
+    #[test]
+    fn provider_unknown_round_trips() {
+        let provider: Provider = serde_json::from_str("\"Linode\"").unwrap();
+        assert_eq!(provider, Provider::Unknown("Linode".into()));
+        assert_eq!(provider.to_string(), "Linode");
+        assert_eq!(serde_json::to_string(&provider).unwrap(), "\"Linode\"");
+        assert_eq!(serde_json::to_string(&Provider::DigitalOcean).unwrap(), "\"DigitalOcean\"");
+        // Only deserializing keeps unknown values, parsing what a user wrote fails
+        assert!("Linode".parse::<Provider>().is_err());
+    }
 }
 
 /// A regulatory region used to restrict data placement
-#[derive(
-    strum::Display, EnumString, Debug, Serialize, Hash, Eq, PartialEq, PartialOrd, Ord, Copy, Clone,
-)]
+///
+/// Besides the codes, regions can be parsed from their aliases and names (see
+/// [`Region::from_alias`]), and looked up from the countries they contain (see
+/// [`Region::from_country`]).
+#[derive(IntoStaticStr, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone)]
 #[allow(clippy::upper_case_acronyms)]
-#[strum(ascii_case_insensitive)]
 #[non_exhaustive]
 pub enum Region {
     /// Asia
@@ -73,9 +76,11 @@ pub enum Region {
     XS,
     /// The UK
     XU,
+    /// A value this version of the SDK doesn't know about
+    Unknown(String),
 }
 
-impl_deser_from_str!(Region);
+impl_str_enum_with_unknown!(Region);
 
 #[cfg(test)]
 mod test_region {
@@ -90,4 +95,48 @@ mod test_region {
         let region: Region = serde_json::from_str("\"XN\"").unwrap();
         assert_eq!(region, Region::XN);
     }
+
+    #[test]
+    fn region_aliases() {
+        assert_eq!("EU".parse::<Region>().unwrap(), Region::XE);
+        assert_eq!("north-america".parse::<Region>().unwrap(), Region::XN);
+        assert_eq!("NAmerica".parse::<Region>().unwrap(), Region::XN);
+        assert_eq!("United Kingdom".parse::<Region>().unwrap(), Region::XU);
+        assert_eq!("peoples_republic_of_china".parse::<Region>().unwrap(), Region::XC);
+        // Countries are not aliases
+        assert_eq!(Region::from_alias("DE"), None);
+        // Aliases are never sent by the server, so only codes are serialized
+        assert_eq!(
+            serde_json::to_string(&Region::from_alias("europe").unwrap()).unwrap(),
+            "\"XE\""
+        );
+    }
+
+    #[test]
+    fn region_countries() {
+        assert_eq!(Region::from_country("de"), Some(Region::XE));
+        assert_eq!(Region::from_country("US"), Some(Region::XN));
+        assert_eq!(Region::from_country("GB"), Some(Region::XU));
+        assert_eq!(Region::from_country("CN"), Some(Region::XC));
+        assert_eq!(Region::from_country("ZZ"), None);
+
+        assert!(Region::XE.countries().any(|c| c == "FR"));
+        assert!(!Region::XE.countries().any(|c| c == "GB"));
+        assert_eq!(Region::XC.countries().collect::<Vec<_>>(), vec!["CN"]);
+        assert_eq!(Region::Unknown("XZ".into()).countries().count(), 0);
+
+        // Every country is in exactly one known region
+        let total: usize = Region::KNOWN.iter().map(|r| r.countries().count()).sum();
+        assert_eq!(total, 249);
+    }
+
+    #[test]
+    fn region_unknown_round_trips() {
+        let region: Region = serde_json::from_str("\"XZ\"").unwrap();
+        assert_eq!(region, Region::Unknown("XZ".into()));
+        assert!(region.is_unknown());
+        assert_eq!(region.to_string(), "XZ");
+        assert_eq!(serde_json::to_string(&region).unwrap(), "\"XZ\"");
+        assert!("XZ".parse::<Region>().is_err());
+    }
 }
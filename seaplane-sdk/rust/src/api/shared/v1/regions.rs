@@ -0,0 +1,174 @@
+//! Lookup tables for [`Region`]s: their human readable names, the aliases they can be parsed
+//! from, and the countries they contain.
+
+use std::str::FromStr;
+
+use crate::api::shared::v1::Region;
+
+/// Every ISO 3166-1 alpha-2 country code and the region it belongs to, sorted by country code.
+///
+/// Countries are mapped by the continent they're on, except for the People's Republic of China
+/// (`XC`) and the United Kingdom along with the Crown Dependencies (`XU`) which are regions of
+/// their own. Hong Kong and Macao are mapped to Asia (`XA`).
+#[rustfmt::skip]
+static COUNTRIES: &[(&str, Region)] = {
+    use Region::*;
+    &[
+        ("AD", XE), ("AE", XA), ("AF", XA), ("AG", XN), ("AI", XN), ("AL", XE),
+        ("AM", XA), ("AO", XF), ("AQ", XQ), ("AR", XS), ("AS", XO), ("AT", XE),
+        ("AU", XO), ("AW", XN), ("AX", XE), ("AZ", XA), ("BA", XE), ("BB", XN),
+        ("BD", XA), ("BE", XE), ("BF", XF), ("BG", XE), ("BH", XA), ("BI", XF),
+        ("BJ", XF), ("BL", XN), ("BM", XN), ("BN", XA), ("BO", XS), ("BQ", XN),
+        ("BR", XS), ("BS", XN), ("BT", XA), ("BV", XQ), ("BW", XF), ("BY", XE),
+        ("BZ", XN), ("CA", XN), ("CC", XA), ("CD", XF), ("CF", XF), ("CG", XF),
+        ("CH", XE), ("CI", XF), ("CK", XO), ("CL", XS), ("CM", XF), ("CN", XC),
+        ("CO", XS), ("CR", XN), ("CU", XN), ("CV", XF), ("CW", XN), ("CX", XA),
+        ("CY", XE), ("CZ", XE), ("DE", XE), ("DJ", XF), ("DK", XE), ("DM", XN),
+        ("DO", XN), ("DZ", XF), ("EC", XS), ("EE", XE), ("EG", XF), ("EH", XF),
+        ("ER", XF), ("ES", XE), ("ET", XF), ("FI", XE), ("FJ", XO), ("FK", XS),
+        ("FM", XO), ("FO", XE), ("FR", XE), ("GA", XF), ("GB", XU), ("GD", XN),
+        ("GE", XA), ("GF", XS), ("GG", XU), ("GH", XF), ("GI", XE), ("GL", XN),
+        ("GM", XF), ("GN", XF), ("GP", XN), ("GQ", XF), ("GR", XE), ("GS", XQ),
+        ("GT", XN), ("GU", XO), ("GW", XF), ("GY", XS), ("HK", XA), ("HM", XQ),
+        ("HN", XN), ("HR", XE), ("HT", XN), ("HU", XE), ("ID", XA), ("IE", XE),
+        ("IL", XA), ("IM", XU), ("IN", XA), ("IO", XA), ("IQ", XA), ("IR", XA),
+        ("IS", XE), ("IT", XE), ("JE", XU), ("JM", XN), ("JO", XA), ("JP", XA),
+        ("KE", XF), ("KG", XA), ("KH", XA), ("KI", XO), ("KM", XF), ("KN", XN),
+        ("KP", XA), ("KR", XA), ("KW", XA), ("KY", XN), ("KZ", XA), ("LA", XA),
+        ("LB", XA), ("LC", XN), ("LI", XE), ("LK", XA), ("LR", XF), ("LS", XF),
+        ("LT", XE), ("LU", XE), ("LV", XE), ("LY", XF), ("MA", XF), ("MC", XE),
+        ("MD", XE), ("ME", XE), ("MF", XN), ("MG", XF), ("MH", XO), ("MK", XE),
+        ("ML", XF), ("MM", XA), ("MN", XA), ("MO", XA), ("MP", XO), ("MQ", XN),
+        ("MR", XF), ("MS", XN), ("MT", XE), ("MU", XF), ("MV", XA), ("MW", XF),
+        ("MX", XN), ("MY", XA), ("MZ", XF), ("NA", XF), ("NC", XO), ("NE", XF),
+        ("NF", XO), ("NG", XF), ("NI", XN), ("NL", XE), ("NO", XE), ("NP", XA),
+        ("NR", XO), ("NU", XO), ("NZ", XO), ("OM", XA), ("PA", XN), ("PE", XS),
+        ("PF", XO), ("PG", XO), ("PH", XA), ("PK", XA), ("PL", XE), ("PM", XN),
+        ("PN", XO), ("PR", XN), ("PS", XA), ("PT", XE), ("PW", XO), ("PY", XS),
+        ("QA", XA), ("RE", XF), ("RO", XE), ("RS", XE), ("RU", XE), ("RW", XF),
+        ("SA", XA), ("SB", XO), ("SC", XF), ("SD", XF), ("SE", XE), ("SG", XA),
+        ("SH", XF), ("SI", XE), ("SJ", XE), ("SK", XE), ("SL", XF), ("SM", XE),
+        ("SN", XF), ("SO", XF), ("SR", XS), ("SS", XF), ("ST", XF), ("SV", XN),
+        ("SX", XN), ("SY", XA), ("SZ", XF), ("TC", XN), ("TD", XF), ("TF", XQ),
+        ("TG", XF), ("TH", XA), ("TJ", XA), ("TK", XO), ("TL", XA), ("TM", XA),
+        ("TN", XF), ("TO", XO), ("TR", XA), ("TT", XN), ("TV", XO), ("TW", XA),
+        ("TZ", XF), ("UA", XE), ("UG", XF), ("UM", XO), ("US", XN), ("UY", XS),
+        ("UZ", XA), ("VA", XE), ("VC", XN), ("VE", XS), ("VG", XN), ("VI", XN),
+        ("VN", XA), ("VU", XO), ("WF", XO), ("WS", XO), ("YE", XA), ("YT", XF),
+        ("ZA", XF), ("ZM", XF), ("ZW", XF),
+    ]
+};
+
+impl Region {
+    /// All the regions this version of the SDK knows about
+    pub const KNOWN: &'static [Region] = &[
+        Region::XA,
+        Region::XC,
+        Region::XE,
+        Region::XF,
+        Region::XN,
+        Region::XO,
+        Region::XQ,
+        Region::XS,
+        Region::XU,
+    ];
+
+    /// The human readable name of the region, or the code itself for unknown regions
+    pub fn name(&self) -> &str {
+        use Region::*;
+        match self {
+            XA => "Asia",
+            XC => "People's Republic of China",
+            XE => "Europe",
+            XF => "Africa",
+            XN => "North America",
+            XO => "Oceania",
+            XQ => "Antarctica",
+            XS => "South America",
+            XU => "United Kingdom",
+            Unknown(code) => code,
+        }
+    }
+
+    /// The aliases the region can be parsed from besides its code and name
+    pub fn aliases(&self) -> &'static [&'static str] {
+        use Region::*;
+        match self {
+            XA => &[],
+            XC => &["PRC", "China"],
+            XE => &["EU"],
+            XF => &[],
+            XN => &["NAmerica"],
+            XO => &[],
+            XQ => &[],
+            XS => &["SAmerica"],
+            XU => &["UK"],
+            Unknown(_) => &[],
+        }
+    }
+
+    /// Parses a region from its code, name or one of its aliases, ignoring case along with any
+    /// spaces, dashes, underscores and apostrophes, i.e. `xe`, `EU`, `north-america` and
+    /// `Peoples_Republic_Of_China` are all valid.
+    ///
+    /// Returns `None` if the value isn't a known region.
+    pub fn from_alias(s: &str) -> Option<Region> {
+        fn normalize(s: &str) -> String {
+            s.chars()
+                .filter(|c| !matches!(c, ' ' | '-' | '_' | '\''))
+                .map(|c| c.to_ascii_lowercase())
+                .collect()
+        }
+
+        let s = normalize(s);
+        Self::KNOWN
+            .iter()
+            .find(|r| {
+                normalize(r.as_ref()) == s
+                    || normalize(r.name()) == s
+                    || r.aliases().iter().any(|a| normalize(a) == s)
+            })
+            .cloned()
+    }
+
+    /// Looks up the region an ISO 3166-1 alpha-2 country code (i.e. `DE`) belongs to, ignoring
+    /// case.
+    ///
+    /// Returns `None` if the value isn't a known country code.
+    pub fn from_country(code: &str) -> Option<Region> {
+        let code = code.to_ascii_uppercase();
+        COUNTRIES
+            .binary_search_by(|(c, _)| (*c).cmp(code.as_str()))
+            .ok()
+            .map(|i| COUNTRIES[i].1.clone())
+    }
+
+    /// The ISO 3166-1 alpha-2 codes of the countries in the region, sorted by code
+    pub fn countries(&self) -> impl Iterator<Item = &'static str> + '_ {
+        COUNTRIES
+            .iter()
+            .filter(move |(_, r)| r == self)
+            .map(|(c, _)| *c)
+    }
+}
+
+impl FromStr for Region {
+    type Err = strum::ParseError;
+
+    /// Parses a region using [`Region::from_alias`]. Unlike deserializing, any other value is an
+    /// error rather than a [`Region::Unknown`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_alias(s).ok_or(strum::ParseError::VariantNotFound)
+    }
+}
+
+#[cfg(test)]
+mod test_countries {
+    use super::*;
+
+    #[test]
+    fn countries_sorted_and_unique() {
+        // Region::from_country relies on a binary search
+        assert!(COUNTRIES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
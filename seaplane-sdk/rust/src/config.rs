@@ -0,0 +1,420 @@
+//! Typed application configuration backed by a Metadata directory, which is kept up to date in the
+//! background
+//!
+//! Every key under the directory becomes a field of the configuration, with `/` in the key
+//! (relative to the directory) creating nested tables. For example the keys `my-app/port` and
+//! `my-app/db/host` under the directory `my-app` deserialize into:
+//!
+//! ```rust
+//! # use serde::Deserialize;
+//! #[derive(Deserialize)]
+//! struct MyConfig {
+//!     port: u16,
+//!     db: DbConfig,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct DbConfig {
+//!     host: String,
+//! }
+//! ```
+//!
+//! Values are parsed as JSON when possible, and used as plain strings otherwise. This means a
+//! value of `8080` is a number and `localhost` is a string, but a string field whose value looks
+//! like a number (or `true`/`false`/`null`) must be stored as a quoted JSON string such as
+//! `"8080"`.
+//!
+//! When a snapshot path is set, the last configuration that was loaded successfully is written to
+//! it, and used at startup if the Metadata API is unreachable.
+
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+    api::{
+        metadata::v1::{KeyValue, MetadataRequestBuilder},
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// The default interval between checks for changes
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+type RequestFn = dyn Fn() -> Result<MetadataRequestBuilder> + Send + Sync;
+
+/// Loads a [`Config`] from a Metadata directory
+pub struct ConfigLoader {
+    directory: Directory,
+    request: Arc<RequestFn>,
+    interval: Duration,
+    snapshot: Option<PathBuf>,
+}
+
+impl fmt::Debug for ConfigLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigLoader")
+            .field("directory", &self.directory)
+            .field("interval", &self.interval)
+            .field("snapshot", &self.snapshot)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConfigLoader {
+    /// Creates a loader for the keys under `directory`.
+    ///
+    /// `request` is called before every check for changes and must return a
+    /// [`MetadataRequestBuilder`] with a valid access token (and any other options such as the
+    /// base URL). The loader sets the request target itself.
+    pub fn new<F>(directory: Directory, request: F) -> Self
+    where
+        F: Fn() -> Result<MetadataRequestBuilder> + Send + Sync + 'static,
+    {
+        Self {
+            directory,
+            request: Arc::new(request),
+            interval: DEFAULT_POLL_INTERVAL,
+            snapshot: None,
+        }
+    }
+
+    /// The interval between checks for changes (default: [`DEFAULT_POLL_INTERVAL`]). An interval
+    /// of zero disables checking in the background, [`Config::refresh`] can still be used.
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// A local file to keep the last successfully loaded configuration in, which is used at
+    /// startup when the Metadata API is unreachable
+    #[must_use]
+    pub fn snapshot_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.snapshot = Some(path.into());
+        self
+    }
+
+    /// Loads the configuration and starts checking for changes in the background.
+    ///
+    /// If the Metadata API is unreachable and a snapshot exists, the snapshot is used instead and
+    /// the remote configuration is loaded by the next successful check.
+    pub fn load<T>(self) -> Result<Config<T>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let (raw, source) = match self.fetch() {
+            Ok(raw) => (raw, ConfigSource::Remote),
+            Err(e) if is_unreachable(&e) => match self.read_snapshot()? {
+                Some(raw) => (raw, ConfigSource::Snapshot),
+                None => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+        let current = Arc::new(parse::<T>(&raw)?);
+        if source == ConfigSource::Remote {
+            self.write_snapshot(&raw)?;
+        }
+
+        let interval = self.interval;
+        let inner = Arc::new(Inner {
+            loader: self,
+            state: Mutex::new(State { current, raw, source, last_error: None }),
+            subscribers: Mutex::new(Vec::new()),
+        });
+
+        let mut config = Config { inner, stop: None, handle: None };
+        if !interval.is_zero() {
+            let (stop, stopped) = mpsc::channel::<()>();
+            let inner = Arc::clone(&config.inner);
+            config.stop = Some(stop);
+            config.handle = Some(thread::spawn(move || {
+                // Any message or the sender being dropped means we're done
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    // Errors are recorded in the state, and the previous configuration is kept
+                    let _ = inner.refresh();
+                }
+            }));
+        }
+
+        Ok(config)
+    }
+
+    // Retrieves all keys under the directory and assembles them into a single JSON value
+    fn fetch(&self) -> Result<JsonValue> {
+        let mut context = RangeQueryContext::new();
+        context.set_directory(self.directory.clone());
+        let mut req = (self.request)()?.range(context).build()?;
+        assemble(&self.directory.decode(), req.get_all_pages()?)
+    }
+
+    fn read_snapshot(&self) -> Result<Option<JsonValue>> {
+        match &self.snapshot {
+            Some(path) if path.exists() => Ok(Some(serde_json::from_slice(&fs::read(path)?)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn write_snapshot(&self, raw: &JsonValue) -> Result<()> {
+        if let Some(path) = &self.snapshot {
+            // Write to a temporary file first so a crash never leaves a partial snapshot behind
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, serde_json::to_vec_pretty(raw)?)?;
+            fs::rename(tmp, path)?;
+        }
+        Ok(())
+    }
+}
+
+// Whether an error means we could not talk to the Metadata API at all, as opposed to the API
+// refusing the request
+fn is_unreachable(e: &SeaplaneError) -> bool {
+    match e {
+        SeaplaneError::UnknownHttp(_) => true,
+        SeaplaneError::ApiResponse(ae) => {
+            matches!(ae.kind, ApiErrorKind::InternalServerError | ApiErrorKind::ServiceUnavailable)
+        }
+        _ => false,
+    }
+}
+
+fn parse<T: DeserializeOwned>(raw: &JsonValue) -> Result<T> {
+    serde_json::from_value(raw.clone()).map_err(|e| SeaplaneError::InvalidConfig(e.to_string()))
+}
+
+// Turns the key-value pairs under `dir` into nested JSON objects
+pub(crate) fn assemble(dir: &[u8], kvs: Vec<KeyValue>) -> Result<JsonValue> {
+    let mut root = Map::new();
+    for kv in kvs {
+        let key = kv.key.decode();
+        // Keys may or may not include the directory
+        let key = key
+            .strip_prefix(dir)
+            .and_then(|k| k.strip_prefix(b"/"))
+            .unwrap_or(&key);
+        let key = std::str::from_utf8(key).map_err(|_| {
+            SeaplaneError::InvalidConfig(format!("key '{}' is not valid UTF-8", kv.key.encoded()))
+        })?;
+        let value = String::from_utf8(kv.value.decode()).map_err(|_| {
+            SeaplaneError::InvalidConfig(format!("the value of '{key}' is not valid UTF-8"))
+        })?;
+        let value = serde_json::from_str(&value).unwrap_or(JsonValue::String(value));
+
+        let mut segments: Vec<_> = key.split('/').filter(|s| !s.is_empty()).collect();
+        let last = segments
+            .pop()
+            .ok_or_else(|| SeaplaneError::InvalidConfig(format!("key '{key}' is empty")))?;
+        let mut table = &mut root;
+        for segment in segments {
+            table = match table
+                .entry(segment)
+                .or_insert_with(|| JsonValue::Object(Map::new()))
+            {
+                JsonValue::Object(t) => t,
+                _ => {
+                    return Err(SeaplaneError::InvalidConfig(format!(
+                        "'{segment}' is both a value and a directory"
+                    )))
+                }
+            };
+        }
+        if table.insert(last.to_owned(), value).is_some() {
+            return Err(SeaplaneError::InvalidConfig(format!(
+                "'{last}' is both a value and a directory"
+            )));
+        }
+    }
+    Ok(JsonValue::Object(root))
+}
+
+/// Where the current configuration was loaded from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The Metadata API
+    Remote,
+    /// The local snapshot, because the Metadata API was unreachable at startup
+    Snapshot,
+}
+
+/// Sent to subscribers whenever the configuration changes
+#[derive(Debug)]
+pub struct ConfigChange<T> {
+    /// The configuration before the change
+    pub old: Arc<T>,
+    /// The configuration after the change
+    pub new: Arc<T>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    current: Arc<T>,
+    raw: JsonValue,
+    source: ConfigSource,
+    last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    loader: ConfigLoader,
+    state: Mutex<State<T>>,
+    subscribers: Mutex<Vec<mpsc::Sender<ConfigChange<T>>>>,
+}
+
+impl<T: DeserializeOwned> Inner<T> {
+    fn refresh(&self) -> Result<bool> {
+        let res = self.try_refresh();
+        self.state.lock().unwrap().last_error = res.as_ref().err().map(ToString::to_string);
+        res
+    }
+
+    fn try_refresh(&self) -> Result<bool> {
+        let raw = self.loader.fetch()?;
+        let mut state = self.state.lock().unwrap();
+        if raw == state.raw {
+            state.source = ConfigSource::Remote;
+            return Ok(false);
+        }
+
+        // Only replace the current configuration (and snapshot) with one that is valid, otherwise
+        // the previous configuration and its source are kept
+        let new = Arc::new(parse::<T>(&raw)?);
+        self.loader.write_snapshot(&raw)?;
+        let old = std::mem::replace(&mut state.current, Arc::clone(&new));
+        state.raw = raw;
+        state.source = ConfigSource::Remote;
+        drop(state);
+
+        // Subscribers that have gone away are forgotten
+        self.subscribers.lock().unwrap().retain(|tx| {
+            tx.send(ConfigChange { old: Arc::clone(&old), new: Arc::clone(&new) })
+                .is_ok()
+        });
+        Ok(true)
+    }
+}
+
+/// A configuration which is kept up to date in the background. Checking for changes stops when
+/// this is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use seaplane::{
+///     api::{metadata::v1::MetadataRequestBuilder, shared::v1::Directory},
+///     config::ConfigLoader,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct MyConfig {
+///     log_level: String,
+///     workers: u32,
+/// }
+///
+/// let config = ConfigLoader::new(Directory::from_unencoded("my-app"), || {
+///     Ok(MetadataRequestBuilder::new().token("abc123_token"))
+/// })
+/// .interval(Duration::from_secs(10))
+/// .snapshot_path("/var/lib/my-app/config.json")
+/// .load::<MyConfig>()
+/// .unwrap();
+///
+/// dbg!(config.current());
+/// for change in config.subscribe() {
+///     println!("workers changed from {} to {}", change.old.workers, change.new.workers);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Config<T> {
+    inner: Arc<Inner<T>>,
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: DeserializeOwned> Config<T> {
+    /// The current configuration
+    pub fn current(&self) -> Arc<T> { Arc::clone(&self.inner.state.lock().unwrap().current) }
+
+    /// Where the current configuration was loaded from
+    pub fn source(&self) -> ConfigSource { self.inner.state.lock().unwrap().source }
+
+    /// The error of the last check for changes, if it failed. While checks fail the previous
+    /// configuration is kept.
+    pub fn last_error(&self) -> Option<String> {
+        self.inner.state.lock().unwrap().last_error.clone()
+    }
+
+    /// Returns a channel which receives the old and new configuration whenever it changes
+    pub fn subscribe(&self) -> mpsc::Receiver<ConfigChange<T>> {
+        let (tx, rx) = mpsc::channel();
+        self.inner.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Checks for changes immediately, returning `true` if the configuration changed
+    pub fn refresh(&self) -> Result<bool> { self.inner.refresh() }
+}
+
+impl<T> Drop for Config<T> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn kv(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: crate::api::metadata::v1::Key::from_unencoded(key),
+            value: crate::api::metadata::v1::Value::from_unencoded(value),
+        }
+    }
+
+    #[test]
+    fn assemble_nested() {
+        let raw = assemble(
+            b"app",
+            vec![
+                kv("app/port", "8080"),
+                kv("app/name", "web"),
+                kv("app/db/host", "localhost"),
+                kv("db/user", r#""1234""#),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            raw,
+            json!({"port": 8080, "name": "web", "db": {"host": "localhost", "user": "1234"}})
+        );
+    }
+
+    #[test]
+    fn assemble_conflicts() {
+        assert_eq!(
+            assemble(b"app", vec![kv("app/db", "x"), kv("app/db/host", "y")]),
+            Err(SeaplaneError::InvalidConfig(String::new()))
+        );
+        assert_eq!(
+            assemble(b"app", vec![kv("app/db/host", "y"), kv("app/db", "x")]),
+            Err(SeaplaneError::InvalidConfig(String::new()))
+        );
+    }
+}
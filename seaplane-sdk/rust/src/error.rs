@@ -40,6 +40,8 @@ pub enum SeaplaneError {
     MissingFlightImageReference,
     #[error("the requirements specified in the builder are in conflict and invalid")]
     ConflictingRequirements,
+    #[error("'{0}' is not a value this version of the SDK knows about")]
+    UnknownValue(String),
     #[error("request did not include the required key")]
     MissingMetadataKey,
     #[error("request must target either key or range")]
@@ -50,6 +52,30 @@ pub enum SeaplaneError {
     IncorrectRestrictRequestTarget,
     #[error("the API returned an error status")]
     ApiResponse(#[from] ApiError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the value at the given key is not a chunked value")]
+    NotChunkedValue,
+    #[error("chunked value failed verification: {0}")]
+    ChunkedValueCorrupted(String),
+    #[error("invalid data key: {0}")]
+    InvalidDataKey(String),
+    #[error("no data key with the ID '{0}' is available")]
+    UnknownDataKey(String),
+    #[error("the value is not encrypted")]
+    NotEncryptedValue,
+    #[error("the value could not be encrypted")]
+    EncryptionFailed,
+    #[error(
+        "the value could not be decrypted, either the data key is wrong or the value was altered"
+    )]
+    DecryptionFailed,
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("invalid feature flag: {0}")]
+    InvalidFlag(String),
+    #[error("timed out after {0:?}")]
+    Timeout(std::time::Duration),
 }
 
 impl From<reqwest::Error> for SeaplaneError {
@@ -83,6 +109,7 @@ impl PartialEq for SeaplaneError {
             MissingFlightName => matches!(rhs, MissingFlightName),
             MissingFlightImageReference => matches!(rhs, MissingFlightImageReference),
             ConflictingRequirements => matches!(rhs, ConflictingRequirements),
+            UnknownValue(_) => matches!(rhs, UnknownValue(_)),
             MissingMetadataKey => matches!(rhs, MissingMetadataKey),
             IncorrectMetadataRequestTarget => matches!(rhs, IncorrectMetadataRequestTarget),
             IncorrectLocksRequestTarget => matches!(rhs, IncorrectLocksRequestTarget),
@@ -91,6 +118,17 @@ impl PartialEq for SeaplaneError {
                 ApiResponse(oae) => ae == oae,
                 _ => false,
             },
+            Io(_) => matches!(rhs, Io(_)),
+            NotChunkedValue => matches!(rhs, NotChunkedValue),
+            ChunkedValueCorrupted(_) => matches!(rhs, ChunkedValueCorrupted(_)),
+            InvalidDataKey(_) => matches!(rhs, InvalidDataKey(_)),
+            UnknownDataKey(_) => matches!(rhs, UnknownDataKey(_)),
+            NotEncryptedValue => matches!(rhs, NotEncryptedValue),
+            EncryptionFailed => matches!(rhs, EncryptionFailed),
+            DecryptionFailed => matches!(rhs, DecryptionFailed),
+            InvalidConfig(_) => matches!(rhs, InvalidConfig(_)),
+            InvalidFlag(_) => matches!(rhs, InvalidFlag(_)),
+            Timeout(_) => matches!(rhs, Timeout(_)),
         }
     }
 }
@@ -0,0 +1,437 @@
+//! Feature flags stored in a Metadata directory and evaluated locally
+//!
+//! Each flag is a single key directly under the flags directory, the last segment of the key is
+//! the name of the flag and the value is a JSON document describing it. For example the key
+//! `flags/new-checkout` could hold:
+//!
+//! ```json
+//! {"kind": "percentage", "enabled": true, "percentage": 25, "regions": ["XE", "XU"]}
+//! ```
+//!
+//! There are three kinds of flags:
+//!
+//! - `boolean`: on for everyone while `enabled` is `true`
+//! - `percentage`: on for `percentage` percent (`0` to `100`) of user IDs
+//! - `allow-list`: on only for the user IDs listed in `users`
+//!
+//! Any kind of flag may also list the `regions` it is limited to, in which case it is off for
+//! contexts in any other region (or without a region). A flag which is not `enabled` is always
+//! off.
+//!
+//! A user is placed in a percentage rollout by taking the first 8 bytes of the SHA-256 digest of
+//! `<flag name>:<user ID>` as a big endian integer modulo 100, and checking whether it is less
+//! than `percentage`. The same user therefore always gets the same result for the same flag, and
+//! stays enabled as the percentage is increased.
+//!
+//! Flags are kept up to date in the background by a [`ConfigLoader`], which means they can also
+//! be kept in a local snapshot for when the Metadata API is unreachable.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{mpsc, Arc},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    api::{
+        metadata::v1::{Key, KeyValue, Value},
+        shared::v1::{Directory, Region},
+    },
+    config::{assemble, Config, ConfigChange, ConfigLoader, ConfigSource},
+    error::{Result, SeaplaneError},
+};
+
+/// A single feature flag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flag {
+    /// A flag which is not enabled is always off
+    pub enabled: bool,
+    /// Who the flag is on for while it is enabled
+    #[serde(flatten)]
+    pub rule: FlagRule,
+    /// The regions the flag is limited to, or all regions when empty
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_regions"
+    )]
+    pub regions: Vec<Region>,
+    /// A human readable description of the flag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+// Flags are written by users rather than the server, so unlike in API responses an unknown region
+// (most likely a typo) makes the flag invalid
+fn deserialize_regions<'de, D: Deserializer<'de>>(
+    de: D,
+) -> std::result::Result<Vec<Region>, D::Error> {
+    Vec::<String>::deserialize(de)?
+        .iter()
+        .map(|r| {
+            r.parse()
+                .map_err(|_| de::Error::custom(format!("unknown region '{r}'")))
+        })
+        .collect()
+}
+
+/// The kind of a [`Flag`] and its settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", try_from = "FlagRuleRepr")]
+pub enum FlagRule {
+    /// On for everyone
+    Boolean,
+    /// On for a stable percentage (`0` to `100`) of user IDs
+    Percentage { percentage: u8 },
+    /// On only for the listed user IDs
+    AllowList { users: BTreeSet<String> },
+}
+
+// The stored form of a FlagRule, which is checked before it is used
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum FlagRuleRepr {
+    Boolean,
+    Percentage { percentage: u8 },
+    AllowList { users: BTreeSet<String> },
+}
+
+impl TryFrom<FlagRuleRepr> for FlagRule {
+    type Error = SeaplaneError;
+
+    fn try_from(repr: FlagRuleRepr) -> Result<Self> {
+        Ok(match repr {
+            FlagRuleRepr::Boolean => FlagRule::Boolean,
+            FlagRuleRepr::Percentage { percentage } if percentage > 100 => {
+                return Err(SeaplaneError::InvalidFlag(format!(
+                    "the percentage must be between 0 and 100, not {percentage}"
+                )))
+            }
+            FlagRuleRepr::Percentage { percentage } => FlagRule::Percentage { percentage },
+            FlagRuleRepr::AllowList { users } => FlagRule::AllowList { users },
+        })
+    }
+}
+
+impl FlagRule {
+    /// The name of the kind of flag as it appears in the stored JSON
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FlagRule::Boolean => "boolean",
+            FlagRule::Percentage { .. } => "percentage",
+            FlagRule::AllowList { .. } => "allow-list",
+        }
+    }
+}
+
+impl Flag {
+    /// Creates an enabled flag with the given rule, for all regions
+    pub fn new(rule: FlagRule) -> Self {
+        Self { enabled: true, rule, regions: Vec::new(), description: None }
+    }
+
+    /// Parses a flag from the value of its key
+    pub fn from_value(value: &Value) -> Result<Self> {
+        serde_json::from_slice(&value.decode())
+            .map_err(|e| SeaplaneError::InvalidFlag(e.to_string()))
+    }
+
+    /// The value to store at the key of this flag
+    pub fn to_value(&self) -> Result<Value> { Ok(Value::from_unencoded(serde_json::to_vec(self)?)) }
+
+    /// Whether the flag named `name` is on for `ctx`
+    pub fn evaluate(&self, name: &str, ctx: &FlagContext) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if !self.regions.is_empty()
+            && !ctx
+                .region
+                .as_ref()
+                .map(|r| self.regions.contains(r))
+                .unwrap_or(false)
+        {
+            return false;
+        }
+        match &self.rule {
+            FlagRule::Boolean => true,
+            FlagRule::Percentage { percentage } => match &ctx.user_id {
+                Some(user_id) => bucket(name, user_id) < u64::from(*percentage),
+                // Without a user ID there is nothing to place in the rollout
+                None => *percentage >= 100,
+            },
+            FlagRule::AllowList { users } => ctx
+                .user_id
+                .as_ref()
+                .map(|u| users.contains(u))
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Returns the key of the flag named `name` under the flags directory `dir`
+pub fn flag_key(dir: &Directory, name: &str) -> Key {
+    let mut key = dir.decode();
+    key.push(b'/');
+    key.extend_from_slice(name.as_bytes());
+    Key::from_unencoded(key)
+}
+
+// The bucket (0 to 99) a user falls into for a percentage rollout of a flag
+fn bucket(name: &str, user_id: &str) -> u64 {
+    let digest = Sha256::digest(format!("{name}:{user_id}").as_bytes());
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes) % 100
+}
+
+/// What a flag is evaluated against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlagContext {
+    /// The ID of the user the flag is evaluated for
+    pub user_id: Option<String>,
+    /// The region the flag is evaluated in
+    pub region: Option<Region>,
+}
+
+impl FlagContext {
+    /// Creates an empty context
+    pub fn new() -> Self { Self::default() }
+
+    /// The ID of the user the flag is evaluated for
+    #[must_use]
+    pub fn user_id<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// The region the flag is evaluated in
+    #[must_use]
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+}
+
+/// All flags of a flags directory.
+///
+/// A flag which can not be parsed does not prevent the others from being used, it is recorded in
+/// [`FlagSet::invalid`] instead (and is off).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlagSet {
+    /// The flags by name
+    pub flags: BTreeMap<String, Flag>,
+    /// The names of the flags which could not be parsed, and why
+    pub invalid: BTreeMap<String, String>,
+}
+
+impl<'de> Deserialize<'de> for FlagSet {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut set = FlagSet::default();
+        for (name, raw) in BTreeMap::<String, JsonValue>::deserialize(deserializer)? {
+            match serde_json::from_value(raw) {
+                Ok(flag) => {
+                    set.flags.insert(name, flag);
+                }
+                Err(e) => {
+                    set.invalid.insert(name, e.to_string());
+                }
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl FlagSet {
+    /// Builds the flags from all key-value pairs under the flags directory `dir`, such as those
+    /// returned by [`MetadataRequest::get_all_pages`]
+    ///
+    /// [`MetadataRequest::get_all_pages`]: crate::api::metadata::v1::MetadataRequest::get_all_pages
+    pub fn from_key_values(dir: &Directory, kvs: Vec<KeyValue>) -> Result<Self> {
+        serde_json::from_value(assemble(&dir.decode(), kvs)?)
+            .map_err(|e| SeaplaneError::InvalidFlag(e.to_string()))
+    }
+
+    /// Whether the flag named `name` is on for `ctx`, flags which do not exist are off
+    pub fn is_enabled(&self, name: &str, ctx: &FlagContext) -> bool {
+        self.flags
+            .get(name)
+            .map(|f| f.evaluate(name, ctx))
+            .unwrap_or(false)
+    }
+}
+
+/// The flags of a flags directory, which are kept up to date in the background. Checking for
+/// changes stops when this is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use seaplane::{
+///     api::{
+///         metadata::v1::MetadataRequestBuilder,
+///         shared::v1::{Directory, Region},
+///     },
+///     config::ConfigLoader,
+///     flags::{FlagContext, Flags},
+/// };
+///
+/// let flags = Flags::load(
+///     ConfigLoader::new(Directory::from_unencoded("flags"), || {
+///         Ok(MetadataRequestBuilder::new().token("abc123_token"))
+///     })
+///     .interval(Duration::from_secs(10)),
+/// )
+/// .unwrap();
+///
+/// let ctx = FlagContext::new().user_id("user-1234").region(Region::XE);
+/// if flags.is_enabled("new-checkout", &ctx) {
+///     println!("using the new checkout");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Flags {
+    config: Config<FlagSet>,
+}
+
+impl Flags {
+    /// Loads the flags from the directory of `loader` and starts checking for changes in the
+    /// background at the interval of `loader`
+    pub fn load(loader: ConfigLoader) -> Result<Self> { Ok(Self { config: loader.load()? }) }
+
+    /// Whether the flag named `name` is on for `ctx`, flags which do not exist are off
+    pub fn is_enabled(&self, name: &str, ctx: &FlagContext) -> bool {
+        self.config.current().is_enabled(name, ctx)
+    }
+
+    /// The flag named `name`, if it exists and is valid
+    pub fn get(&self, name: &str) -> Option<Flag> { self.config.current().flags.get(name).cloned() }
+
+    /// All current flags
+    pub fn current(&self) -> Arc<FlagSet> { self.config.current() }
+
+    /// Where the current flags were loaded from
+    pub fn source(&self) -> ConfigSource { self.config.source() }
+
+    /// The error of the last check for changes, if it failed. While checks fail the previous
+    /// flags are kept.
+    pub fn last_error(&self) -> Option<String> { self.config.last_error() }
+
+    /// Returns a channel which receives the old and new flags whenever they change
+    pub fn subscribe(&self) -> mpsc::Receiver<ConfigChange<FlagSet>> { self.config.subscribe() }
+
+    /// Checks for changes immediately, returning `true` if the flags changed
+    pub fn refresh(&self) -> Result<bool> { self.config.refresh() }
+}
+
+#[cfg(test)]
+mod flags_test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn flag_json() {
+        let flag: Flag = serde_json::from_value(json!({
+            "kind": "percentage",
+            "enabled": true,
+            "percentage": 25,
+            "regions": ["xe"]
+        }))
+        .unwrap();
+        assert_eq!(
+            flag,
+            Flag {
+                enabled: true,
+                rule: FlagRule::Percentage { percentage: 25 },
+                regions: vec![Region::XE],
+                description: None
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(Flag::new(FlagRule::Boolean)).unwrap(),
+            json!({"kind": "boolean", "enabled": true})
+        );
+
+        // Percentages above 100 are rejected
+        let value = Value::from_unencoded(
+            json!({"kind": "percentage", "enabled": true, "percentage": 101}).to_string(),
+        );
+        assert_eq!(Flag::from_value(&value), Err(SeaplaneError::InvalidFlag(String::new())));
+        // as are unknown regions
+        let value = Value::from_unencoded(
+            json!({"kind": "boolean", "enabled": true, "regions": ["XZ"]}).to_string(),
+        );
+        assert_eq!(Flag::from_value(&value), Err(SeaplaneError::InvalidFlag(String::new())));
+    }
+
+    #[test]
+    fn evaluate_boolean_and_regions() {
+        let mut flag = Flag::new(FlagRule::Boolean);
+        assert!(flag.evaluate("f", &FlagContext::new()));
+        flag.regions = vec![Region::XE];
+        assert!(!flag.evaluate("f", &FlagContext::new()));
+        assert!(!flag.evaluate("f", &FlagContext::new().region(Region::XN)));
+        assert!(flag.evaluate("f", &FlagContext::new().region(Region::XE)));
+        flag.enabled = false;
+        assert!(!flag.evaluate("f", &FlagContext::new().region(Region::XE)));
+    }
+
+    #[test]
+    fn evaluate_percentage() {
+        let users: Vec<_> = (0..1000).map(|i| format!("user-{i}")).collect();
+        let enabled = |percentage| {
+            let flag = Flag::new(FlagRule::Percentage { percentage });
+            users
+                .iter()
+                .filter(|u| flag.evaluate("f", &FlagContext::new().user_id(u.as_str())))
+                .count()
+        };
+        assert_eq!(enabled(0), 0);
+        assert_eq!(enabled(100), 1000);
+        let quarter = enabled(25);
+        assert!((200..300).contains(&quarter), "{quarter}");
+        assert!(enabled(50) > quarter);
+
+        let flag = Flag::new(FlagRule::Percentage { percentage: 50 });
+        assert!(!flag.evaluate("f", &FlagContext::new()));
+    }
+
+    #[test]
+    fn evaluate_allow_list() {
+        let flag = Flag::new(FlagRule::AllowList { users: ["alice".to_owned()].into() });
+        assert!(flag.evaluate("f", &FlagContext::new().user_id("alice")));
+        assert!(!flag.evaluate("f", &FlagContext::new().user_id("bob")));
+        assert!(!flag.evaluate("f", &FlagContext::new()));
+    }
+
+    #[test]
+    fn flag_set_skips_invalid() {
+        let set: FlagSet = serde_json::from_value(json!({
+            "on": {"kind": "boolean", "enabled": true},
+            "bad": {"kind": "sometimes", "enabled": true},
+            "nested": {"deeper": "x"}
+        }))
+        .unwrap();
+        assert!(set.is_enabled("on", &FlagContext::new()));
+        assert!(!set.is_enabled("bad", &FlagContext::new()));
+        assert!(!set.is_enabled("missing", &FlagContext::new()));
+        assert_eq!(set.invalid.keys().collect::<Vec<_>>(), ["bad", "nested"]);
+    }
+
+    #[test]
+    fn key_of_flag() {
+        assert_eq!(
+            flag_key(&Directory::from_unencoded("flags"), "beta"),
+            Key::from_unencoded("flags/beta")
+        );
+    }
+}
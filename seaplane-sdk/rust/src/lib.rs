@@ -28,7 +28,13 @@
 mod macros;
 pub mod api;
 pub mod base64;
+#[cfg(feature = "metadata_api_v1")]
+pub mod config;
 pub mod error;
+#[cfg(feature = "metadata_api_v1")]
+pub mod flags;
+#[cfg(all(feature = "metadata_api_v1", feature = "locks_api_v1"))]
+pub mod queue;
 
 /// Allows using the exact same traits derived from these dependencies. If re-exported here, that
 /// most likely means the derived trait appears in a types public API and you should `use` the
@@ -1,14 +1,50 @@
-/// Implements Deserialize using FromStr
-macro_rules! impl_deser_from_str {
+/// Implements `AsRef<str>`, `Display`, `Serialize` and `Deserialize` for an enum of strings sent by
+/// the server which has an `Unknown(String)` variant, so that values added on the
+/// server side later are kept as-is (and round-trip) instead of failing to deserialize.
+///
+/// Only deserializing is lenient, the `FromStr` implementation of the enum (usually derived with
+/// `strum::EnumString` and `#[strum(disabled)]` on the `Unknown` variant) must reject unknown
+/// values so that typos in values written by users are caught. The enum must also derive
+/// `strum::IntoStaticStr`.
+macro_rules! impl_str_enum_with_unknown {
     ($t:ty) => {
+        impl AsRef<str> for $t {
+            fn as_ref(&self) -> &str {
+                match self {
+                    Self::Unknown(s) => s,
+                    known => known.into(),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_ref())
+            }
+        }
+
+        impl ::serde::Serialize for $t {
+            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::ser::Serializer,
+            {
+                serializer.serialize_str(self.as_ref())
+            }
+        }
+
         impl<'de> ::serde::Deserialize<'de> for $t {
             fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
             where
                 D: ::serde::de::Deserializer<'de>,
             {
                 let s = String::deserialize(deserializer)?;
-                s.parse().map_err(::serde::de::Error::custom)
+                Ok(s.parse().unwrap_or(Self::Unknown(s)))
             }
         }
+
+        impl $t {
+            /// Returns `true` if this is a value this version of the SDK doesn't know about
+            pub fn is_unknown(&self) -> bool { matches!(self, Self::Unknown(_)) }
+        }
     };
 }
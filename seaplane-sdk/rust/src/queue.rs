@@ -0,0 +1,255 @@
+//! A distributed work queue built on the Metadata and Locks APIs
+//!
+//! Each task is a key-value pair under the queue directory. Task keys start with the time they
+//! were pushed, so tasks are handed out roughly in the order they were pushed.
+//!
+//! A consumer claims a task by acquiring the lock with the same name as the task key. The TTL of
+//! the lock acts as a visibility timeout: while the lock is held no other consumer can claim the
+//! task, and if the consumer fails to complete the task before the TTL expires the task becomes
+//! available again. Completing a task deletes the key and then releases the lock.
+//!
+//! Tasks are delivered *at least once*, a consumer that takes longer than the TTL (and does not
+//! [`Queue::extend`] it) may find the task has also been handed to another consumer.
+
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    api::{
+        locks::v1::{HeldLock, LockName, LocksRequestBuilder},
+        metadata::v1::{Key, MetadataRequestBuilder, Value},
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// The default time in seconds a claimed task is hidden from other consumers
+pub const DEFAULT_VISIBILITY_TIMEOUT: u32 = 30;
+
+/// The default client ID stored with the locks of claimed tasks
+pub const DEFAULT_CLIENT_ID: &str = "seaplane-queue";
+
+type MetadataFn = dyn Fn() -> Result<MetadataRequestBuilder> + Send + Sync;
+type LocksFn = dyn Fn() -> Result<LocksRequestBuilder> + Send + Sync;
+
+/// A task that has been claimed by this consumer
+#[derive(Debug)]
+pub struct ClaimedTask {
+    key: Key,
+    value: Value,
+    lock: HeldLock,
+}
+
+impl ClaimedTask {
+    /// The key of the task
+    pub fn key(&self) -> &Key { &self.key }
+
+    /// The value of the task
+    pub fn value(&self) -> &Value { &self.value }
+
+    /// The lock held while the task is claimed
+    pub fn lock(&self) -> &HeldLock { &self.lock }
+}
+
+/// A task waiting in the queue, as returned by [`Queue::list`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedTask {
+    /// The key of the task
+    pub key: Key,
+    /// The value of the task
+    pub value: Value,
+    /// The client ID of the consumer currently holding a claim on the task, if any
+    pub claimed_by: Option<String>,
+}
+
+/// A queue of tasks stored under a Metadata directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use seaplane::{
+///     api::{
+///         locks::v1::LocksRequestBuilder, metadata::v1::MetadataRequestBuilder,
+///         shared::v1::Directory,
+///     },
+///     queue::Queue,
+/// };
+///
+/// let queue = Queue::new(
+///     Directory::from_unencoded("jobs"),
+///     || Ok(MetadataRequestBuilder::new().token("abc123_token")),
+///     || Ok(LocksRequestBuilder::new().token("abc123_token")),
+/// )
+/// .client_id("worker-1");
+///
+/// queue.push("resize image-1234.png").unwrap();
+///
+/// if let Some(task) = queue.pop(60).unwrap() {
+///     println!("working on {}", String::from_utf8_lossy(&task.value().decode()));
+///     queue.complete(task).unwrap();
+/// }
+/// ```
+pub struct Queue {
+    directory: Directory,
+    metadata: Box<MetadataFn>,
+    locks: Box<LocksFn>,
+    client_id: String,
+}
+
+impl fmt::Debug for Queue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Queue")
+            .field("directory", &self.directory)
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Queue {
+    /// Creates a queue of the tasks under `directory`.
+    ///
+    /// `metadata` and `locks` are called before every request and must return a request builder
+    /// with a valid access token (and any other options such as the base URL). The queue sets the
+    /// request target itself.
+    pub fn new<M, L>(directory: Directory, metadata: M, locks: L) -> Self
+    where
+        M: Fn() -> Result<MetadataRequestBuilder> + Send + Sync + 'static,
+        L: Fn() -> Result<LocksRequestBuilder> + Send + Sync + 'static,
+    {
+        Self {
+            directory,
+            metadata: Box::new(metadata),
+            locks: Box::new(locks),
+            client_id: DEFAULT_CLIENT_ID.into(),
+        }
+    }
+
+    /// The client ID stored with the locks of claimed tasks (default: [`DEFAULT_CLIENT_ID`])
+    #[must_use]
+    pub fn client_id<S: Into<String>>(mut self, client_id: S) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// The directory the tasks are stored under
+    pub fn directory(&self) -> &Directory { &self.directory }
+
+    /// Adds a task to the end of the queue, returning its key
+    pub fn push<V: AsRef<[u8]>>(&self, value: V) -> Result<Key> {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut key = self.directory.decode();
+        key.extend_from_slice(
+            format!("/{:020}-{}", since_epoch.as_nanos(), uuid::Uuid::new_v4().simple()).as_bytes(),
+        );
+        let key = Key::from_unencoded(key);
+
+        (self.metadata)()?
+            .encoded_key(key.encoded())
+            .build()?
+            .put_value(Value::from_unencoded(value))?;
+        Ok(key)
+    }
+
+    /// Claims the first task that is not claimed by another consumer, hiding it from other
+    /// consumers for `visibility_timeout` seconds. Returns `None` if there is no such task.
+    pub fn pop(&self, visibility_timeout: u32) -> Result<Option<ClaimedTask>> {
+        let mut context = RangeQueryContext::new();
+        context.set_directory(self.directory.clone());
+        loop {
+            let page = (self.metadata)()?
+                .range(context.clone())
+                .build()?
+                .get_page()?;
+            for kv in page.kvs {
+                let lock = match (self.locks)()?
+                    .lock_name(LockName::from_encoded(kv.key.encoded()))
+                    .build()?
+                    .acquire(visibility_timeout, &self.client_id)
+                {
+                    Ok(lock) => lock,
+                    // Claimed by someone else
+                    Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::Conflict => {
+                        continue
+                    }
+                    Err(e) => return Err(e),
+                };
+
+                // The task may have been completed between listing and claiming it
+                match (self.metadata)()?
+                    .encoded_key(kv.key.encoded())
+                    .build()?
+                    .get_value()
+                {
+                    Ok(value) => return Ok(Some(ClaimedTask { key: kv.key, value, lock })),
+                    Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::NotFound => {
+                        self.release(lock)?;
+                    }
+                    Err(e) => {
+                        let _ = self.release(lock);
+                        return Err(e);
+                    }
+                }
+            }
+            match page.next_key {
+                Some(next_key) => context.set_from(next_key),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Removes a claimed task from the queue
+    pub fn complete(&self, task: ClaimedTask) -> Result<()> {
+        (self.metadata)()?
+            .encoded_key(task.key.encoded())
+            .build()?
+            .delete_value()?;
+        // The task is gone, so if the lock has already expired there is nothing left to protect
+        // and the lock does not need to be released
+        let _ = self.release(task.lock);
+        Ok(())
+    }
+
+    /// Gives up the claim on a task, making it available to other consumers immediately
+    pub fn abandon(&self, task: ClaimedTask) -> Result<()> { self.release(task.lock) }
+
+    /// Hides a claimed task from other consumers for another `visibility_timeout` seconds
+    pub fn extend(&self, task: &ClaimedTask, visibility_timeout: u32) -> Result<()> {
+        let lock =
+            HeldLock::new(task.lock.name().clone(), task.lock.id().clone(), task.lock.sequencer());
+        (self.locks)()?
+            .held_lock(lock)
+            .build()?
+            .renew(visibility_timeout)
+    }
+
+    /// Lists all tasks in the queue, including those that are currently claimed
+    pub fn list(&self) -> Result<Vec<QueuedTask>> {
+        let mut context = RangeQueryContext::new();
+        context.set_directory(self.directory.clone());
+        let kvs = (self.metadata)()?.range(context).build()?.get_all_pages()?;
+
+        let mut context = RangeQueryContext::new();
+        context.set_directory(self.directory.clone());
+        let locks = (self.locks)()?.range(context).build()?.get_all_pages()?;
+
+        Ok(kvs
+            .into_iter()
+            .map(|kv| {
+                let claimed_by = locks
+                    .iter()
+                    .find(|l| l.name.encoded() == kv.key.encoded())
+                    .map(|l| l.info.client_id.clone());
+                QueuedTask { key: kv.key, value: kv.value, claimed_by }
+            })
+            .collect())
+    }
+
+    fn release(&self, lock: HeldLock) -> Result<()> {
+        (self.locks)()?.held_lock(lock).build()?.release()
+    }
+}
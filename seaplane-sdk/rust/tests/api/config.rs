@@ -0,0 +1,209 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use httpmock::{prelude::*, Method, Then, When};
+use seaplane::{
+    api::{metadata::v1::MetadataRequestBuilder, shared::v1::Directory},
+    config::{ConfigLoader, ConfigSource},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::MOCK_SERVER;
+
+fn when(when: When, m: Method, p: &str) -> When {
+    when.method(m)
+        .path(p)
+        .header("authorization", "Bearer abc123")
+        .header("accept", "*/*")
+        .header("host", format!("{}:{}", MOCK_SERVER.host(), MOCK_SERVER.port()))
+}
+
+fn then(then: Then, resp_body: serde_json::Value) -> Then {
+    then.status(200)
+        .header("content-type", "application/json")
+        .json_body(resp_body)
+}
+
+fn loader(dir: &str) -> ConfigLoader {
+    ConfigLoader::new(Directory::from_unencoded(dir), || {
+        Ok(MetadataRequestBuilder::new()
+            .token("abc123")
+            .base_url(MOCK_SERVER.base_url()))
+    })
+    .interval(Duration::ZERO)
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    port: u16,
+    db: DbConfig,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct DbConfig {
+    host: String,
+}
+
+// GET /config/base64:{dir}/
+#[test]
+fn load_and_refresh() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YXBw/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                {"key": "YXBwL3BvcnQ", "value": "ODA4MA"},
+                {"key": "YXBwL2RiL2hvc3Q", "value": "bG9jYWxob3N0"}
+            ]}),
+        );
+    });
+
+    let config = loader("app").load::<AppConfig>().unwrap();
+    mock.assert();
+    assert_eq!(config.source(), ConfigSource::Remote);
+    assert_eq!(
+        *config.current(),
+        AppConfig { port: 8080, db: DbConfig { host: "localhost".into() } }
+    );
+    let changes = config.subscribe();
+
+    // Nothing changed
+    assert_eq!(config.refresh(), Ok(false));
+    assert!(changes.try_recv().is_err());
+
+    mock.delete();
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YXBw/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                {"key": "YXBwL3BvcnQ", "value": "OTA5MA"},
+                {"key": "YXBwL2RiL2hvc3Q", "value": "bG9jYWxob3N0"}
+            ]}),
+        );
+    });
+
+    assert_eq!(config.refresh(), Ok(true));
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.old.port, 8080);
+    assert_eq!(change.new.port, 9090);
+    assert_eq!(config.current().port, 9090);
+
+    // An invalid configuration is not applied
+    mock.delete();
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YXBw/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [{"key": "YXBwL3BvcnQ", "value": "bG9jYWxob3N0"}]}),
+        );
+    });
+    assert!(config.refresh().is_err());
+    assert!(config.last_error().is_some());
+    assert_eq!(config.current().port, 9090);
+    assert!(changes.try_recv().is_err());
+    mock.delete();
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct PortConfig {
+    port: u16,
+}
+
+// GET /config/base64:{dir}/
+#[test]
+fn load_snapshot_when_unreachable() {
+    let snapshot =
+        std::env::temp_dir().join(format!("seaplane-config-{}.json", std::process::id()));
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Y2Zn/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [{"key": "Y2ZnL3BvcnQ", "value": "ODA4MA"}]}),
+        );
+    });
+
+    // A successful load writes the snapshot
+    let config = loader("cfg")
+        .snapshot_path(&snapshot)
+        .load::<PortConfig>()
+        .unwrap();
+    mock.assert();
+    assert_eq!(config.source(), ConfigSource::Remote);
+    drop(config);
+    mock.delete();
+
+    let unreachable = || {
+        ConfigLoader::new(Directory::from_unencoded("cfg"), || {
+            // Nothing listens on port 1
+            Ok(MetadataRequestBuilder::new()
+                .token("abc123")
+                .base_url("http://127.0.0.1:1"))
+        })
+        .interval(Duration::ZERO)
+    };
+
+    let config = unreachable()
+        .snapshot_path(&snapshot)
+        .load::<PortConfig>()
+        .unwrap();
+    assert_eq!(config.source(), ConfigSource::Snapshot);
+    assert_eq!(*config.current(), PortConfig { port: 8080 });
+
+    // Without a snapshot the error is returned
+    assert!(unreachable().load::<PortConfig>().is_err());
+
+    // The Metadata API comes back after starting from the snapshot
+    let reachable = Arc::new(AtomicBool::new(false));
+    let config = {
+        let reachable = Arc::clone(&reachable);
+        ConfigLoader::new(Directory::from_unencoded("cfg"), move || {
+            let base_url = if reachable.load(Ordering::SeqCst) {
+                MOCK_SERVER.base_url()
+            } else {
+                "http://127.0.0.1:1".into()
+            };
+            Ok(MetadataRequestBuilder::new()
+                .token("abc123")
+                .base_url(base_url))
+        })
+        .interval(Duration::ZERO)
+        .snapshot_path(&snapshot)
+        .load::<PortConfig>()
+        .unwrap()
+    };
+    assert_eq!(config.source(), ConfigSource::Snapshot);
+    reachable.store(true, Ordering::SeqCst);
+
+    // An invalid remote configuration leaves the snapshot in use
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Y2Zn/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [{"key": "Y2ZnL3BvcnQ", "value": "bG9jYWxob3N0"}]}),
+        );
+    });
+    assert!(config.refresh().is_err());
+    assert_eq!(config.source(), ConfigSource::Snapshot);
+    mock.delete();
+
+    // An unchanged remote configuration is now the source
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Y2Zn/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [{"key": "Y2ZnL3BvcnQ", "value": "ODA4MA"}]}),
+        );
+    });
+    assert_eq!(config.refresh(), Ok(false));
+    assert_eq!(config.source(), ConfigSource::Remote);
+    mock.delete();
+
+    std::fs::remove_file(snapshot).unwrap();
+}
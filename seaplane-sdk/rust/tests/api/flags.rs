@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use httpmock::{prelude::*, Method, Then, When};
+use seaplane::{
+    api::{
+        metadata::v1::MetadataRequestBuilder,
+        shared::v1::{Directory, Region},
+    },
+    config::ConfigLoader,
+    flags::{FlagContext, Flags},
+};
+use serde_json::json;
+
+use super::MOCK_SERVER;
+
+fn when(when: When, m: Method, p: &str) -> When {
+    when.method(m)
+        .path(p)
+        .header("authorization", "Bearer abc123")
+        .header("accept", "*/*")
+        .header("host", format!("{}:{}", MOCK_SERVER.host(), MOCK_SERVER.port()))
+}
+
+fn then(then: Then, resp_body: serde_json::Value) -> Then {
+    then.status(200)
+        .header("content-type", "application/json")
+        .json_body(resp_body)
+}
+
+// GET /config/base64:{dir}/
+#[test]
+fn load_and_evaluate() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:ZmxhZ3M/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                // flags/beta = {"kind":"boolean","enabled":true}
+                {"key": "ZmxhZ3MvYmV0YQ", "value": "eyJraW5kIjoiYm9vbGVhbiIsImVuYWJsZWQiOnRydWV9"},
+                // flags/eu = {"kind":"allow-list","enabled":true,"users":["alice"],"regions":["XE"]}
+                {"key": "ZmxhZ3MvZXU", "value": "eyJraW5kIjoiYWxsb3ctbGlzdCIsImVuYWJsZWQiOnRydWUsInVzZXJzIjpbImFsaWNlIl0sInJlZ2lvbnMiOlsiWEUiXX0"},
+                // flags/broken = not a flag
+                {"key": "ZmxhZ3MvYnJva2Vu", "value": "bm90IGEgZmxhZw"}
+            ]}),
+        );
+    });
+
+    let flags = Flags::load(
+        ConfigLoader::new(Directory::from_unencoded("flags"), || {
+            Ok(MetadataRequestBuilder::new()
+                .token("abc123")
+                .base_url(MOCK_SERVER.base_url()))
+        })
+        .interval(Duration::ZERO),
+    )
+    .unwrap();
+    mock.assert();
+
+    let alice = FlagContext::new().user_id("alice");
+    assert!(flags.is_enabled("beta", &FlagContext::new()));
+    assert!(!flags.is_enabled("eu", &alice));
+    assert!(flags.is_enabled("eu", &alice.clone().region(Region::XE)));
+    assert!(!flags.is_enabled("eu", &FlagContext::new().user_id("bob").region(Region::XE)));
+    assert!(!flags.is_enabled("broken", &alice));
+    assert!(!flags.is_enabled("missing", &alice));
+    assert!(flags.current().invalid.contains_key("broken"));
+
+    mock.delete();
+}
@@ -1,7 +1,13 @@
 use httpmock::{prelude::*, Method, Then, When};
-use seaplane::api::{
-    metadata::v1::{Key, KeyValue, MetadataRequestBuilder, Value},
-    shared::v1::{Directory, RangeQueryContext},
+use seaplane::{
+    api::{
+        metadata::v1::{
+            ChunkManifest, Key, KeyValue, MetadataRequestBuilder, Value, WatchEvent, WatchTarget,
+            Watcher, CHUNK_MANIFEST_FORMAT,
+        },
+        shared::v1::{Directory, RangeQueryContext},
+    },
+    error::SeaplaneError,
 };
 use serde_json::json;
 
@@ -147,3 +153,234 @@ fn delete_value() {
 
     assert!(resp.is_ok())
 }
+
+fn hello_manifest() -> ChunkManifest {
+    ChunkManifest {
+        format: CHUNK_MANIFEST_FORMAT.into(),
+        id: "abc".into(),
+        size: 5,
+        chunks: 2,
+        sha256: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".into(),
+    }
+}
+
+fn manifest_kv(manifest: &ChunkManifest) -> KeyValue {
+    KeyValue {
+        key: Key::from_unencoded("big"),
+        value: Value::from_unencoded(serde_json::to_vec(manifest).unwrap()),
+    }
+}
+
+// GET /config/base64:{key} and GET /config/base64:{key}/.chunks/{id}-{index}
+#[test]
+fn get_value_chunked() {
+    let manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Ymln");
+        then(t, json!(manifest_kv(&hello_manifest())));
+    });
+    let chunk0_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAw");
+        then(t, json!({"key": "YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAw", "value": "aGVs"}));
+    });
+    let mut chunk1_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx");
+        then(t, json!({"key": "YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx", "value": "bG8"}));
+    });
+
+    let req = partial_build().encoded_key("Ymln").build().unwrap();
+    let mut out = Vec::new();
+    let resp = req.get_value_chunked(&mut out).unwrap();
+
+    // Ensure the endpoints were hit
+    manifest_mock.assert();
+    chunk0_mock.assert();
+    chunk1_mock.assert();
+
+    assert_eq!(resp, hello_manifest());
+    assert_eq!(out, b"hello");
+
+    // A chunk that doesn't match the manifest's hash is an error
+    chunk1_mock.delete();
+    let _chunk1_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx");
+        // "loo" instead of "lo"
+        then(t, json!({"key": "YmlnLy5jaHVua3MvYWJjLTAwMDAwMDAx", "value": "bG9v"}));
+    });
+    let resp = req.get_value_chunked(Vec::new());
+    assert_eq!(resp, Err(SeaplaneError::ChunkedValueCorrupted(String::new())));
+}
+
+// PUT /config/base64:{key}/.chunks/{id}-{index} and PUT /config/base64:{key}
+#[test]
+fn put_value_chunked() {
+    let not_found_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:cHV0");
+        t.status(404)
+            .header("content-type", "application/json")
+            .json_body(json!({"status": 404, "title": "Not Found", "detail": "key not found"}));
+    });
+    let chunks_mock = MOCK_SERVER.mock(|w, t| {
+        w.method(PUT)
+            .path_contains("/v1/config/base64:cHV0Ly5jaHVua3Mv")
+            .header("content-type", "application/octet-stream");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:cHV0").header("content-type", "application/octet-stream");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let req = partial_build().encoded_key("cHV0").build().unwrap();
+    let resp = req.put_value_chunked(&b"hello"[..], 2).unwrap();
+
+    // Ensure the endpoints were hit
+    not_found_mock.assert();
+    chunks_mock.assert_hits(3);
+    manifest_mock.assert();
+
+    assert_eq!(resp.format, CHUNK_MANIFEST_FORMAT);
+    assert_eq!(resp.size, 5);
+    assert_eq!(resp.chunks, 3);
+    assert_eq!(resp.sha256, hello_manifest().sha256);
+}
+
+// DELETE /config/base64:{key}/.chunks/{id}-{index}
+#[test]
+fn delete_chunks() {
+    let chunk0_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:ZGMvLmNodW5rcy9hYmMtMDAwMDAwMDA");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let chunk1_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:ZGMvLmNodW5rcy9hYmMtMDAwMDAwMDE");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let req = partial_build().encoded_key("ZGM").build().unwrap();
+    req.delete_chunks(&hello_manifest()).unwrap();
+
+    // Ensure the endpoints were hit
+    chunk0_mock.assert();
+    chunk1_mock.assert();
+}
+
+// GET /config/base64:{key}/.chunks/ and DELETE /config/base64:{key}/.chunks/{id}-{index}
+#[test]
+fn cleanup_chunks() {
+    let mut manifest = hello_manifest();
+    manifest.chunks = 1;
+    let _manifest_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Z2M");
+        then(t, json!(manifest_kv(&manifest)));
+    });
+    let list_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:Z2MvLmNodW5rcw/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                {"key": "Z2MvLmNodW5rcy9hYmMtMDAwMDAwMDA", "value": "aGVsbG8"},
+                {"key": "Z2MvLmNodW5rcy9vbGQtMDAwMDAwMDA", "value": "b2xk"}
+            ]}),
+        );
+    });
+    let delete_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:Z2MvLmNodW5rcy9vbGQtMDAwMDAwMDA");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let req = partial_build().encoded_key("Z2M").build().unwrap();
+    let resp = req.cleanup_chunks().unwrap();
+
+    // Ensure the endpoints were hit
+    list_mock.assert();
+    delete_mock.assert();
+
+    assert_eq!(resp, 1);
+}
+
+#[cfg(feature = "encryption")]
+mod encryption {
+    use seaplane::api::metadata::v1::{
+        is_sealed, DataKey, EncryptedMetadataRequest, EncryptionAlgorithm, Keyring,
+    };
+
+    use super::*;
+
+    fn keyring() -> Keyring {
+        Keyring::new(DataKey::new("k1", EncryptionAlgorithm::Aes256Gcm, [7; 32]).unwrap())
+    }
+
+    // GET /config/base64:{key}
+    #[test]
+    fn get_value_encrypted() {
+        let sealed = Value::from_unencoded(keyring().seal(b"s3cr3t").unwrap());
+        let mock = MOCK_SERVER.mock(|w, t| {
+            when(w, GET, "/v1/config/base64:c2VjcmV0");
+            then(t, json!({"key": "c2VjcmV0", "value": sealed}));
+        });
+
+        let req = partial_build().encoded_key("c2VjcmV0").build().unwrap();
+        let req = EncryptedMetadataRequest::new(req, keyring());
+        let resp = req.get_value().unwrap();
+
+        // Ensure the endpoint was hit
+        mock.assert();
+
+        assert_eq!(resp, b"s3cr3t");
+    }
+
+    // PUT /config/base64:{key}
+    #[test]
+    fn put_value_encrypted() {
+        let mock = MOCK_SERVER.mock(|w, t| {
+            when(w, PUT, "/v1/config/base64:cHV0LXNlY3JldA")
+                .header("content-type", "application/octet-stream")
+                .matches(|req| {
+                    // The body is the sealed value encoded as base64
+                    let body = String::from_utf8(req.body.clone().unwrap_or_default()).unwrap();
+                    is_sealed(&Value::from_encoded(body).decode())
+                });
+            then(t, json!({"status": 200, "title": "Ok"}));
+        });
+
+        let req = partial_build()
+            .encoded_key("cHV0LXNlY3JldA")
+            .build()
+            .unwrap();
+        let req = EncryptedMetadataRequest::new(req, keyring());
+        req.put_value("s3cr3t").unwrap();
+
+        // Ensure the endpoint was hit
+        mock.assert();
+    }
+}
+
+// GET /config/base64:{key}
+#[test]
+fn watch_key() {
+    let key = Key::from_encoded("d2F0Y2hlZA");
+    let mut watcher = Watcher::new(WatchTarget::Key(key.clone()));
+
+    // A key that does not exist yet is not an error
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:d2F0Y2hlZA");
+        t.status(404)
+            .header("content-type", "application/json")
+            .json_body(json!({"status": 404, "title": "Not Found", "detail": "key not found"}));
+    });
+    assert_eq!(watcher.poll(partial_build()), Ok(vec![]));
+    mock.assert();
+    mock.delete();
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:d2F0Y2hlZA");
+        then(t, json!({"key": "d2F0Y2hlZA", "value": "Zm9v"}));
+    });
+    assert_eq!(
+        watcher.poll(partial_build()),
+        Ok(vec![WatchEvent::Added { key: key.clone(), value: Value::from_encoded("Zm9v") }])
+    );
+    assert_eq!(watcher.poll(partial_build()), Ok(vec![]));
+    mock.assert_hits(2);
+    mock.delete();
+}
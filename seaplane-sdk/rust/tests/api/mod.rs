@@ -5,10 +5,16 @@
 mod compute_v1;
 #[cfg(all(feature = "compute_api_v2", feature = "unstable"))]
 mod compute_v2;
+#[cfg(feature = "metadata_api_v1")]
+mod config;
+#[cfg(feature = "metadata_api_v1")]
+mod flags;
 #[cfg(feature = "locks_api_v1")]
 mod locks_v1;
 #[cfg(feature = "metadata_api_v1")]
 mod metadata_v1;
+#[cfg(all(feature = "metadata_api_v1", feature = "locks_api_v1"))]
+mod queue;
 #[cfg(feature = "restrict_api_v1")]
 mod restrict_v1;
 mod token_v1;
@@ -0,0 +1,187 @@
+use httpmock::{prelude::*, Method, Then, When};
+use seaplane::{
+    api::{
+        locks::v1::LocksRequestBuilder,
+        metadata::v1::{Key, MetadataRequestBuilder, Value},
+        shared::v1::Directory,
+    },
+    queue::{Queue, QueuedTask},
+};
+use serde_json::json;
+
+use super::MOCK_SERVER;
+
+fn when(when: When, m: Method, p: &str) -> When {
+    when.method(m)
+        .path(p)
+        .header("authorization", "Bearer abc123")
+        .header("accept", "*/*")
+        .header("host", format!("{}:{}", MOCK_SERVER.host(), MOCK_SERVER.port()))
+}
+
+fn then(then: Then, resp_body: serde_json::Value) -> Then {
+    then.status(200)
+        .header("content-type", "application/json")
+        .json_body(resp_body)
+}
+
+fn queue(dir: &str) -> Queue {
+    Queue::new(
+        Directory::from_unencoded(dir),
+        || {
+            Ok(MetadataRequestBuilder::new()
+                .token("abc123")
+                .base_url(MOCK_SERVER.base_url()))
+        },
+        || {
+            Ok(LocksRequestBuilder::new()
+                .token("abc123")
+                .base_url(MOCK_SERVER.base_url()))
+        },
+    )
+    .client_id("worker-1")
+}
+
+// PUT /config/base64:{dir}/{id}
+#[test]
+fn push() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        // "jobs/" followed by the timestamp
+        w.method(PUT)
+            .path_contains("/v1/config/base64:am9icy8")
+            .body("b25l");
+        t.status(200);
+    });
+
+    let key = queue("jobs").push("one").unwrap();
+    mock.assert();
+    assert!(String::from_utf8(key.decode())
+        .unwrap()
+        .starts_with("jobs/"));
+}
+
+// GET /config/base64:{dir}/
+// POST /locks/base64:{key}?ttl={ttl}&client-id={client_id}
+// GET /config/base64:{key}
+// DELETE /config/base64:{key}
+// DELETE /locks/base64:{key}?id={id}
+#[test]
+fn pop_and_complete() {
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:cG9w/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                {"key": "cG9wL2E", "value": "b25l"},
+                {"key": "cG9wL2I", "value": "dHdv"}
+            ]}),
+        );
+    });
+    // pop/a is already claimed by someone else
+    let mut claimed_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:cG9wL2E")
+            .query_param("ttl", "60")
+            .query_param("client-id", "worker-1");
+        t.status(409)
+            .header("content-type", "application/json")
+            .json_body(json!({"status": 409, "title": "Conflict", "detail": "lock is held"}));
+    });
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:cG9wL2I")
+            .query_param("ttl", "60")
+            .query_param("client-id", "worker-1");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 1}));
+    });
+    let mut get_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:cG9wL2I");
+        then(t, json!({"key": "cG9wL2I", "value": "dHdv"}));
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/config/base64:cG9wL2I");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/locks/base64:cG9wL2I").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let queue = queue("pop");
+    let task = queue.pop(60).unwrap().unwrap();
+    list_mock.assert();
+    claimed_mock.assert();
+    acquire_mock.assert();
+    get_mock.assert();
+    assert_eq!(task.key(), &Key::from_unencoded("pop/b"));
+    assert_eq!(task.value(), &Value::from_unencoded("two"));
+
+    queue.complete(task).unwrap();
+    delete_mock.assert();
+    release_mock.assert();
+
+    list_mock.delete();
+    claimed_mock.delete();
+    acquire_mock.delete();
+    get_mock.delete();
+    delete_mock.delete();
+    release_mock.delete();
+}
+
+// GET /config/base64:{dir}/
+#[test]
+fn pop_empty() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:ZW1wdHk/");
+        then(t, json!({"next_key": None::<String>, "kvs": []}));
+    });
+
+    assert!(queue("empty").pop(60).unwrap().is_none());
+    mock.assert();
+    mock.delete();
+}
+
+// GET /config/base64:{dir}/
+// GET /locks/base64:{dir}/
+#[test]
+fn list() {
+    let mut kvs_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/config/base64:bHM/");
+        then(
+            t,
+            json!({"next_key": None::<String>, "kvs": [
+                {"key": "bHMvYQ", "value": "b25l"},
+                {"key": "bHMvYg", "value": "dHdv"}
+            ]}),
+        );
+    });
+    let mut locks_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/locks/base64:bHM/");
+        then(
+            t,
+            json!({"next": None::<String>, "locks": [
+                {"name": "bHMvYg", "id": "D4lbVpdBE_U", "info": {"ttl": 30, "client-id": "worker-2", "ip": ""}}
+            ]}),
+        );
+    });
+
+    let tasks = queue("ls").list().unwrap();
+    kvs_mock.assert();
+    locks_mock.assert();
+    assert_eq!(
+        tasks,
+        vec![
+            QueuedTask {
+                key: Key::from_unencoded("ls/a"),
+                value: Value::from_unencoded("one"),
+                claimed_by: None
+            },
+            QueuedTask {
+                key: Key::from_unencoded("ls/b"),
+                value: Value::from_unencoded("two"),
+                claimed_by: Some("worker-2".into())
+            },
+        ]
+    );
+
+    kvs_mock.delete();
+    locks_mock.delete();
+}
@@ -1,7 +1,12 @@
+use std::time::Duration;
+
 use httpmock::{prelude::*, Method, Then, When};
-use seaplane::api::{
-    restrict::v1::{RestrictRequestBuilder, RestrictionDetails},
-    shared::v1::RangeQueryContext,
+use seaplane::{
+    api::{
+        restrict::v1::{RestrictRequestBuilder, RestrictionDetails, RestrictionState},
+        shared::v1::RangeQueryContext,
+    },
+    error::SeaplaneError,
 };
 use serde_json::json;
 
@@ -59,6 +64,66 @@ fn get_restriction() {
     assert_eq!(resp, serde_json::from_value(resp_json).unwrap());
 }
 
+fn restriction_json(dir: &str, state: &str) -> serde_json::Value {
+    json!({
+        "api": "Config",
+        "directory": dir,
+        "details": {
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        },
+        "state": state
+    })
+}
+
+// GET /restrict/{api}/base64:{key}/
+#[test]
+fn wait_until_enforced() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/restrict/config/base64:ZW5mb3JjZWQ/");
+        then(t, restriction_json("ZW5mb3JjZWQ", "Enforced"));
+    });
+
+    let req = partial_build()
+        .single_restriction("config", "ZW5mb3JjZWQ")
+        .build()
+        .unwrap();
+    let resp = req
+        .wait_until_enforced(Duration::from_secs(5), Duration::from_millis(10))
+        .unwrap();
+
+    mock.assert_hits(1);
+    assert_eq!(resp.state, RestrictionState::Enforced);
+    mock.delete();
+}
+
+// GET /restrict/{api}/base64:{key}/
+#[test]
+fn wait_until_enforced_timeout() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/restrict/config/base64:cGVuZGluZw/");
+        then(t, restriction_json("cGVuZGluZw", "Pending"));
+    });
+
+    let req = partial_build()
+        .single_restriction("config", "cGVuZGluZw")
+        .build()
+        .unwrap();
+    let mut polls = 0;
+    let resp =
+        req.wait_until_enforced_with(Duration::from_millis(50), Duration::from_millis(10), |r| {
+            assert_eq!(r.state, RestrictionState::Pending);
+            polls += 1;
+        });
+
+    assert_eq!(resp, Err(SeaplaneError::Timeout(Duration::from_millis(50))));
+    assert!(polls > 1);
+    assert_eq!(mock.hits(), polls);
+    mock.delete();
+}
+
 // PUT /restrict/{api}/base64:{key}/
 #[test]
 fn set_restriction() {